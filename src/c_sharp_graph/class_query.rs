@@ -1,13 +1,18 @@
-use std::{collections::HashMap, vec};
+use std::collections::HashMap;
 
-use anyhow::{Error, Ok};
+use anyhow::{anyhow, Error, Ok};
 use stack_graphs::{
     arena::Handle,
-    graph::{Node, StackGraph},
+    graph::{File, Node, StackGraph},
+    partial::PartialPaths,
+    stitching::{Database, ForwardPartialPathStitcher, StitcherConfig},
+    NoCancellation,
 };
-use tracing::{debug, trace};
+use tracing::debug;
 
-use crate::c_sharp_graph::query::{get_fqdn, Fqdn, GetMatcher, Search, SymbolMatcher, SyntaxType};
+use crate::c_sharp_graph::query::{
+    fqdn_dotted_name, get_fqdn, Fqdn, GetMatcher, Search, SymbolMatcher, SyntaxType,
+};
 
 pub(crate) struct ClassSymbolsGetter {}
 
@@ -29,6 +34,17 @@ impl GetMatcher for ClassSymbolsGetter {
 
 pub(crate) struct ClassSymbols {
     classes: HashMap<Fqdn, Handle<Node>>,
+    /// Declared type of every local/field variable found alongside
+    /// `classes`, keyed by the variable's own symbol text. Lets
+    /// `symbol_in_namespace` resolve a `<var>.<method_name>` receiver to the
+    /// class `<var>` was declared as, instead of only matching when
+    /// `<thing>` is itself a class name.
+    vars: HashMap<String, Fqdn>,
+    /// Kept so [`SymbolMatcher::match_fqdn_captures`] can re-match a found
+    /// `Fqdn` against the original pattern and recover any `$name`
+    /// placeholder bindings -- `classes`'s keys alone only tell us *that* a
+    /// class matched, not what a placeholder in the search captured.
+    search: Search,
 }
 
 // Create exposed methods for NamesapceSymbols
@@ -39,15 +55,49 @@ impl ClassSymbols {
         search: &Search,
     ) -> anyhow::Result<ClassSymbols, Error> {
         let mut classes: HashMap<Fqdn, Handle<Node>> = HashMap::new();
+        let mut var_candidates: Vec<(String, String)> = vec![];
+
+        // `find_minimal_partial_path_set_in_file` is the local, per-file
+        // analysis step -- it only ever discovers paths made up of nodes
+        // `file` itself owns. Since `chunk5-3` interns shared namespace/
+        // class declarations into a single node owned by whichever file
+        // created it first, a plain `using Foo.Bar;`-style lookup rooted in
+        // one file routinely needs to cross into a `ClassDef` owned by a
+        // *different* file than the one the search root lives in --
+        // collecting local paths only for the files `nodes` happen to be
+        // owned by would miss that. Collecting every file in the graph's
+        // local paths into one shared `Database` instead, then stitching
+        // across all of it in a single pass, is what actually resolves
+        // that cross-file case rather than silently finding nothing.
+        let mut partials = PartialPaths::new();
+        let mut database = Database::new();
+        for file in graph.iter_files() {
+            Self::collect_file_partial_paths(graph, file, &mut partials, &mut database)?;
+        }
+        Self::stitch_classes(graph, &mut partials, &mut database, nodes.clone(), search, &mut classes)?;
 
         for node_handle in nodes {
-            //Get all the edges
-            Self::traverse_node(graph, node_handle, search, &mut classes)
+            Self::collect_var_types(graph, node_handle, &mut var_candidates);
+        }
+
+        let mut vars: HashMap<String, Fqdn> = HashMap::new();
+        for (var_symbol, type_symbol) in var_candidates {
+            if let Some(fqdn) = classes
+                .keys()
+                .find(|fqdn| fqdn.class.as_deref() == Some(type_symbol.as_str()))
+            {
+                vars.insert(var_symbol, fqdn.clone());
+            }
         }
 
         debug!("class nodes found: {:?}", classes);
+        debug!("variable declared types found: {:?}", vars);
 
-        Ok(ClassSymbols { classes })
+        Ok(ClassSymbols {
+            classes,
+            vars,
+            search: search.clone(),
+        })
     }
 }
 
@@ -60,68 +110,231 @@ impl SymbolMatcher for ClassSymbols {
             .keys()
             .any(|f| f.namespace == fqdn.namespace && f.class == fqdn.class)
     }
+    fn match_fqdn_captures(&self, fqdn: &Fqdn) -> Option<HashMap<String, String>> {
+        self.search.match_fqdn_pattern(&fqdn_dotted_name(fqdn))
+    }
 }
 
 // Private methods for NamespaceSymbols
 impl ClassSymbols {
-    fn traverse_node(
+    /// Runs the local, per-file partial-path analysis for `file` and adds
+    /// every path it finds into the shared `database`, so
+    /// [`ClassSymbols::stitch_classes`] can later stitch across it together
+    /// with every other file's local paths. This step alone only ever
+    /// produces paths made up of nodes `file` itself owns -- it is not the
+    /// cross-file stitcher.
+    fn collect_file_partial_paths(
+        graph: &StackGraph,
+        file: Handle<File>,
+        partials: &mut PartialPaths,
+        database: &mut Database,
+    ) -> Result<(), Error> {
+        ForwardPartialPathStitcher::find_minimal_partial_path_set_in_file(
+            graph,
+            partials,
+            file,
+            StitcherConfig::default().with_collect_stats(true),
+            &NoCancellation,
+            |graph, partials, path| database.add_partial_path(graph, partials, path.clone()),
+        )
+        .map_err(|e| anyhow!("failed to collect local partial paths for class search: {:?}", e))
+    }
+
+    /// Resolves every `ClassDef` definition reachable from `roots` via
+    /// stack-graph forward partial-path stitching over `database`, rather
+    /// than a hand-rolled outgoing-edge walk that treated every edge the
+    /// same regardless of scope. Stitching across `database` (populated by
+    /// [`ClassSymbols::collect_file_partial_paths`] for every file in the
+    /// graph) resolves complete paths by matching symbol stacks across
+    /// scope nodes *and* file boundaries, so a class reached through an
+    /// import/`using` directive that crosses into a class declared in
+    /// another file, and one shadowed by a closer declaration, no longer
+    /// get the same, inconsistent treatment the old `precedence == 10`
+    /// special case only partially accounted for.
+    fn stitch_classes(
         graph: &StackGraph,
-        node: Handle<Node>,
+        partials: &mut PartialPaths,
+        database: &mut Database,
+        roots: Vec<Handle<Node>>,
         search: &Search,
         classes: &mut HashMap<Fqdn, Handle<Node>>,
-    ) {
-        let mut child_edges: Vec<Handle<Node>> = vec![];
-        for edge in graph.outgoing_edges(node) {
-            debug!("edge precedence during search: {}", edge.precedence);
-            if edge.precedence == 10 {
+    ) -> Result<(), Error> {
+        let paths = ForwardPartialPathStitcher::find_all_complete_partial_paths(
+            graph,
+            partials,
+            database,
+            roots,
+            StitcherConfig::default().with_collect_stats(true),
+            &NoCancellation,
+        )
+        .map_err(|e| anyhow!("failed to stitch partial paths for class search: {:?}", e))?;
+
+        // When more than one complete path reaches the same `ClassDef`
+        // (e.g. one path going through an import, another a direct,
+        // in-scope reference), keep the one whose final edge has the
+        // highest precedence -- the same signal an un-stitched edge
+        // already carried -- instead of just the last path seen.
+        let mut best_precedence: HashMap<Handle<Node>, i32> = HashMap::new();
+
+        for path in &paths {
+            let end_node = &graph[path.end_node];
+            let Some(symbol) = end_node.symbol() else {
+                continue;
+            };
+            let symbol_text = &graph[symbol];
+            if !search.match_symbol(symbol_text) {
                 continue;
             }
-            child_edges.push(edge.sink);
-            let child_node = &graph[edge.sink];
-            let symbol = match child_node.symbol() {
-                None => continue,
-                Some(symbol) => &graph[symbol],
+            debug!("got stitched definition: {:?}, symbol: {}", path.end_node, symbol_text);
+
+            let Some(source_info) = graph.source_info(path.end_node) else {
+                continue;
             };
-            if !search.match_symbol(symbol) {
+            let Some(syntax_type) = source_info.syntax_type.into_option() else {
+                continue;
+            };
+            if !matches!(SyntaxType::get(&graph[syntax_type]), SyntaxType::ClassDef) {
+                continue;
+            }
+
+            let precedence = path
+                .edges
+                .iter_unordered(partials)
+                .map(|edge| edge.precedence)
+                .max()
+                .unwrap_or(0);
+            if best_precedence
+                .get(&path.end_node)
+                .is_some_and(|&best| best >= precedence)
+            {
+                continue;
+            }
+            best_precedence.insert(path.end_node, precedence);
+
+            let fqdn_name =
+                get_fqdn(path.end_node, graph).expect("We should always get a FQDN for methods");
+            classes.insert(fqdn_name, path.end_node);
+        }
+
+        Ok(())
+    }
+
+    /// Walks every edge reachable from `node` except the precedence-10
+    /// FQDN edges (unfiltered by `search` --
+    /// a local variable's name has nothing to do with the class/method
+    /// pattern being searched for) looking for
+    /// `local_var` nodes, and records each one's declared-type symbol text
+    /// as a candidate in `candidates`.
+    fn collect_var_types(graph: &StackGraph, node: Handle<Node>, candidates: &mut Vec<(String, String)>) {
+        for edge in graph.outgoing_edges(node) {
+            if edge.precedence == 10 {
                 continue;
             }
-            trace!("got node: {:?}, symbol: {} matching", edge.sink, symbol,);
-            match graph.source_info(edge.sink) {
-                None => continue,
-                Some(source_info) => match source_info.syntax_type.into_option() {
-                    None => continue,
-                    Some(syntax_type) => {
-                        if let SyntaxType::ClassDef = SyntaxType::get(&graph[syntax_type]) {
-                            let fqdn_name = get_fqdn(edge.sink, graph)
-                                .expect("We should always get a FQDN for methods");
-                            classes.insert(fqdn_name, node);
-                        } else {
-                            trace!(
-                                "got node: {:?}, symbol: {} not matching syntax_type: {}",
-                                edge.sink,
-                                symbol,
-                                &graph[syntax_type]
-                            );
+            if let Some(var_symbol) = graph[edge.sink].symbol() {
+                if let Some(syntax_type) = graph
+                    .source_info(edge.sink)
+                    .and_then(|info| info.syntax_type.into_option())
+                {
+                    if let SyntaxType::LocalVar = SyntaxType::get(&graph[syntax_type]) {
+                        if let Some(type_symbol) = Self::declared_type_symbol(graph, edge.sink) {
+                            candidates.push((graph[var_symbol].to_string(), type_symbol));
                         }
                     }
-                },
+                }
             }
+            Self::collect_var_types(graph, edge.sink, candidates);
         }
-        for child_edge in child_edges {
-            Self::traverse_node(graph, child_edge, search, classes);
-        }
+    }
+
+    /// Follows `var_node`'s outgoing type-reference edge (the same hop
+    /// [`crate::c_sharp_graph::query::Querier::find_member_of_declared_type`]
+    /// uses) to the symbol text of the type it was declared as.
+    fn declared_type_symbol(graph: &StackGraph, var_node: Handle<Node>) -> Option<String> {
+        let type_ref_node = graph
+            .outgoing_edges(var_node)
+            .find_map(|e| {
+                let edge_node = &graph[e.sink];
+                if edge_node.is_reference() {
+                    Some(edge_node)
+                } else {
+                    None
+                }
+            })?;
+        let symbol = type_ref_node.symbol()?;
+        Some(graph[symbol].to_string())
     }
 
     // Symbol here must be of <thing>.<method_name>.
-    // <thing> may be a class or a variable.
-    // if a variable, we may have to enhance this method
-    // to get the actual "class" of the variable.
-    // TODO: Consider scoped things for this(??)
-    // TODO: Consider a edge from the var to the class symbol
+    // <thing> may be a class or a variable. If a variable, `vars` (built by
+    // `collect_var_types` from the reference edge each local/field
+    // declaration carries to its type) resolves it to the class it was
+    // declared as before falling back to the direct class-name comparison.
     fn symbol_in_namespace(&self, symbol: String) -> bool {
+        if let Some(fqdn) = self.vars.get(&symbol) {
+            return self.match_fqdn(fqdn);
+        }
         self.classes.keys().any(|fqdn| {
             let class = fqdn.class.clone().unwrap_or("".to_string());
             class == symbol
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a graph spanning *two* files: `using.cs` declares/references
+    /// the namespace `System.Configuration` and holds the search root, while
+    /// `configuration_manager.cs` defines the `ConfigurationManager` class
+    /// actually being searched for. Since `chunk5-3` interns a shared
+    /// namespace/class node into whichever file's analyzer created it
+    /// first, this is the realistic shape a cross-file `using` lookup takes
+    /// -- the node reached from `using.cs`'s root is not itself owned by
+    /// `using.cs`.
+    fn build_mock_cross_file_graph() -> (StackGraph, Vec<Handle<Node>>) {
+        let mut graph = StackGraph::new();
+        let using_file = graph.add_file("using.cs").unwrap();
+        let class_file = graph.add_file("configuration_manager.cs").unwrap();
+
+        let root_id = graph.new_node_id(using_file);
+        let root_symbol = graph.add_symbol("root");
+        let root = graph.add_pop_symbol_node(root_id, root_symbol, true).unwrap();
+
+        let ns_id = graph.new_node_id(using_file);
+        let ns_symbol = graph.add_symbol("System.Configuration");
+        let ns_node = graph.add_pop_symbol_node(ns_id, ns_symbol, true).unwrap();
+        let ns_syntax = graph.add_string("namespace_declaration");
+        graph.source_info_mut(ns_node).syntax_type = ns_syntax.into();
+
+        let class_id = graph.new_node_id(class_file);
+        let class_symbol = graph.add_symbol("ConfigurationManager");
+        let class_node = graph.add_pop_symbol_node(class_id, class_symbol, true).unwrap();
+        let class_syntax = graph.add_string("class_def");
+        graph.source_info_mut(class_node).syntax_type = class_syntax.into();
+
+        graph.add_edge(root, ns_node, 0);
+        graph.add_edge(ns_node, class_node, 0);
+        graph.add_edge(class_node, ns_node, 10); // FQDN edge
+
+        (graph, vec![root])
+    }
+
+    #[test]
+    fn test_cross_file_class_lookup_resolves_class_in_a_different_file() {
+        let (graph, roots) = build_mock_cross_file_graph();
+        let search = Search::create_search("*".to_string()).unwrap();
+
+        let class_symbols = ClassSymbols::new(&graph, roots, &search)
+            .expect("cross-file class stitching should succeed");
+
+        assert!(class_symbols.symbol_in_namespace("ConfigurationManager".to_string()));
+        assert!(class_symbols.match_fqdn(&Fqdn {
+            namespace: Some("System.Configuration".to_string()),
+            class: Some("ConfigurationManager".to_string()),
+            method: None,
+            field: None,
+            parameters: Vec::new(),
+        }));
+    }
+}
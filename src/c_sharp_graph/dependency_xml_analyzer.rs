@@ -1,7 +1,9 @@
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::iter::DoubleEndedIterator;
 use std::iter::Extend;
 use std::path::Path;
+use std::sync::Mutex;
 
 use quick_xml::events::Event;
 use quick_xml::name::QName;
@@ -13,6 +15,8 @@ use stack_graphs::graph::StackGraph;
 use tracing::debug;
 use tracing::error;
 use tracing::info;
+use tracing::trace;
+use tracing::warn;
 use tree_sitter_stack_graphs::BuildError;
 use tree_sitter_stack_graphs::CancellationFlag;
 use tree_sitter_stack_graphs::FileAnalyzer;
@@ -21,12 +25,50 @@ use crate::c_sharp_graph::query::SyntaxType;
 
 const MEMBER_NAME: QName = QName(b"member");
 
-pub struct DepXMLFileAnalyzer {}
+/// 1-based line number of the byte `offset` within `source`, for pairing
+/// with `reader.buffer_position()` in parse error/skip logging.
+fn line_for_offset(source: &str, offset: usize) -> usize {
+    source[..offset.min(source.len())].matches('\n').count() + 1
+}
+
+/// The `_globals` key carrying a project/assembly-root scope symbol, so
+/// `Widget` defined in two different dependency assemblies doesn't collide
+/// in `DepXMLFileAnalyzer::global_index`. Missing or empty means unscoped,
+/// matching the pre-existing single-assembly behavior.
+const SCOPE_GLOBAL: &str = "SCOPE";
+
+pub struct DepXMLFileAnalyzer {
+    /// Fully-qualified-name -> canonical definition node, shared across
+    /// every file this analyzer instance processes (`build_stack_graph_into`
+    /// is called once per file in the corpus, reusing the same instance).
+    /// A member in file A that references a type defined in file B reuses
+    /// B's node here instead of getting a fresh, disconnected pop node of
+    /// its own.
+    global_index: Mutex<HashMap<String, Handle<Node>>>,
+}
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+impl DepXMLFileAnalyzer {
+    pub fn new() -> Self {
+        DepXMLFileAnalyzer {
+            global_index: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl Default for DepXMLFileAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct NodeInfo {
     symbol: String,
     syntax_type: SyntaxType,
+    /// Number of generic parameters decoded from a `` `N `` (generic type,
+    /// e.g. `` List`1 ``) or ``` ``N ``` (generic method, e.g. `` Select``2 ``)
+    /// arity marker, or `None` if `symbol` carried no such marker.
+    arity: Option<u32>,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -36,6 +78,137 @@ pub struct EdgeInfo {
     precedence: i32,
 }
 
+/// Why `DepXMLFileAnalyzer::try_handle_member` couldn't decompose a member
+/// ID, naming the specific missing/malformed piece rather than a generic
+/// failure -- so callers driving rule evaluation have something actionable
+/// to log or surface.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MemberParseError {
+    /// `member_type` (the `N`/`T`/`F`/`P`/`E`/`M` prefix) isn't recognized.
+    UnknownMemberType(String),
+    /// `member_type` was the empty string.
+    EmptyMemberType,
+    /// The member identifier (the part after the `:`) was empty.
+    EmptyIdentifier,
+    /// `kind`'s identifier had only `found` dot-separated parts but needs
+    /// at least `needed` (e.g. a field needs both a declaring type and a
+    /// field name).
+    InsufficientParts {
+        kind: String,
+        found: usize,
+        needed: usize,
+    },
+    /// A `#ctor` method ID had no declaring type before it.
+    ConstructorWithoutClass,
+    /// A method ID's parameter list (inside the parens) couldn't be parsed.
+    MalformedParameters(String),
+}
+
+impl std::fmt::Display for MemberParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MemberParseError::UnknownMemberType(kind) => write!(
+                f,
+                "unknown member type {kind:?}; expected one of N, T, F, P, E, M"
+            ),
+            MemberParseError::EmptyMemberType => write!(f, "member type is empty"),
+            MemberParseError::EmptyIdentifier => write!(f, "member identifier is empty"),
+            MemberParseError::InsufficientParts {
+                kind,
+                found,
+                needed,
+            } => write!(
+                f,
+                "{kind} identifier has only {found} dot-separated part(s), need at least {needed}"
+            ),
+            MemberParseError::ConstructorWithoutClass => {
+                write!(f, "#ctor has no declaring type before it")
+            }
+            MemberParseError::MalformedParameters(raw) => {
+                write!(f, "malformed parameter list in member id {raw:?}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for MemberParseError {}
+
+/// Render an intermediate node/edge graph (as produced by `handle_member`)
+/// as a Graphviz `digraph`, for pasting into a viewer when debugging
+/// precedence or missing-namespace cases. Each node is labelled with its
+/// `SyntaxType` and `symbol`; each edge is drawn source -> sink labelled
+/// with its `precedence`, so the precedence-10 FQDN edges read visually
+/// distinct from the precedence-0 structural ones.
+pub fn to_dot(nodes: &[NodeInfo], edges: &[EdgeInfo]) -> String {
+    let mut dot = String::from("digraph handle_member {\n");
+    for (i, node) in nodes.iter().enumerate() {
+        dot.push_str(&format!(
+            "  n{i} [label=\"{:?}\\n{}\"];\n",
+            node.syntax_type,
+            node.symbol.replace('"', "\\\"")
+        ));
+    }
+    for edge in edges {
+        let source_idx = nodes.iter().position(|n| n == &edge.source);
+        let sink_idx = nodes.iter().position(|n| n == &edge.sink);
+        if let (Some(source_idx), Some(sink_idx)) = (source_idx, sink_idx) {
+            dot.push_str(&format!(
+                "  n{source_idx} -> n{sink_idx} [label=\"{}\"];\n",
+                edge.precedence
+            ));
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// Reconstruct `node`'s fully-qualified name by walking its precedence-0
+/// containment edge (container -> node) in `edges`, recursively prefixing
+/// with the container's own qualified name. A `NamespaceDeclaration`'s
+/// `symbol` is already the full dotted namespace, so it's the base case;
+/// a node with no containment edge (malformed input) falls back to its own
+/// bare symbol.
+fn qualified_key(node: &NodeInfo, edges: &[EdgeInfo]) -> String {
+    qualified_key_tracking_cycles(node, edges, &mut HashSet::new())
+}
+
+/// Same walk as [`qualified_key`], with `in_progress` recording every node
+/// still on the current containment chain. A self-nested-type doc-XML
+/// member id (e.g. `T:NS.X+X`) parses into a structural `X -> X`
+/// precedence-0 edge, which would otherwise send this recursion into itself
+/// forever -- this runs ahead of the stack graph even existing, so
+/// `stable_post_order`'s cycle check never gets a chance to catch it.
+/// A node whose container is still `in_progress` stops there with its own
+/// bare symbol instead of recursing into the container again, mirroring the
+/// gray-node check `stable_post_order` does over the built graph.
+fn qualified_key_tracking_cycles(
+    node: &NodeInfo,
+    edges: &[EdgeInfo],
+    in_progress: &mut HashSet<NodeInfo>,
+) -> String {
+    if node.syntax_type == SyntaxType::NamespaceDeclaration {
+        return node.symbol.clone();
+    }
+    in_progress.insert(node.clone());
+    let key = match edges.iter().find(|e| e.precedence == 0 && e.sink == *node) {
+        Some(containing_edge) if in_progress.contains(&containing_edge.source) => {
+            warn!(
+                symbol = %node.symbol,
+                "containment cycle detected while computing qualified key, using bare symbol"
+            );
+            node.symbol.clone()
+        }
+        Some(containing_edge) => format!(
+            "{}.{}",
+            qualified_key_tracking_cycles(&containing_edge.source, edges, in_progress),
+            node.symbol
+        ),
+        None => node.symbol.clone(),
+    };
+    in_progress.remove(node);
+    key
+}
+
 impl FileAnalyzer for DepXMLFileAnalyzer {
     #[allow(clippy::needless_lifetimes)]
     fn build_stack_graph_into<'a>(
@@ -44,21 +217,37 @@ impl FileAnalyzer for DepXMLFileAnalyzer {
         file: Handle<File>,
         path: &Path,
         source: &str,
-        _all_paths: &mut dyn Iterator<Item = &'a Path>,
-        _globals: &HashMap<String, String>,
+        all_paths: &mut dyn Iterator<Item = &'a Path>,
+        globals: &HashMap<String, String>,
         _cancellation_flag: &dyn CancellationFlag,
     ) -> Result<(), tree_sitter_stack_graphs::BuildError> {
-        let mut reader = Reader::from_str(source);
+        debug!(
+            file=?path,
+            "{} files participating in this corpus's cross-file resolution",
+            all_paths.count()
+        );
+        let scope = globals.get(SCOPE_GLOBAL).cloned().unwrap_or_default();
+        // Buffered streaming reader: `buf` is cleared and reused on every
+        // event instead of the whole document being re-sliced, so large BCL
+        // doc XML files don't hold their contents twice over.
+        let mut reader = Reader::from_reader(source.as_bytes());
+        let mut buf: Vec<u8> = Vec::new();
 
         reader.config_mut().trim_text(true);
 
         let mut inter_node_info: Vec<NodeInfo> = vec![];
         let mut inter_edge_info: Vec<EdgeInfo> = vec![];
         loop {
-            match reader.read_event() {
+            buf.clear();
+            match reader.read_event_into(&mut buf) {
                 Err(e) => {
-                    error!(file=?path, "got errror {}", e);
-                    return Err(BuildError::ParseError);
+                    let offset = reader.buffer_position();
+                    let line = line_for_offset(source, offset);
+                    error!(file=?path, offset, line, "got errror {}", e);
+                    return Err(BuildError::UnknownSymbolType(format!(
+                        "XML parse error in {} at byte offset {offset} (line {line}): {e}",
+                        path.display()
+                    )));
                 }
                 Ok(Event::Eof) => {
                     break;
@@ -66,18 +255,36 @@ impl FileAnalyzer for DepXMLFileAnalyzer {
                 Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
                     if e.name() == MEMBER_NAME {
                         // Look specifically for the "name" attribute for deterministic behavior
-                        let member_name = e.attributes().find(|attr| match attr {
-                            Ok(a) => a.key == QName(b"name"),
-                            Err(_) => false,
+                        let member_name = e.attributes().find_map(|attr| match attr {
+                            Ok(a) if a.key == QName(b"name") => Some(a),
+                            Ok(_) => None,
+                            Err(err) => {
+                                let offset = reader.buffer_position();
+                                warn!(
+                                    file=?path,
+                                    offset,
+                                    line = line_for_offset(source, offset),
+                                    "malformed attribute on <member>, skipping element: {}",
+                                    err
+                                );
+                                None
+                            }
                         });
-                        if member_name.is_none() {
-                            continue;
-                        }
-                        let member_name = member_name.unwrap().unwrap();
+                        let member_name = match member_name {
+                            Some(a) => a,
+                            None => continue,
+                        };
                         let member_name = String::from_utf8_lossy(&member_name.value).to_string();
                         let parts: Vec<&str> = member_name.split(":").collect();
                         if parts.len() != 2 {
-                            debug!(file=?path, "unable to get correct parts: {}", &member_name);
+                            let offset = reader.buffer_position();
+                            debug!(
+                                file=?path,
+                                offset,
+                                line = line_for_offset(source, offset),
+                                "unable to get correct parts: {}",
+                                &member_name
+                            );
                             continue;
                         }
                         let (nodes, mut edges) =
@@ -116,86 +323,51 @@ impl FileAnalyzer for DepXMLFileAnalyzer {
         let mut map_class_nodes: HashMap<String, Handle<Node>> = HashMap::new();
         let mut map_method_nodes: HashMap<String, Handle<Node>> = HashMap::new();
         let mut map_field_nodes: HashMap<String, Handle<Node>> = HashMap::new();
+        let mut map_event_nodes: HashMap<String, Handle<Node>> = HashMap::new();
+        let mut global_index = self.global_index.lock().unwrap();
 
         let mut node_tracking_number = 0;
-        for node in inter_node_info {
-            let id: Handle<Node> = match node.syntax_type {
-                SyntaxType::FieldName => {
-                    let node_id = map_field_nodes.get(&node.symbol);
-                    if node_id.is_none() {
-                        let id = stack_graph.new_node_id(file);
-                        let symbol = stack_graph.add_symbol(&node.symbol);
-                        let node_handle = stack_graph.add_pop_symbol_node(id, symbol, true);
-                        if node_handle.is_none() {
-                            continue;
-                        }
-                        let node_handle = node_handle.unwrap();
-                        map_field_nodes.insert(node.symbol.clone(), node_handle);
-                        node_handle
-                    } else {
-                        continue;
-                    }
-                }
-                SyntaxType::ClassDef => {
-                    let node_id = map_class_nodes.get(&node.symbol);
-                    if node_id.is_none() {
-                        let id = stack_graph.new_node_id(file);
-                        let symbol = stack_graph.add_symbol(&node.symbol);
-                        let node_handle = stack_graph.add_pop_symbol_node(id, symbol, true);
-                        if node_handle.is_none() {
-                            continue;
-                        }
-                        let node_handle = node_handle.unwrap();
-                        map_class_nodes.insert(node.symbol.clone(), node_handle);
-                        node_handle
-                    } else {
-                        continue;
-                    }
-                }
-                SyntaxType::MethodName => {
-                    let node_id = map_method_nodes.get(&node.symbol);
-                    if node_id.is_none() {
-                        let id = stack_graph.new_node_id(file);
-                        let symbol = stack_graph.add_symbol(&node.symbol);
-                        let node_handle = stack_graph.add_pop_symbol_node(id, symbol, true);
-                        if node_handle.is_none() {
-                            continue;
-                        }
-                        let node_handle = node_handle.unwrap();
-                        map_method_nodes.insert(node.symbol.clone(), node_handle);
-                        node_handle
-                    } else {
-                        continue;
-                    }
-                }
-                SyntaxType::NamespaceDeclaration => {
-                    let node_id = map_namespace_nodes.get(&node.symbol);
-                    if node_id.is_none() {
-                        let id = stack_graph.new_node_id(file);
-                        let symbol = stack_graph.add_symbol(&node.symbol);
-                        let node_handle = stack_graph.add_pop_symbol_node(id, symbol, true);
-                        if node_handle.is_none() {
-                            continue;
-                        }
-                        let node_handle = node_handle.unwrap();
-                        map_namespace_nodes.insert(node.symbol.clone(), node_handle);
-
-                        stack_graph.add_edge(comp_unit_node_handle, node_handle, 0);
-                        node_handle
-                    } else {
-                        continue;
-                    }
-                }
+        for node in &inter_node_info {
+            let global_key = format!("{}::{}", scope, qualified_key(node, &inter_edge_info));
+            let (local_map, is_namespace) = match node.syntax_type {
+                SyntaxType::FieldName => (&mut map_field_nodes, false),
+                SyntaxType::EventName => (&mut map_event_nodes, false),
+                SyntaxType::ClassDef => (&mut map_class_nodes, false),
+                SyntaxType::MethodName => (&mut map_method_nodes, false),
+                SyntaxType::NamespaceDeclaration => (&mut map_namespace_nodes, true),
                 _ => {
                     error!(file = ?path, "unable to get node syntax type");
                     return Err(BuildError::ParseError);
                 }
             };
-            let syntax_type = stack_graph.add_string(&node.syntax_type.to_string());
-            let source_info = stack_graph.source_info_mut(id);
-            source_info.syntax_type = syntax_type.into();
-            node_tracking_number += 1
+            if local_map.contains_key(&node.symbol) {
+                continue;
+            }
+            let (node_handle, is_new) = match global_index.get(&global_key) {
+                Some(&handle) => (handle, false),
+                None => {
+                    let id = stack_graph.new_node_id(file);
+                    let symbol = stack_graph.add_symbol(&node.symbol);
+                    let node_handle = match stack_graph.add_pop_symbol_node(id, symbol, true) {
+                        Some(node_handle) => node_handle,
+                        None => continue,
+                    };
+                    global_index.insert(global_key, node_handle);
+                    (node_handle, true)
+                }
+            };
+            local_map.insert(node.symbol.clone(), node_handle);
+            if is_namespace {
+                stack_graph.add_edge(comp_unit_node_handle, node_handle, 0);
+            }
+            if is_new {
+                let syntax_type = stack_graph.add_string(&node.syntax_type.to_string());
+                let source_info = stack_graph.source_info_mut(node_handle);
+                source_info.syntax_type = syntax_type.into();
+                node_tracking_number += 1
+            }
         }
+        drop(global_index);
 
         let mut edge_tracking_number = 0;
         for edge in inter_edge_info {
@@ -208,6 +380,14 @@ impl FileAnalyzer for DepXMLFileAnalyzer {
                     }
                     graph_node.unwrap()
                 }
+                SyntaxType::EventName => {
+                    let graph_node = map_event_nodes.get(&edge.source.symbol);
+                    if graph_node.is_none() {
+                        error!(file=?path, "didn't create graph node for event {:?}", edge);
+                        return Err(BuildError::ParseError);
+                    }
+                    graph_node.unwrap()
+                }
                 SyntaxType::ClassDef => {
                     let graph_node = map_class_nodes.get(&edge.source.symbol);
                     if graph_node.is_none() {
@@ -249,6 +429,14 @@ impl FileAnalyzer for DepXMLFileAnalyzer {
                     }
                     graph_node.unwrap()
                 }
+                SyntaxType::EventName => {
+                    let graph_node = map_event_nodes.get(&edge.sink.symbol);
+                    if graph_node.is_none() {
+                        error!(file=?path, "didn't create graph node for event {:?}", edge.sink);
+                        return Err(BuildError::ParseError);
+                    }
+                    graph_node.unwrap()
+                }
                 SyntaxType::ClassDef => {
                     let graph_node = map_class_nodes.get(&edge.sink.symbol);
                     if graph_node.is_none() {
@@ -282,6 +470,13 @@ impl FileAnalyzer for DepXMLFileAnalyzer {
             edge_tracking_number += 1;
         }
 
+        let ordered = Self::stable_post_order(stack_graph, comp_unit_node_handle)?;
+        trace!(
+            file=?path,
+            "containment subgraph validated acyclic, {} nodes in stable order",
+            ordered.len()
+        );
+
         info!(
             file=?path,
             "created {} graph nodes {} edge nodes",
@@ -291,127 +486,311 @@ impl FileAnalyzer for DepXMLFileAnalyzer {
     }
 }
 
+/// Split a generic arity marker off `segment` if present: a trailing
+/// `` `N `` (type generic arity, e.g. `` List`1 `` -> (`"List"`, `Some(1)`))
+/// or ``` ``N ``` (method generic arity, e.g. `` Select``2 `` ->
+/// (`"Select"`, `Some(2)`)). Returns `segment` unchanged with `None` if it
+/// carries no marker or the digits after the backtick(s) don't parse.
+fn decode_generic_arity(segment: &str) -> (String, Option<u32>) {
+    for marker in ["``", "`"] {
+        if let Some(idx) = segment.rfind(marker) {
+            let (name, rest) = segment.split_at(idx);
+            let digits = &rest[marker.len()..];
+            if !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()) {
+                if let Ok(arity) = digits.parse::<u32>() {
+                    return (name.to_string(), Some(arity));
+                }
+            }
+        }
+    }
+    (segment.to_string(), None)
+}
+
+/// Build `ClassDef` nodes for a `+`-nested type chain (e.g. `Outer+Inner`),
+/// decoding each segment's generic arity marker, plus the precedence 0/10
+/// containment edges between consecutive nesting levels -- mirroring how
+/// namespace/type containment is already encoded elsewhere in this file.
+/// The returned node list runs outermost to innermost; for a non-nested
+/// segment it is a single node and the edge list is empty.
+fn build_nested_type_chain(segment: &str) -> (Vec<NodeInfo>, Vec<EdgeInfo>) {
+    let nodes: Vec<NodeInfo> = segment
+        .split('+')
+        .map(|s| {
+            let (symbol, arity) = decode_generic_arity(s);
+            NodeInfo {
+                symbol,
+                syntax_type: SyntaxType::ClassDef,
+                arity,
+            }
+        })
+        .collect();
+
+    let mut edges = Vec::new();
+    for pair in nodes.windows(2) {
+        edges.push(EdgeInfo {
+            source: pair[0].clone(),
+            sink: pair[1].clone(),
+            precedence: 0,
+        });
+        edges.push(EdgeInfo {
+            source: pair[1].clone(),
+            sink: pair[0].clone(),
+            precedence: 10,
+        });
+    }
+    (nodes, edges)
+}
+
+/// Precedence for an edge that records an actual usage dependency (e.g. a
+/// method parameter's type) rather than containment (`0`) or FQDN upward
+/// traversal (`10`).
+pub(crate) const REFERENCE_PRECEDENCE: i32 = 20;
+
+/// Build the `ClassDef`/`NamespaceDeclaration` nodes and containment edges
+/// for a dotted, possibly `+`-nested fully-qualified type name -- the same
+/// shape a `T:` member ID produces. `arity_override` fills in the
+/// innermost type's arity when the name itself carried no backtick marker
+/// (e.g. a parameter type written as `Dictionary{System.String,System.Int32}`
+/// rather than `` Dictionary`2 ``). Returns the full node list, the
+/// containment edges, and the innermost type's own node so callers can
+/// attach further edges to it.
+fn build_type_nodes(fqn: &str, arity_override: Option<u32>) -> Option<(Vec<NodeInfo>, Vec<EdgeInfo>, NodeInfo)> {
+    if fqn.is_empty() {
+        return None;
+    }
+    let mut parts = fqn.split('.');
+    let last = parts.next_back()?;
+    let (mut type_chain, mut nested_edges) = build_nested_type_chain(last);
+    if let Some(innermost) = type_chain.last_mut() {
+        if innermost.arity.is_none() {
+            innermost.arity = arity_override;
+        }
+    }
+    let outer_type = type_chain.first()?.clone();
+    let type_name = type_chain.last()?.clone();
+    let mut nodes = type_chain;
+
+    let namespace_symbol = parts.fold("".to_string(), |acc, p| {
+        let interface_check_parts: Vec<&str> = p.split('#').collect();
+        let t = if interface_check_parts.len() > 1 {
+            interface_check_parts[0]
+        } else {
+            p
+        };
+        if acc.is_empty() {
+            t.to_string()
+        } else {
+            format!("{}.{}", acc, t)
+        }
+    });
+    let namespace_node = NodeInfo {
+        symbol: namespace_symbol,
+        syntax_type: SyntaxType::NamespaceDeclaration,
+        arity: None,
+    };
+    nodes.push(namespace_node.clone());
+
+    let mut edges = vec![EdgeInfo {
+        source: namespace_node.clone(),
+        sink: outer_type.clone(),
+        precedence: 0,
+    }];
+    edges.append(&mut nested_edges);
+    edges.push(EdgeInfo {
+        source: outer_type,
+        sink: namespace_node,
+        precedence: 10,
+    });
+
+    Some((nodes, edges, type_name))
+}
+
+/// Shared decomposition for `F:`/`P:`/`E:` member IDs: the leaf identifier
+/// (field, property or event name) is built as a `leaf_syntax_type` node,
+/// with the remaining dotted segments forming the declaring type chain and
+/// namespace exactly as `build_type_nodes` does for `T:`. Fields/properties
+/// and events only differ in which `SyntaxType` their leaf node carries.
+fn build_leaf_member_nodes(
+    kind: &str,
+    name: &str,
+    leaf_syntax_type: SyntaxType,
+) -> Result<(Vec<NodeInfo>, Vec<EdgeInfo>), MemberParseError> {
+    if name.is_empty() {
+        return Err(MemberParseError::EmptyIdentifier);
+    }
+    let mut parts = name.split('.');
+    let mut nodes: Vec<NodeInfo> = vec![];
+    let mut edges: Vec<EdgeInfo> = vec![];
+    let part = parts.next_back().ok_or(MemberParseError::EmptyIdentifier)?;
+    let leaf_node = NodeInfo {
+        symbol: part.to_string(),
+        syntax_type: leaf_syntax_type,
+        arity: None,
+    };
+    nodes.push(leaf_node.clone());
+    let part = parts.next_back().ok_or(MemberParseError::InsufficientParts {
+        kind: kind.to_string(),
+        found: 1,
+        needed: 2,
+    })?;
+    let (type_chain, mut nested_edges) = build_nested_type_chain(part);
+    let outer_type = type_chain.first().unwrap().clone();
+    let type_name = type_chain.last().unwrap().clone();
+    nodes.extend(type_chain);
+    let namespace_symbol = parts.fold("".to_string(), |acc, p| {
+        if acc.is_empty() {
+            p.to_string()
+        } else {
+            format!("{}.{}", acc, p)
+        }
+    });
+    let namesapce_node = NodeInfo {
+        symbol: namespace_symbol.clone(),
+        syntax_type: SyntaxType::NamespaceDeclaration,
+        arity: None,
+    };
+    nodes.push(namesapce_node.clone());
+    edges.push(EdgeInfo {
+        source: namesapce_node.clone(),
+        sink: outer_type.clone(),
+        precedence: 0,
+    });
+    edges.append(&mut nested_edges);
+    edges.push(EdgeInfo {
+        source: type_name.clone(),
+        sink: leaf_node.clone(),
+        precedence: 0,
+    });
+    edges.push(EdgeInfo {
+        source: leaf_node,
+        sink: type_name,
+        precedence: 10,
+    });
+    edges.push(EdgeInfo {
+        source: outer_type,
+        sink: namesapce_node,
+        precedence: 10,
+    });
+    Ok((nodes, edges))
+}
+
+/// Split a method parameter list on top-level commas, treating commas
+/// nested inside `{...}` (generic arguments) or `[...]` (array rank) as
+/// part of the enclosing parameter rather than a separator, so
+/// `Dictionary{System.String,System.Int32},System.Boolean` splits into two
+/// parameters rather than three.
+fn split_top_level_params(params: &str) -> Vec<&str> {
+    if params.trim().is_empty() {
+        return vec![];
+    }
+    let mut result = Vec::new();
+    let mut depth = 0i32;
+    let mut start = 0;
+    for (i, c) in params.char_indices() {
+        match c {
+            '{' | '[' => depth += 1,
+            '}' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                result.push(params[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    result.push(params[start..].trim());
+    result
+}
+
+/// Strip a parameter type down to the fully-qualified type name
+/// `build_type_nodes` understands: drop a trailing `[]`/`[,]` array rank
+/// and a `{...}` generic argument list, returning the number of top-level
+/// arguments found in the braces (if any) as the type's arity.
+fn clean_param_type(raw: &str) -> (String, Option<u32>) {
+    let raw = raw.trim();
+    let mut base = raw;
+    let mut arity = None;
+    if let Some(brace_idx) = raw.find('{') {
+        if let Some(end_idx) = raw.rfind('}') {
+            if end_idx > brace_idx {
+                let arg_count = split_top_level_params(&raw[brace_idx + 1..end_idx]).len();
+                if arg_count > 0 {
+                    arity = Some(arg_count as u32);
+                }
+                base = &raw[..brace_idx];
+            }
+        }
+    }
+    let base = base.trim_end_matches(|c| matches!(c, '[' | ']' | ',' | '@' | '*'));
+    (base.to_string(), arity)
+}
+
+/// A parameter written as a bare backtick marker (e.g. `` `0 `` or ``0``)
+/// isn't a real type name -- it's a reference to the Nth generic parameter
+/// of the enclosing type (single backtick) or method (double backtick).
+/// Decode it to a synthetic type-variable node (`!0`/`!!0`, the shorthand
+/// CLR metadata viewers use) instead of trying to resolve it through
+/// `build_type_nodes`, which would otherwise treat the bare digits as an
+/// empty, namespace-less type.
+fn generic_param_placeholder(base_type: &str) -> Option<NodeInfo> {
+    for (marker, var_prefix) in [("``", "!!"), ("`", "!")] {
+        if let Some(digits) = base_type.strip_prefix(marker) {
+            if !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()) {
+                return Some(NodeInfo {
+                    symbol: format!("{var_prefix}{digits}"),
+                    syntax_type: SyntaxType::ClassDef,
+                    arity: None,
+                });
+            }
+        }
+    }
+    None
+}
+
 impl DepXMLFileAnalyzer {
+    /// Convenience wrapper around `handle_member` + `to_dot`, for dumping a
+    /// single XML-doc member ID's decomposition straight into a Graphviz
+    /// viewer when debugging precedence or missing-namespace cases.
+    pub fn handle_member_dot(&self, member_type: &str, name: &str) -> String {
+        let (nodes, edges) = self.handle_member(member_type, name);
+        to_dot(&nodes, &edges)
+    }
+
     fn handle_member(&self, member_type: &str, name: &str) -> (Vec<NodeInfo>, Vec<EdgeInfo>) {
+        self.try_handle_member(member_type, name).unwrap_or_default()
+    }
+
+    /// Fallible variant of `handle_member` that names the concrete parse
+    /// failure instead of collapsing every error into an indistinguishable
+    /// empty result, so callers driving rule evaluation can log or surface
+    /// something actionable.
+    pub fn try_handle_member(
+        &self,
+        member_type: &str,
+        name: &str,
+    ) -> Result<(Vec<NodeInfo>, Vec<EdgeInfo>), MemberParseError> {
         match member_type {
             // namespace.
             "N" => {
                 let node = NodeInfo {
                     symbol: name.to_string(),
                     syntax_type: SyntaxType::NamespaceDeclaration,
+                    arity: None,
                 };
-                (vec![node], vec![])
+                Ok((vec![node], vec![]))
             }
             // type, field and property
-            "T" => {
-                if name.is_empty() {
-                    return (vec![], vec![]);
-                }
-                let mut parts = name.split('.');
-                let mut nodes: Vec<NodeInfo> = vec![];
-                let mut edges: Vec<EdgeInfo> = vec![];
-                let part = parts.next_back();
-                if part.is_none() {
-                    return (vec![], vec![]);
-                }
-                let type_name = NodeInfo {
-                    symbol: part.unwrap().to_string(),
-                    syntax_type: SyntaxType::ClassDef,
-                };
-                nodes.push(type_name.clone());
-                let namespace_symbol = parts.fold("".to_string(), |acc, p| {
-                    let interface_check_parts: Vec<&str> = p.split("#").collect();
-                    let t = if interface_check_parts.len() > 1 {
-                        interface_check_parts[0]
-                    } else {
-                        p
-                    };
-
-                    if acc.is_empty() {
-                        t.to_string()
-                    } else {
-                        format!("{}.{}", acc, t)
-                    }
-                });
-                let namesapce_node = NodeInfo {
-                    symbol: namespace_symbol.clone(),
-                    syntax_type: SyntaxType::NamespaceDeclaration,
-                };
-                nodes.push(namesapce_node.clone());
-                edges.push(EdgeInfo {
-                    source: namesapce_node.clone(),
-                    sink: type_name.clone(),
-                    precedence: 0,
-                });
-                edges.push(EdgeInfo {
-                    source: type_name,
-                    sink: namesapce_node,
-                    precedence: 10,
-                });
-                (nodes, edges)
-            }
-            "F" | "P" => {
-                if name.is_empty() {
-                    return (vec![], vec![]);
-                }
-                let mut parts = name.split('.');
-                let mut nodes: Vec<NodeInfo> = vec![];
-                let mut edges: Vec<EdgeInfo> = vec![];
-                let part = parts.next_back();
-                if part.is_none() {
-                    return (vec![], vec![]);
-                }
-                let field_name = NodeInfo {
-                    symbol: part.unwrap().to_string(),
-                    syntax_type: SyntaxType::FieldName,
-                };
-                nodes.push(field_name.clone());
-                let part = parts.next_back();
-                if part.is_none() {
-                    return (vec![], vec![]);
-                }
-                let type_name = NodeInfo {
-                    symbol: part.unwrap().to_string(),
-                    syntax_type: SyntaxType::ClassDef,
-                };
-                nodes.push(type_name.clone());
-                let namespace_symbol = parts.fold("".to_string(), |acc, p| {
-                    if acc.is_empty() {
-                        p.to_string()
-                    } else {
-                        format!("{}.{}", acc, p)
-                    }
-                });
-                let namesapce_node = NodeInfo {
-                    symbol: namespace_symbol.clone(),
-                    syntax_type: SyntaxType::NamespaceDeclaration,
-                };
-                nodes.push(namesapce_node.clone());
-                edges.push(EdgeInfo {
-                    source: namesapce_node.clone(),
-                    sink: type_name.clone(),
-                    precedence: 0,
-                });
-                edges.push(EdgeInfo {
-                    source: type_name.clone(),
-                    sink: field_name.clone(),
-                    precedence: 0,
-                });
-                edges.push(EdgeInfo {
-                    source: field_name,
-                    sink: type_name.clone(),
-                    precedence: 10,
-                });
-                edges.push(EdgeInfo {
-                    source: type_name.clone(),
-                    sink: namesapce_node,
-                    precedence: 10,
-                });
-                (nodes, edges)
-            }
+            "T" => match build_type_nodes(name, None) {
+                Some((nodes, edges, _type_name)) => Ok((nodes, edges)),
+                None => Err(MemberParseError::EmptyIdentifier),
+            },
+            // field and property
+            "F" | "P" => build_leaf_member_nodes(member_type, name, SyntaxType::FieldName),
+            // event -- structured identically to a field/property, but kept
+            // distinguishable as its own syntax node.
+            "E" => build_leaf_member_nodes(member_type, name, SyntaxType::EventName),
             "M" => {
                 if name.is_empty() {
-                    return (vec![], vec![]);
+                    return Err(MemberParseError::EmptyIdentifier);
                 }
                 let mut new_name = name;
                 if name.contains('(') {
@@ -422,46 +801,43 @@ impl DepXMLFileAnalyzer {
                 let mut parts = new_name.split('.');
                 let mut nodes: Vec<NodeInfo> = vec![];
                 let mut edges: Vec<EdgeInfo> = vec![];
-                let part = parts.next_back();
-                if part.is_none() {
-                    return (vec![], vec![]);
-                }
+                let part = parts.next_back().ok_or(MemberParseError::EmptyIdentifier)?;
                 // Handle the name of the method here.
                 // if #ctor means constructor.
                 // for now we can ignore the parameters.
-                let part = part.unwrap();
                 let method_node: NodeInfo;
-                let type_name: NodeInfo;
+                let type_chain: Vec<NodeInfo>;
+                let mut nested_edges: Vec<EdgeInfo>;
                 if part.contains("#ctor") {
                     // Get the next back Symbol and that will be the symbol.
-                    let part = parts.next_back();
-                    if part.is_none() {
-                        return (vec![], vec![]);
-                    }
+                    let part = parts
+                        .next_back()
+                        .ok_or(MemberParseError::ConstructorWithoutClass)?;
+                    (type_chain, nested_edges) = build_nested_type_chain(part);
+                    let innermost = type_chain.last().unwrap();
                     method_node = NodeInfo {
-                        symbol: part.unwrap().to_string(),
+                        symbol: innermost.symbol.clone(),
                         syntax_type: SyntaxType::MethodName,
-                    };
-                    type_name = NodeInfo {
-                        symbol: part.unwrap().to_string(),
-                        syntax_type: SyntaxType::ClassDef,
+                        arity: None,
                     };
                 } else {
+                    let (method_symbol, method_arity) = decode_generic_arity(part);
                     method_node = NodeInfo {
-                        symbol: part.to_string(),
+                        symbol: method_symbol,
                         syntax_type: SyntaxType::MethodName,
+                        arity: method_arity,
                     };
-                    let part = parts.next_back();
-                    if part.is_none() {
-                        return (vec![], vec![]);
-                    }
-                    type_name = NodeInfo {
-                        symbol: part.unwrap().to_string(),
-                        syntax_type: SyntaxType::ClassDef,
-                    };
+                    let part = parts.next_back().ok_or(MemberParseError::InsufficientParts {
+                        kind: member_type.to_string(),
+                        found: 1,
+                        needed: 2,
+                    })?;
+                    (type_chain, nested_edges) = build_nested_type_chain(part);
                 };
+                let outer_type = type_chain.first().unwrap().clone();
+                let type_name = type_chain.last().unwrap().clone();
                 nodes.push(method_node.clone());
-                nodes.push(type_name.clone());
+                nodes.extend(type_chain);
                 let namespace_symbol = parts.fold("".to_string(), |acc, p| {
                     if acc.is_empty() {
                         p.to_string()
@@ -472,45 +848,179 @@ impl DepXMLFileAnalyzer {
                 let namesapce_node = NodeInfo {
                     symbol: namespace_symbol.clone(),
                     syntax_type: SyntaxType::NamespaceDeclaration,
+                    arity: None,
                 };
                 nodes.push(namesapce_node.clone());
                 edges.push(EdgeInfo {
                     source: namesapce_node.clone(),
-                    sink: type_name.clone(),
+                    sink: outer_type.clone(),
                     precedence: 0,
                 });
+                edges.append(&mut nested_edges);
                 edges.push(EdgeInfo {
                     source: type_name.clone(),
                     sink: method_node.clone(),
                     precedence: 0,
                 });
+
+                // Outbound parameter types are real usage dependencies, not
+                // containment: reference edges from the method to each
+                // parameter's type, added with the method's declaring type
+                // and namespace edges below.
+                let mut rest_after_params = "";
+                if let Some(open) = name.find('(') {
+                    let close = name
+                        .rfind(')')
+                        .filter(|&c| c > open)
+                        .ok_or_else(|| MemberParseError::MalformedParameters(name.to_string()))?;
+                    for param in split_top_level_params(&name[open + 1..close]) {
+                        let (base_type, param_arity) = clean_param_type(param);
+                        if base_type.is_empty() {
+                            continue;
+                        }
+                        if let Some(placeholder) = generic_param_placeholder(&base_type) {
+                            nodes.push(placeholder.clone());
+                            edges.push(EdgeInfo {
+                                source: method_node.clone(),
+                                sink: placeholder,
+                                precedence: REFERENCE_PRECEDENCE,
+                            });
+                            continue;
+                        }
+                        if let Some((param_nodes, mut param_edges, param_type_node)) =
+                            build_type_nodes(&base_type, param_arity)
+                        {
+                            nodes.extend(param_nodes);
+                            edges.append(&mut param_edges);
+                            edges.push(EdgeInfo {
+                                source: method_node.clone(),
+                                sink: param_type_node,
+                                precedence: REFERENCE_PRECEDENCE,
+                            });
+                        }
+                    }
+                    rest_after_params = &name[close + 1..];
+                }
+                // A conversion operator's return type is appended after the
+                // parameter list as `~ReturnType` (e.g. `op_Implicit(...)~
+                // System.Decimal`); it's a usage dependency like a parameter,
+                // not part of the signature, so it gets the same reference
+                // edge treatment.
+                if let Some(return_type) = rest_after_params.strip_prefix('~') {
+                    let (base_type, return_arity) = clean_param_type(return_type);
+                    if !base_type.is_empty() {
+                        if let Some(placeholder) = generic_param_placeholder(&base_type) {
+                            nodes.push(placeholder.clone());
+                            edges.push(EdgeInfo {
+                                source: method_node.clone(),
+                                sink: placeholder,
+                                precedence: REFERENCE_PRECEDENCE,
+                            });
+                        } else if let Some((return_nodes, mut return_edges, return_type_node)) =
+                            build_type_nodes(&base_type, return_arity)
+                        {
+                            nodes.extend(return_nodes);
+                            edges.append(&mut return_edges);
+                            edges.push(EdgeInfo {
+                                source: method_node.clone(),
+                                sink: return_type_node,
+                                precedence: REFERENCE_PRECEDENCE,
+                            });
+                        }
+                    }
+                }
+
                 edges.push(EdgeInfo {
                     source: method_node,
-                    sink: type_name.clone(),
+                    sink: type_name,
                     precedence: 10,
                 });
                 edges.push(EdgeInfo {
-                    source: type_name.clone(),
+                    source: outer_type,
                     sink: namesapce_node,
                     precedence: 10,
                 });
-                (nodes, edges)
+                Ok((nodes, edges))
             }
+            "" => Err(MemberParseError::EmptyMemberType),
             _ => {
                 info!("unable to handle: {} -- {}", member_type, name);
-                (vec![], vec![])
+                Err(MemberParseError::UnknownMemberType(member_type.to_string()))
+            }
+        }
+    }
+
+    /// Walk the precedence-0 (containment) subgraph from `root` with an
+    /// iterative DFS, producing a stable reverse-post-order `Vec<Handle<Node>>`
+    /// -- deterministic regardless of the HashMap iteration order that drove
+    /// node/edge emission above. A three-color map (absent=white, gray=on the
+    /// current DFS stack, black=done) catches a node reached through a
+    /// precedence-0 edge while still gray, i.e. a type contained in itself,
+    /// and reports it as a `BuildError` naming the offending symbol instead
+    /// of looping forever.
+    fn stable_post_order(
+        stack_graph: &StackGraph,
+        root: Handle<Node>,
+    ) -> Result<Vec<Handle<Node>>, BuildError> {
+        #[derive(PartialEq)]
+        enum Color {
+            Gray,
+            Black,
+        }
+
+        let mut color: HashMap<Handle<Node>, Color> = HashMap::new();
+        let mut post_order = Vec::new();
+        let mut stack: Vec<(Handle<Node>, usize)> = vec![(root, 0)];
+        color.insert(root, Color::Gray);
+
+        while let Some((node, child_index)) = stack.pop() {
+            let children: Vec<Handle<Node>> = stack_graph
+                .outgoing_edges(node)
+                .filter(|e| e.precedence == 0)
+                .map(|e| e.sink)
+                .collect();
+
+            if let Some(&child) = children.get(child_index) {
+                stack.push((node, child_index + 1));
+                match color.get(&child) {
+                    Some(Color::Gray) => {
+                        let symbol_name = stack_graph[child]
+                            .symbol()
+                            .map(|s| stack_graph[s].to_string())
+                            .unwrap_or_else(|| "<unknown>".to_string());
+                        error!(
+                            symbol = %symbol_name,
+                            "containment cycle detected through precedence-0 edge"
+                        );
+                        return Err(BuildError::UnknownSymbolType(format!(
+                            "containment cycle detected: {symbol_name} is contained in itself"
+                        )));
+                    }
+                    Some(Color::Black) => {}
+                    None => {
+                        color.insert(child, Color::Gray);
+                        stack.push((child, 0));
+                    }
+                }
+            } else {
+                color.insert(node, Color::Black);
+                post_order.push(node);
             }
         }
+
+        post_order.reverse();
+        Ok(post_order)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::path::PathBuf;
 
     // Helper function to create a DepXMLFileAnalyzer for testing
     fn create_analyzer() -> DepXMLFileAnalyzer {
-        DepXMLFileAnalyzer {}
+        DepXMLFileAnalyzer::new()
     }
 
     // Tests for Namespace (N) type
@@ -728,7 +1238,9 @@ mod tests {
         let (nodes, _edges) =
             analyzer.handle_member("M", "System.String.Format(System.String,System.Object)");
 
-        assert_eq!(nodes.len(), 3);
+        // Method, declaring class, declaring namespace, plus a ClassDef +
+        // NamespaceDeclaration pair for each of the two parameter types.
+        assert_eq!(nodes.len(), 7);
 
         // Method name should be extracted without parameters
         assert_eq!(nodes[0].symbol, "Format");
@@ -736,6 +1248,11 @@ mod tests {
 
         assert_eq!(nodes[1].symbol, "String");
         assert_eq!(nodes[2].symbol, "System");
+
+        // Parameter types are present as their own ClassDef nodes
+        assert!(nodes
+            .iter()
+            .any(|n| n.symbol == "Object" && n.syntax_type == SyntaxType::ClassDef));
     }
 
     #[test]
@@ -746,10 +1263,71 @@ mod tests {
             "System.Collections.Generic.List.Add(System.Collections.Generic.T)",
         );
 
-        assert_eq!(nodes.len(), 3);
+        // Method, declaring class, declaring namespace, plus a ClassDef +
+        // NamespaceDeclaration pair for the single parameter type.
+        assert_eq!(nodes.len(), 5);
         assert_eq!(nodes[0].symbol, "Add");
         assert_eq!(nodes[1].symbol, "List");
         assert_eq!(nodes[2].symbol, "System.Collections.Generic");
+        assert!(nodes
+            .iter()
+            .any(|n| n.symbol == "T" && n.syntax_type == SyntaxType::ClassDef));
+    }
+
+    #[test]
+    fn test_handle_member_method_by_ref_and_pointer_parameters() {
+        let analyzer = create_analyzer();
+        let (nodes, _) =
+            analyzer.handle_member("M", "System.Int32.TryParse(System.String,System.Int32@)");
+
+        // The `@` by-ref suffix is stripped so the parameter still resolves
+        // to plain `Int32`, not a dangling `Int32@` symbol.
+        assert!(nodes
+            .iter()
+            .any(|n| n.symbol == "Int32" && n.syntax_type == SyntaxType::ClassDef));
+        assert!(!nodes.iter().any(|n| n.symbol.contains('@')));
+
+        let (nodes, _) = analyzer.handle_member("M", "System.IntPtr.op_Explicit(System.Void*)");
+        assert!(nodes
+            .iter()
+            .any(|n| n.symbol == "Void" && n.syntax_type == SyntaxType::ClassDef));
+        assert!(!nodes.iter().any(|n| n.symbol.contains('*')));
+    }
+
+    #[test]
+    fn test_handle_member_method_generic_parameter_placeholder() {
+        let analyzer = create_analyzer();
+        // `Array.Sort``1(`0[])`: a method generic parameter (``1) plus a
+        // parameter that's an array of the enclosing type's own generic
+        // parameter (`0), neither of which is a resolvable namespaced type.
+        let (nodes, edges) =
+            analyzer.handle_member("M", "System.Array.Sort``1(`0[])");
+
+        assert_eq!(nodes[0].symbol, "Sort");
+        assert_eq!(nodes[0].arity, Some(1));
+        assert!(nodes
+            .iter()
+            .any(|n| n.symbol == "!0" && n.syntax_type == SyntaxType::ClassDef));
+        assert!(edges.iter().any(|e| e.source.symbol == "Sort"
+            && e.sink.symbol == "!0"
+            && e.precedence == REFERENCE_PRECEDENCE));
+    }
+
+    #[test]
+    fn test_handle_member_conversion_operator_return_type() {
+        let analyzer = create_analyzer();
+        let (nodes, edges) = analyzer.handle_member(
+            "M",
+            "System.Decimal.op_Implicit(System.Int32)~System.Decimal",
+        );
+
+        assert_eq!(nodes[0].symbol, "op_Implicit");
+        // The return type is a reference edge off the method, same as a
+        // parameter, not part of the method's own containment chain.
+        assert!(edges.iter().any(|e| e.source.symbol == "op_Implicit"
+            && e.sink.symbol == "Decimal"
+            && e.sink.syntax_type == SyntaxType::ClassDef
+            && e.precedence == REFERENCE_PRECEDENCE));
     }
 
     #[test]
@@ -775,12 +1353,16 @@ mod tests {
         let analyzer = create_analyzer();
         let (nodes, _) = analyzer.handle_member("M", "System.String.#ctor(System.Char[])");
 
-        assert_eq!(nodes.len(), 3);
-
-        // Constructor with parameters - params should be stripped
+        // Constructor, declaring class, declaring namespace, plus a ClassDef
+        // + NamespaceDeclaration pair for the (array-stripped) parameter type.
+        assert_eq!(nodes.len(), 5);
         assert_eq!(nodes[0].symbol, "String");
         assert_eq!(nodes[1].symbol, "String");
         assert_eq!(nodes[2].symbol, "System");
+        // The `[]` array suffix is stripped off, leaving the element type
+        assert!(nodes
+            .iter()
+            .any(|n| n.symbol == "Char" && n.syntax_type == SyntaxType::ClassDef));
     }
 
     #[test]
@@ -845,6 +1427,113 @@ mod tests {
         assert_eq!(edges.len(), 0);
     }
 
+    // Tests for try_handle_member's structured diagnostics
+
+    #[test]
+    fn test_try_handle_member_unknown_type() {
+        let analyzer = create_analyzer();
+        let err = analyzer
+            .try_handle_member("X", "System.Something")
+            .unwrap_err();
+        assert_eq!(err, MemberParseError::UnknownMemberType("X".to_string()));
+    }
+
+    #[test]
+    fn test_try_handle_member_empty_member_type() {
+        let analyzer = create_analyzer();
+        let err = analyzer
+            .try_handle_member("", "System.Something")
+            .unwrap_err();
+        assert_eq!(err, MemberParseError::EmptyMemberType);
+    }
+
+    #[test]
+    fn test_try_handle_member_empty_identifier() {
+        let analyzer = create_analyzer();
+        assert_eq!(
+            analyzer.try_handle_member("T", "").unwrap_err(),
+            MemberParseError::EmptyIdentifier
+        );
+        assert_eq!(
+            analyzer.try_handle_member("F", "").unwrap_err(),
+            MemberParseError::EmptyIdentifier
+        );
+        assert_eq!(
+            analyzer.try_handle_member("M", "").unwrap_err(),
+            MemberParseError::EmptyIdentifier
+        );
+    }
+
+    #[test]
+    fn test_try_handle_member_insufficient_parts() {
+        let analyzer = create_analyzer();
+        assert_eq!(
+            analyzer.try_handle_member("F", "Out").unwrap_err(),
+            MemberParseError::InsufficientParts {
+                kind: "F".to_string(),
+                found: 1,
+                needed: 2,
+            }
+        );
+        assert_eq!(
+            analyzer.try_handle_member("M", "Format").unwrap_err(),
+            MemberParseError::InsufficientParts {
+                kind: "M".to_string(),
+                found: 1,
+                needed: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn test_try_handle_member_constructor_without_class() {
+        let analyzer = create_analyzer();
+        assert_eq!(
+            analyzer.try_handle_member("M", "#ctor").unwrap_err(),
+            MemberParseError::ConstructorWithoutClass
+        );
+    }
+
+    #[test]
+    fn test_try_handle_member_malformed_parameters() {
+        let analyzer = create_analyzer();
+        let err = analyzer
+            .try_handle_member("M", "System.String.Format(System.String")
+            .unwrap_err();
+        assert_eq!(
+            err,
+            MemberParseError::MalformedParameters(
+                "System.String.Format(System.String".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_try_handle_member_success_matches_handle_member() {
+        let analyzer = create_analyzer();
+        let (nodes, edges) = analyzer
+            .try_handle_member("M", "System.String.Format")
+            .unwrap();
+        assert_eq!(
+            (nodes, edges),
+            analyzer.handle_member("M", "System.String.Format")
+        );
+    }
+
+    #[test]
+    fn test_member_parse_error_display_names_the_cause() {
+        assert!(MemberParseError::UnknownMemberType("X".to_string())
+            .to_string()
+            .contains("X"));
+        assert!(MemberParseError::InsufficientParts {
+            kind: "F".to_string(),
+            found: 1,
+            needed: 2,
+        }
+        .to_string()
+        .contains("1"));
+    }
+
     // Edge precedence tests
 
     #[test]
@@ -906,10 +1595,18 @@ mod tests {
             "System.Linq.Enumerable.Where(System.Collections.Generic.IEnumerable,System.Func)",
         );
 
-        assert_eq!(nodes.len(), 3);
+        // Method, declaring class, declaring namespace, plus a ClassDef +
+        // NamespaceDeclaration pair for each of the two parameter types.
+        assert_eq!(nodes.len(), 7);
         assert_eq!(nodes[0].symbol, "Where");
         assert_eq!(nodes[1].symbol, "Enumerable");
         assert_eq!(nodes[2].symbol, "System.Linq");
+        assert!(nodes
+            .iter()
+            .any(|n| n.symbol == "IEnumerable" && n.syntax_type == SyntaxType::ClassDef));
+        assert!(nodes
+            .iter()
+            .any(|n| n.symbol == "Func" && n.syntax_type == SyntaxType::ClassDef));
     }
 
     #[test]
@@ -918,11 +1615,90 @@ mod tests {
         let (nodes, _) = analyzer.handle_member("T", "System.Collections.Generic.List`1");
 
         assert_eq!(nodes.len(), 2);
-        // Generic type notation is preserved
-        assert_eq!(nodes[0].symbol, "List`1");
+        // Generic arity marker is decoded off the symbol rather than kept literal
+        assert_eq!(nodes[0].symbol, "List");
+        assert_eq!(nodes[0].arity, Some(1));
         assert_eq!(nodes[1].symbol, "System.Collections.Generic");
     }
 
+    #[test]
+    fn test_handle_member_event() {
+        let analyzer = create_analyzer();
+        let (nodes, edges) =
+            analyzer.handle_member("E", "System.ComponentModel.Component.Disposed");
+
+        // Events decompose exactly like fields/properties, but keep their
+        // own syntax type so they stay distinguishable downstream.
+        assert_eq!(nodes.len(), 3);
+        assert_eq!(edges.len(), 4);
+
+        assert_eq!(nodes[0].symbol, "Disposed");
+        assert_eq!(nodes[0].syntax_type, SyntaxType::EventName);
+        assert_eq!(nodes[1].symbol, "Component");
+        assert_eq!(nodes[1].syntax_type, SyntaxType::ClassDef);
+        assert_eq!(nodes[2].symbol, "System.ComponentModel");
+        assert_eq!(nodes[2].syntax_type, SyntaxType::NamespaceDeclaration);
+    }
+
+    #[test]
+    fn test_handle_member_method_generic_arity() {
+        let analyzer = create_analyzer();
+        let (nodes, _) = analyzer.handle_member(
+            "M",
+            "System.Linq.Enumerable.Select``2(System.Collections.Generic.IEnumerable,System.Func)",
+        );
+
+        assert_eq!(nodes[0].symbol, "Select");
+        assert_eq!(nodes[0].arity, Some(2));
+        assert_eq!(nodes[1].symbol, "Enumerable");
+        assert_eq!(nodes[1].arity, None);
+    }
+
+    #[test]
+    fn test_handle_member_nested_type() {
+        let analyzer = create_analyzer();
+        let (nodes, edges) = analyzer.handle_member("T", "MyApp.Models+Address");
+
+        // Outer and inner type both get ClassDef nodes, plus the namespace
+        assert_eq!(nodes.len(), 3);
+        assert_eq!(nodes[0].symbol, "Models");
+        assert_eq!(nodes[0].syntax_type, SyntaxType::ClassDef);
+        assert_eq!(nodes[1].symbol, "Address");
+        assert_eq!(nodes[1].syntax_type, SyntaxType::ClassDef);
+        assert_eq!(nodes[2].symbol, "MyApp");
+        assert_eq!(nodes[2].syntax_type, SyntaxType::NamespaceDeclaration);
+
+        // namespace -> Models (0), Models -> Address (0), Address -> Models (10), Models -> namespace (10)
+        assert_eq!(edges.len(), 4);
+        assert_eq!(edges[0].source.symbol, "MyApp");
+        assert_eq!(edges[0].sink.symbol, "Models");
+        assert_eq!(edges[0].precedence, 0);
+        assert_eq!(edges[1].source.symbol, "Models");
+        assert_eq!(edges[1].sink.symbol, "Address");
+        assert_eq!(edges[1].precedence, 0);
+        assert_eq!(edges[2].source.symbol, "Address");
+        assert_eq!(edges[2].sink.symbol, "Models");
+        assert_eq!(edges[2].precedence, 10);
+        assert_eq!(edges[3].source.symbol, "Models");
+        assert_eq!(edges[3].sink.symbol, "MyApp");
+        assert_eq!(edges[3].precedence, 10);
+    }
+
+    #[test]
+    fn test_handle_member_nested_type_fqdn() {
+        let analyzer = create_analyzer();
+        let (nodes, edges) = analyzer.handle_member("T", "MyApp.Models+Address");
+
+        let (graph, node_map) = build_stack_graph_from_nodes_edges(nodes, edges);
+        let class_key = format!("{:?}:Address", SyntaxType::ClassDef);
+        let class_handle = node_map.get(&class_key).unwrap();
+
+        let fqdn = get_fqdn(*class_handle, &graph).unwrap();
+
+        assert_eq!(fqdn.namespace, Some("MyApp".to_string()));
+        assert_eq!(fqdn.class, Some("Models.Address".to_string()));
+    }
+
     // FQDN Integration Tests
     // These tests verify that nodes/edges from handle_member() produce correct FQDNs
 
@@ -1048,7 +1824,8 @@ mod tests {
 
         let (graph, node_map) = build_stack_graph_from_nodes_edges(nodes, edges);
 
-        // Get FQDN from the method node (Format) - parameters should be stripped
+        // Get FQDN from the method node (Format) -- the signature is exposed
+        // separately via `parameters` so overloads stay distinguishable.
         let method_key = format!("{:?}:Format", SyntaxType::MethodName);
         let method_handle = node_map.get(&method_key).unwrap();
 
@@ -1058,6 +1835,23 @@ mod tests {
         assert_eq!(fqdn.class, Some("String".to_string()));
         assert_eq!(fqdn.method, Some("Format".to_string()));
         assert_eq!(fqdn.field, None);
+        assert_eq!(
+            fqdn.parameters,
+            vec!["System.String".to_string(), "System.Object".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_fqdn_for_method_without_parameters_is_empty() {
+        let analyzer = create_analyzer();
+        let (nodes, edges) = analyzer.handle_member("M", "System.String.Format");
+
+        let (graph, node_map) = build_stack_graph_from_nodes_edges(nodes, edges);
+        let method_key = format!("{:?}:Format", SyntaxType::MethodName);
+        let method_handle = node_map.get(&method_key).unwrap();
+
+        let fqdn = get_fqdn(*method_handle, &graph).unwrap();
+        assert!(fqdn.parameters.is_empty());
     }
 
     #[test]
@@ -1200,4 +1994,262 @@ mod tests {
         assert_eq!(fqdn.field, None);
         assert_eq!(fqdn.method, None);
     }
+
+    #[test]
+    fn test_qualified_key_reconstructs_namespace_and_class_chain() {
+        let namespace = NodeInfo {
+            symbol: "MyApp.Utils".to_string(),
+            syntax_type: SyntaxType::NamespaceDeclaration,
+            arity: None,
+        };
+        let class_def = NodeInfo {
+            symbol: "Helper".to_string(),
+            syntax_type: SyntaxType::ClassDef,
+            arity: None,
+        };
+        let edges = vec![EdgeInfo {
+            source: namespace.clone(),
+            sink: class_def.clone(),
+            precedence: 0,
+        }];
+
+        assert_eq!(qualified_key(&class_def, &edges), "MyApp.Utils.Helper");
+        assert_eq!(qualified_key(&namespace, &edges), "MyApp.Utils");
+    }
+
+    #[test]
+    fn test_qualified_key_terminates_on_self_containing_node() {
+        // A self-nested-type doc-XML member id like `T:NS.X+X` parses into
+        // a structural `X -> X` precedence-0 edge -- `X` contains itself.
+        let self_nested = NodeInfo {
+            symbol: "X".to_string(),
+            syntax_type: SyntaxType::ClassDef,
+            arity: None,
+        };
+        let edges = vec![EdgeInfo {
+            source: self_nested.clone(),
+            sink: self_nested.clone(),
+            precedence: 0,
+        }];
+
+        assert_eq!(qualified_key(&self_nested, &edges), "X");
+    }
+
+    #[test]
+    fn test_qualified_key_terminates_on_longer_containment_cycle() {
+        let a = NodeInfo {
+            symbol: "A".to_string(),
+            syntax_type: SyntaxType::ClassDef,
+            arity: None,
+        };
+        let b = NodeInfo {
+            symbol: "B".to_string(),
+            syntax_type: SyntaxType::ClassDef,
+            arity: None,
+        };
+        // A contains B, and (malformed input) B also contains A.
+        let edges = vec![
+            EdgeInfo {
+                source: a.clone(),
+                sink: b.clone(),
+                precedence: 0,
+            },
+            EdgeInfo {
+                source: b.clone(),
+                sink: a.clone(),
+                precedence: 0,
+            },
+        ];
+
+        assert_eq!(qualified_key(&a, &edges), "B.A");
+        assert_eq!(qualified_key(&b, &edges), "A.B");
+    }
+
+    #[test]
+    fn test_cross_file_resolution_reuses_canonical_node() {
+        let analyzer = DepXMLFileAnalyzer::new();
+        let mut graph = StackGraph::new();
+        let globals: HashMap<String, String> = HashMap::new();
+        let paths = vec![PathBuf::from("a.xml"), PathBuf::from("b.xml")];
+
+        // File B defines the type.
+        let file_b = graph.add_file("b.xml").unwrap();
+        let mut path_iter = paths.iter().map(|p| p.as_path());
+        analyzer
+            .build_stack_graph_into(
+                &mut graph,
+                file_b,
+                Path::new("b.xml"),
+                r#"<member name="T:MyApp.Utils.Helper" />"#,
+                &mut path_iter,
+                &globals,
+                &tree_sitter_stack_graphs::NoCancellation,
+            )
+            .unwrap();
+
+        // File A references the same type as a method parameter.
+        let file_a = graph.add_file("a.xml").unwrap();
+        let mut path_iter = paths.iter().map(|p| p.as_path());
+        analyzer
+            .build_stack_graph_into(
+                &mut graph,
+                file_a,
+                Path::new("a.xml"),
+                r#"<member name="M:MyApp.Service.Do(MyApp.Utils.Helper)" />"#,
+                &mut path_iter,
+                &globals,
+                &tree_sitter_stack_graphs::NoCancellation,
+            )
+            .unwrap();
+
+        let helper_nodes: Vec<_> = graph
+            .iter_nodes()
+            .filter(|&n| match graph[n].symbol() {
+                Some(sym) => &graph[sym] == "Helper",
+                None => false,
+            })
+            .collect();
+        assert_eq!(
+            helper_nodes.len(),
+            1,
+            "Helper should be interned once and reused across files, not redeclared per-file"
+        );
+    }
+
+    #[test]
+    fn test_stable_post_order_orders_root_before_descendants() {
+        let mut graph = StackGraph::new();
+        let file = graph.add_file("order.xml").unwrap();
+
+        let root_id = graph.new_node_id(file);
+        let root_symbol = graph.add_symbol("order.xml");
+        let root = graph.add_pop_symbol_node(root_id, root_symbol, true).unwrap();
+
+        let namespace_id = graph.new_node_id(file);
+        let namespace_symbol = graph.add_symbol("MyApp");
+        let namespace = graph
+            .add_pop_symbol_node(namespace_id, namespace_symbol, true)
+            .unwrap();
+
+        let class_id = graph.new_node_id(file);
+        let class_symbol = graph.add_symbol("Helper");
+        let class = graph
+            .add_pop_symbol_node(class_id, class_symbol, true)
+            .unwrap();
+
+        graph.add_edge(root, namespace, 0);
+        graph.add_edge(namespace, class, 0);
+
+        let order = DepXMLFileAnalyzer::stable_post_order(&graph, root).unwrap();
+        let root_pos = order.iter().position(|&n| n == root).unwrap();
+        let namespace_pos = order.iter().position(|&n| n == namespace).unwrap();
+        let class_pos = order.iter().position(|&n| n == class).unwrap();
+        assert!(root_pos < namespace_pos);
+        assert!(namespace_pos < class_pos);
+    }
+
+    #[test]
+    fn test_stable_post_order_detects_containment_cycle() {
+        let mut graph = StackGraph::new();
+        let file = graph.add_file("cycle.xml").unwrap();
+
+        let id_a = graph.new_node_id(file);
+        let symbol_a = graph.add_symbol("A");
+        let node_a = graph.add_pop_symbol_node(id_a, symbol_a, true).unwrap();
+
+        let id_b = graph.new_node_id(file);
+        let symbol_b = graph.add_symbol("B");
+        let node_b = graph.add_pop_symbol_node(id_b, symbol_b, true).unwrap();
+
+        // A contains B and B contains A: an illegal containment cycle.
+        graph.add_edge(node_a, node_b, 0);
+        graph.add_edge(node_b, node_a, 0);
+
+        let result = DepXMLFileAnalyzer::stable_post_order(&graph, node_a);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_build_stack_graph_into_skips_malformed_member_name() {
+        let analyzer = create_analyzer();
+        let mut graph = StackGraph::new();
+        let globals: HashMap<String, String> = HashMap::new();
+        let paths = vec![PathBuf::from("doc.xml")];
+        let file = graph.add_file("doc.xml").unwrap();
+        let mut path_iter = paths.iter().map(|p| p.as_path());
+
+        // The first <member> has no ":" separator in its name and should be
+        // skipped; the second is well-formed and should still be processed.
+        let source = r#"
+            <member name="BadName" />
+            <member name="T:MyApp.Utils.Helper" />
+        "#;
+
+        analyzer
+            .build_stack_graph_into(
+                &mut graph,
+                file,
+                Path::new("doc.xml"),
+                source,
+                &mut path_iter,
+                &globals,
+                &tree_sitter_stack_graphs::NoCancellation,
+            )
+            .unwrap();
+
+        let helper_nodes: Vec<_> = graph
+            .iter_nodes()
+            .filter(|&n| match graph[n].symbol() {
+                Some(sym) => &graph[sym] == "Helper",
+                None => false,
+            })
+            .collect();
+        assert_eq!(
+            helper_nodes.len(),
+            1,
+            "malformed member should be skipped without aborting the rest of the file"
+        );
+    }
+
+    #[test]
+    fn test_to_dot_renders_nodes_and_precedence_labelled_edges() {
+        let namespace = NodeInfo {
+            symbol: "System".to_string(),
+            syntax_type: SyntaxType::NamespaceDeclaration,
+            arity: None,
+        };
+        let class_def = NodeInfo {
+            symbol: "String".to_string(),
+            syntax_type: SyntaxType::ClassDef,
+            arity: None,
+        };
+        let edges = vec![
+            EdgeInfo {
+                source: namespace.clone(),
+                sink: class_def.clone(),
+                precedence: 0,
+            },
+            EdgeInfo {
+                source: class_def.clone(),
+                sink: namespace.clone(),
+                precedence: 10,
+            },
+        ];
+
+        let dot = to_dot(&[namespace, class_def], &edges);
+        assert!(dot.starts_with("digraph handle_member {\n"));
+        assert!(dot.contains("System"));
+        assert!(dot.contains("String"));
+        assert!(dot.contains("label=\"0\""));
+        assert!(dot.contains("label=\"10\""));
+    }
+
+    #[test]
+    fn test_handle_member_dot_renders_type_decomposition() {
+        let analyzer = create_analyzer();
+        let dot = analyzer.handle_member_dot("T", "System.String");
+        assert!(dot.contains("System"));
+        assert!(dot.contains("String"));
+        assert!(dot.contains("-> n"));
+    }
 }
@@ -7,7 +7,9 @@ use stack_graphs::{
 };
 use tracing::{debug, trace};
 
-use crate::c_sharp_graph::query::{get_fqdn, Fqdn, GetMatcher, Search, SymbolMatcher, SyntaxType};
+use crate::c_sharp_graph::query::{
+    fqdn_dotted_name, get_fqdn, Fqdn, GetMatcher, Search, SymbolMatcher, SyntaxType,
+};
 
 pub(crate) struct FieldSymbolsGetter {}
 
@@ -29,6 +31,11 @@ impl GetMatcher for FieldSymbolsGetter {
 
 pub(crate) struct FieldSymbols {
     fields: HashMap<Fqdn, Handle<Node>>,
+    /// Kept so [`SymbolMatcher::match_fqdn_captures`] can re-match a found
+    /// `Fqdn` against the original pattern and recover any `$name`
+    /// placeholder bindings -- `fields`'s keys alone only tell us *that* a
+    /// field matched, not what a placeholder in the search captured.
+    search: Search,
 }
 
 // Create exposed methods for NamesapceSymbols
@@ -48,7 +55,10 @@ impl FieldSymbols {
 
         debug!("field nodes found: {:?}", fields);
 
-        Ok(FieldSymbols { fields })
+        Ok(FieldSymbols {
+            fields,
+            search: search.clone(),
+        })
     }
 }
 
@@ -59,6 +69,9 @@ impl SymbolMatcher for FieldSymbols {
     fn match_fqdn(&self, fqdn: &Fqdn) -> bool {
         self.fields.contains_key(fqdn)
     }
+    fn match_fqdn_captures(&self, fqdn: &Fqdn) -> Option<HashMap<String, String>> {
+        self.search.match_fqdn_pattern(&fqdn_dotted_name(fqdn))
+    }
 }
 
 // Private methods for NamespaceSymbols
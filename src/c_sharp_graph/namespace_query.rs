@@ -12,8 +12,20 @@ use crate::c_sharp_graph::{
     field_query::FieldSymbols,
     method_query::MethodSymbols,
     query::{get_fqdn, Fqdn, GetMatcher, Search, SymbolMatcher, SyntaxType},
+    results::{Location, Position},
 };
 
+/// A single matching class/method/field/namespace declaration found by
+/// [`NamespaceSymbols::find_all`], carrying where it was found in addition
+/// to what matched -- the same shape an IDE reports "go to definition"
+/// results as navigation targets rather than a yes/no.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Match {
+    pub(crate) fqdn: Fqdn,
+    pub(crate) node: Handle<Node>,
+    pub(crate) location: Location,
+}
+
 #[derive(Debug, Clone)]
 pub struct NamespaceFQDNNotFoundError;
 
@@ -47,6 +59,22 @@ pub(crate) struct NamespaceSymbols {
     fields: FieldSymbols,
     methods: MethodSymbols,
     namespace: Fqdn,
+    /// The declared namespace's dotted segments (e.g. `["System",
+    /// "Configuration"]`), kept alongside the joined `namespace.namespace`
+    /// string so a query for a shorter namespace (`System`) can match
+    /// declarations nested under it (`System.Configuration`) without
+    /// re-splitting the string on every match.
+    namespace_segments: Vec<String>,
+    /// The definition root nodes this scope was built from, retained so
+    /// [`Self::find_all`] can re-walk the full subtree on demand instead of
+    /// only surfacing the first hit the way [`Self::traverse_node`] does
+    /// during construction.
+    roots: Vec<Handle<Node>>,
+    /// Kept so [`SymbolMatcher::match_fqdn_captures`] can recover `$name`
+    /// placeholder bindings for a namespace-only match, the same way
+    /// `classes`/`fields`/`methods` each retain their own copy for their
+    /// part of the pattern.
+    search: Search,
 }
 
 // Create exposed methods for NamesapceSymbols
@@ -62,8 +90,8 @@ impl NamespaceSymbols {
         let method_symbols = MethodSymbols::new(graph, nodes.clone(), search)?;
 
         let mut fqdn: Option<Fqdn> = None;
-        for node in nodes {
-            fqdn = Self::traverse_node(graph, node, search);
+        for node in &nodes {
+            fqdn = Self::traverse_node(graph, *node, search);
             if fqdn.is_some() {
                 break;
             }
@@ -71,24 +99,69 @@ impl NamespaceSymbols {
         if fqdn.is_none() {
             return Err(anyhow!(NamespaceFQDNNotFoundError {}));
         }
+        let fqdn = fqdn.unwrap();
+        let namespace_segments = fqdn
+            .namespace
+            .as_deref()
+            .map(|ns| ns.split('.').map(str::to_string).collect())
+            .unwrap_or_default();
 
         Ok(NamespaceSymbols {
             classes: class_symbol,
             fields: field_symbol,
             methods: method_symbols,
-            namespace: fqdn.unwrap(),
+            namespace: fqdn,
+            namespace_segments,
+            roots: nodes,
+            search: search.clone(),
         })
     }
+
+    /// Walk the full subtree reachable from every definition root this
+    /// scope was built from and collect every matching class/method/field/
+    /// namespace declaration as a [`Match`], instead of stopping at the
+    /// first hit the way [`Self::traverse_node`] does. Lets callers report
+    /// every matching location in a codebase rather than just confirming
+    /// one exists.
+    pub(crate) fn find_all(&self, graph: &StackGraph, search: &Search) -> Vec<Match> {
+        let mut matches = Vec::new();
+        for &root in &self.roots {
+            Self::collect_matches(graph, root, search, &mut matches);
+        }
+        matches
+    }
+
+    /// Structurally match `fqdn`'s reconstructed dotted name (e.g.
+    /// `System.Configuration.ConfigurationManager.GetSection`) against
+    /// `search`'s `**`/`$name` pattern, returning the captured placeholder
+    /// bindings on success. Built on [`Search::match_fqdn_pattern`] rather
+    /// than the sets this scope already collected, so it also works for
+    /// structural queries -- `$ns.ConfigurationManager.$method` -- that
+    /// [`SymbolMatcher::match_fqdn`]'s plain boolean can't express. Kept as a
+    /// separate method rather than changing `match_fqdn`'s return type, so
+    /// existing callers of the trait's boolean contract are unaffected.
+    pub(crate) fn match_fqdn_with_captures(
+        fqdn: &Fqdn,
+        search: &Search,
+    ) -> Option<std::collections::HashMap<String, String>> {
+        search.match_fqdn_pattern(&Self::dotted_fqdn(fqdn))
+    }
+
+    /// Join `fqdn`'s namespace/class/method/field parts (skipping absent
+    /// ones) into the dotted form a [`Search`] pattern is matched against.
+    fn dotted_fqdn(fqdn: &Fqdn) -> String {
+        [&fqdn.namespace, &fqdn.class, &fqdn.method, &fqdn.field]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(".")
+    }
 }
 
 impl SymbolMatcher for NamespaceSymbols {
     fn match_symbol(&self, symbol: String) -> bool {
-        if self
-            .namespace
-            .namespace
-            .clone()
-            .is_some_and(|x| x == symbol)
-        {
+        if self.match_namespace_segments(&symbol).is_some() {
             return true;
         }
         self.classes.match_symbol(symbol.clone())
@@ -97,15 +170,61 @@ impl SymbolMatcher for NamespaceSymbols {
     }
 
     fn match_fqdn(&self, fqdn: &Fqdn) -> bool {
+        if let Some(ns) = &fqdn.namespace {
+            if fqdn.class.is_none() && fqdn.method.is_none() && fqdn.field.is_none() {
+                return self.match_namespace_segments(ns).is_some();
+            }
+        }
         self.classes.match_fqdn(fqdn)
             || self.fields.match_fqdn(fqdn)
             || self.methods.match_fqdn(fqdn)
     }
+
+    fn match_fqdn_captures(&self, fqdn: &Fqdn) -> Option<std::collections::HashMap<String, String>> {
+        if let Some(ns) = &fqdn.namespace {
+            if fqdn.class.is_none() && fqdn.method.is_none() && fqdn.field.is_none() {
+                return self.search.match_fqdn_pattern(ns);
+            }
+        }
+        self.classes
+            .match_fqdn_captures(fqdn)
+            .or_else(|| self.fields.match_fqdn_captures(fqdn))
+            .or_else(|| self.methods.match_fqdn_captures(fqdn))
+    }
 }
 
 // Private methods for NamespaceSymbols
 impl NamespaceSymbols {
+    /// Whether `symbol`, split on `.`, is the declared namespace or a
+    /// dotted prefix of it (so a query for `System` matches a declared
+    /// `System.Configuration`). Returns `None` when `symbol` doesn't match
+    /// at all, `Some(true)` for an exact full-namespace match, and
+    /// `Some(false)` for a partial (prefix-only) match.
+    fn match_namespace_segments(&self, symbol: &str) -> Option<bool> {
+        let candidate: Vec<&str> = symbol.split('.').collect();
+        if candidate.is_empty() || candidate.len() > self.namespace_segments.len() {
+            return None;
+        }
+        let is_prefix = candidate
+            .iter()
+            .zip(self.namespace_segments.iter())
+            .all(|(a, b)| a == b);
+        if !is_prefix {
+            return None;
+        }
+        Some(candidate.len() == self.namespace_segments.len())
+    }
     fn traverse_node(db: &StackGraph, node: Handle<Node>, search: &Search) -> Option<Fqdn> {
+        let mut matches = Vec::new();
+        Self::collect_matches(db, node, search, &mut matches);
+        matches.into_iter().next().map(|m| m.fqdn)
+    }
+
+    /// Recursively collect every matching class/method/field/namespace
+    /// declaration under `node` into `matches`, in the same deterministic
+    /// (sorted-child) traversal order [`Self::traverse_node`] uses, but
+    /// without stopping at the first hit.
+    fn collect_matches(db: &StackGraph, node: Handle<Node>, search: &Search, matches: &mut Vec<Match>) {
         let mut child_edges: Vec<Handle<Node>> = vec![];
         for edge in db.outgoing_edges(node) {
             if edge.precedence == 10 {
@@ -128,7 +247,24 @@ impl NamespaceSymbols {
                         SyntaxType::NamespaceDeclaration
                         | SyntaxType::ClassDef
                         | SyntaxType::MethodName
-                        | SyntaxType::FieldName => return get_fqdn(edge.sink, db),
+                        | SyntaxType::FieldName => {
+                            if let Some(fqdn) = get_fqdn(edge.sink, db) {
+                                matches.push(Match {
+                                    fqdn,
+                                    node: edge.sink,
+                                    location: Location {
+                                        start_position: Position {
+                                            line: source_info.span.start.line,
+                                            character: source_info.span.start.column.utf8_offset,
+                                        },
+                                        end_position: Position {
+                                            line: source_info.span.end.line,
+                                            character: source_info.span.end.column.utf8_offset,
+                                        },
+                                    },
+                                });
+                            }
+                        }
 
                         _ => {}
                     },
@@ -138,18 +274,15 @@ impl NamespaceSymbols {
         // Sort child_edges to ensure deterministic traversal order
         child_edges.sort();
         for child_edge in child_edges {
-            if let Some(fqdn) = Self::traverse_node(db, child_edge, search) {
-                return Some(fqdn);
-            }
+            Self::collect_matches(db, child_edge, search, matches);
         }
-        None
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::c_sharp_graph::query::Search;
+    use crate::c_sharp_graph::query::{MatchMode, Search};
 
     /// Helper to build a comprehensive mock graph with namespace, class, method, and field
     fn build_mock_namespace_graph() -> (StackGraph, Vec<Handle<Node>>) {
@@ -276,6 +409,7 @@ mod tests {
             class: Some("ConfigurationManager".to_string()),
             method: None,
             field: None,
+            parameters: Vec::new(),
         };
 
         assert!(ns_symbols.match_fqdn(&fqdn));
@@ -292,6 +426,7 @@ mod tests {
             class: Some("ConfigurationManager".to_string()),
             method: Some("GetSection".to_string()),
             field: None,
+            parameters: Vec::new(),
         };
 
         assert!(ns_symbols.match_fqdn(&fqdn));
@@ -308,6 +443,7 @@ mod tests {
             class: Some("ConfigurationManager".to_string()),
             field: Some("AppSettings".to_string()),
             method: None,
+            parameters: Vec::new(),
         };
 
         assert!(ns_symbols.match_fqdn(&fqdn));
@@ -324,11 +460,132 @@ mod tests {
             class: Some("NonExistent".to_string()),
             method: None,
             field: None,
+            parameters: Vec::new(),
         };
 
         assert!(!ns_symbols.match_fqdn(&fqdn));
     }
 
+    #[test]
+    fn test_namespace_symbols_match_symbol_namespace_prefix() {
+        let (graph, roots) = build_mock_namespace_graph();
+        let search = Search::create_search("*".to_string()).unwrap();
+        let ns_symbols = NamespaceSymbols::new(&graph, roots, &search).unwrap();
+
+        // A query for the outer namespace segment should match the nested declaration.
+        assert!(ns_symbols.match_symbol("System".to_string()));
+    }
+
+    #[test]
+    fn test_namespace_symbols_match_namespace_segments_distinguishes_exact_and_prefix() {
+        let (graph, roots) = build_mock_namespace_graph();
+        let search = Search::create_search("*".to_string()).unwrap();
+        let ns_symbols = NamespaceSymbols::new(&graph, roots, &search).unwrap();
+
+        assert_eq!(ns_symbols.match_namespace_segments("System"), Some(false));
+        assert_eq!(
+            ns_symbols.match_namespace_segments("System.Configuration"),
+            Some(true)
+        );
+        assert_eq!(ns_symbols.match_namespace_segments("Other"), None);
+        assert_eq!(
+            ns_symbols.match_namespace_segments("System.Configuration.Extra"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_namespace_symbols_match_fqdn_namespace_only_prefix() {
+        let (graph, roots) = build_mock_namespace_graph();
+        let search = Search::create_search("*".to_string()).unwrap();
+        let ns_symbols = NamespaceSymbols::new(&graph, roots, &search).unwrap();
+
+        let fqdn = Fqdn {
+            namespace: Some("System".to_string()),
+            class: None,
+            method: None,
+            field: None,
+            parameters: Vec::new(),
+        };
+
+        assert!(ns_symbols.match_fqdn(&fqdn));
+    }
+
+    #[test]
+    fn test_find_all_collects_every_matching_declaration() {
+        let (graph, roots) = build_mock_namespace_graph();
+        let search = Search::create_search("*".to_string()).unwrap();
+        let ns_symbols = NamespaceSymbols::new(&graph, roots, &search).unwrap();
+
+        let matches = ns_symbols.find_all(&graph, &search);
+
+        // namespace, class, method, and field should each be found exactly once.
+        assert_eq!(matches.len(), 4);
+        assert!(matches
+            .iter()
+            .any(|m| m.fqdn.namespace == Some("System.Configuration".to_string())
+                && m.fqdn.class.is_none()));
+        assert!(matches
+            .iter()
+            .any(|m| m.fqdn.class == Some("ConfigurationManager".to_string())
+                && m.fqdn.method.is_none()
+                && m.fqdn.field.is_none()));
+        assert!(matches
+            .iter()
+            .any(|m| m.fqdn.method == Some("GetSection".to_string())));
+        assert!(matches
+            .iter()
+            .any(|m| m.fqdn.field == Some("AppSettings".to_string())));
+    }
+
+    #[test]
+    fn test_match_fqdn_with_captures_binds_named_placeholders() {
+        let fqdn = Fqdn {
+            namespace: Some("System.Configuration".to_string()),
+            class: Some("ConfigurationManager".to_string()),
+            method: Some("GetSection".to_string()),
+            field: None,
+            parameters: Vec::new(),
+        };
+        let search = Search::create_search("$ns.ConfigurationManager.$method".to_string()).unwrap();
+
+        let captures = NamespaceSymbols::match_fqdn_with_captures(&fqdn, &search).unwrap();
+
+        assert_eq!(
+            captures.get("ns"),
+            Some(&"System.Configuration".to_string())
+        );
+        assert_eq!(captures.get("method"), Some(&"GetSection".to_string()));
+    }
+
+    #[test]
+    fn test_match_fqdn_with_captures_supports_any_seq_wildcard() {
+        let fqdn = Fqdn {
+            namespace: Some("System.Configuration".to_string()),
+            class: Some("ConfigurationManager".to_string()),
+            method: Some("GetSection".to_string()),
+            field: None,
+            parameters: Vec::new(),
+        };
+        let search = Search::create_search("**.GetSection".to_string()).unwrap();
+
+        assert!(NamespaceSymbols::match_fqdn_with_captures(&fqdn, &search).is_some());
+    }
+
+    #[test]
+    fn test_match_fqdn_with_captures_returns_none_when_pattern_does_not_match() {
+        let fqdn = Fqdn {
+            namespace: Some("System.Configuration".to_string()),
+            class: Some("ConfigurationManager".to_string()),
+            method: Some("GetSection".to_string()),
+            field: None,
+            parameters: Vec::new(),
+        };
+        let search = Search::create_search("$ns.OtherClass.$method".to_string()).unwrap();
+
+        assert!(NamespaceSymbols::match_fqdn_with_captures(&fqdn, &search).is_none());
+    }
+
     #[test]
     fn test_namespace_symbols_error_when_no_namespace_found() {
         let mut graph = StackGraph::new();
@@ -345,4 +602,27 @@ mod tests {
         // Should return error when no namespace is found
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_namespace_symbols_match_symbol_fuzzy_tolerates_typo() {
+        let (graph, roots) = build_mock_namespace_graph();
+        let search =
+            Search::create_search_with_mode("ConfigurationManagr".to_string(), MatchMode::Fuzzy)
+                .unwrap();
+        let ns_symbols = NamespaceSymbols::new(&graph, roots, &search).unwrap();
+
+        assert!(ns_symbols.match_symbol("ConfigurationManager".to_string()));
+    }
+
+    #[test]
+    fn test_namespace_symbols_match_symbol_regex_mode() {
+        let (graph, roots) = build_mock_namespace_graph();
+        let search =
+            Search::create_search_with_mode("Config.*Manager".to_string(), MatchMode::Regex)
+                .unwrap();
+        let ns_symbols = NamespaceSymbols::new(&graph, roots, &search).unwrap();
+
+        assert!(ns_symbols.match_symbol("ConfigurationManager".to_string()));
+        assert!(!ns_symbols.match_symbol("AppSettings".to_string()));
+    }
 }
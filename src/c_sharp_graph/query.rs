@@ -3,7 +3,8 @@ use std::{
     vec,
 };
 
-use anyhow::{Error, Ok};
+use anyhow::{anyhow, Error, Ok};
+use rayon::prelude::*;
 use regex::Regex;
 use serde_json::Value;
 use stack_graphs::{
@@ -15,18 +16,21 @@ use url::Url;
 
 use crate::c_sharp_graph::{
     class_query::ClassSymbolsGetter,
+    dependency_xml_analyzer::REFERENCE_PRECEDENCE,
     field_query::FieldSymbolsGetter,
     loader::SourceType,
     method_query::MethodSymbolsGetter,
     namespace_query::NamespaceSymbolsGetter,
     results::{Location, Position, ResultNode},
+    search_grammar::{parse_and_validate, NodeKind, QueryPlan, ValueMatch},
+    symbol_index::SymbolIndex,
 };
 
 pub trait Query {
-    fn query(self, query: String) -> anyhow::Result<Vec<ResultNode>, Error>;
+    fn query(self, query: String, mode: MatchMode) -> anyhow::Result<Vec<ResultNode>, Error>;
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum SyntaxType {
     Import,
     CompUnit,
@@ -34,6 +38,7 @@ pub enum SyntaxType {
     ClassDef,
     MethodName,
     FieldName,
+    EventName,
     LocalVar,
     Argument,
     Name,
@@ -48,6 +53,7 @@ impl SyntaxType {
             "class_def" => Self::ClassDef,
             "method_name" => Self::MethodName,
             "field_name" => Self::FieldName,
+            "event_name" => Self::EventName,
             "local_var" => Self::LocalVar,
             "argument" => Self::Argument,
             "name" => Self::Name,
@@ -57,12 +63,62 @@ impl SyntaxType {
     }
 }
 
-#[derive(Eq, Hash, PartialEq, Debug)]
+#[derive(Eq, Hash, PartialEq, Debug, Clone)]
 pub(crate) struct Fqdn {
     pub(crate) namespace: Option<String>,
     pub(crate) class: Option<String>,
     pub(crate) method: Option<String>,
     pub(crate) field: Option<String>,
+    /// Ordered, dotted FQDNs of a method's parameter types, so two
+    /// same-named overloads can be told apart. Empty for non-methods and
+    /// for parameterless methods.
+    pub(crate) parameters: Vec<String>,
+}
+
+/// The dotted type name a parameter/return-type reference edge points at
+/// (e.g. `System.Collections.Generic.List`), joining `fqdn`'s namespace and
+/// class segments the same way a `T:` member ID would have spelled it.
+fn dotted_type_name(fqdn: &Fqdn) -> String {
+    [&fqdn.namespace, &fqdn.class]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// `fqdn`'s namespace/class/method/field parts, joined into the dotted form
+/// a [`Search`] pattern is matched against -- the same shape
+/// [`Search::match_fqdn_pattern`] expects, used by matchers that want to
+/// capture `$name` placeholder bindings from the pattern that matched
+/// rather than just a bare `bool`.
+pub(crate) fn fqdn_dotted_name(fqdn: &Fqdn) -> String {
+    [&fqdn.namespace, &fqdn.class, &fqdn.method, &fqdn.field]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// A method node's parameter types, in declaration order. Parameter/return
+/// reference edges (precedence [`REFERENCE_PRECEDENCE`]) aren't ordered by
+/// the graph itself, so this sorts by sink `Handle`, which -- like
+/// `SymbolIndex::collect`'s `child_edges.sort()` -- tracks creation order
+/// closely enough to reconstruct the declaration order the XML-doc ID was
+/// parsed in.
+fn method_parameters(node: Handle<Node>, graph: &StackGraph) -> Vec<String> {
+    let mut params: Vec<Handle<Node>> = graph
+        .outgoing_edges(node)
+        .filter(|e| e.precedence == REFERENCE_PRECEDENCE)
+        .map(|e| e.sink)
+        .collect();
+    params.sort();
+    params
+        .into_iter()
+        .filter_map(|sink| get_fqdn(sink, graph))
+        .map(|f| dotted_type_name(&f))
+        .collect()
 }
 
 pub(crate) fn get_fqdn(node: Handle<Node>, graph: &StackGraph) -> Option<Fqdn> {
@@ -71,6 +127,7 @@ pub(crate) fn get_fqdn(node: Handle<Node>, graph: &StackGraph) -> Option<Fqdn> {
         class: None,
         method: None,
         field: None,
+        parameters: Vec::new(),
     };
     // traverse upwards based on the FQDN edge
     // Once there is no FQDN edge, return
@@ -96,6 +153,7 @@ pub(crate) fn get_fqdn(node: Handle<Node>, graph: &StackGraph) -> Option<Fqdn> {
             }
             SyntaxType::MethodName => {
                 fqdn.method = Some(symbol);
+                fqdn.parameters = method_parameters(node, graph);
                 Some(fqdn)
             }
             SyntaxType::ClassDef => {
@@ -123,6 +181,7 @@ pub(crate) fn get_fqdn(node: Handle<Node>, graph: &StackGraph) -> Option<Fqdn> {
                         || Some(symbol.clone()),
                         |m| Some(format!("{}.{}", m, symbol.clone())),
                     );
+                    f.parameters = method_parameters(node, graph);
                     Some(f)
                 }
                 SyntaxType::ClassDef => {
@@ -145,6 +204,54 @@ pub(crate) fn get_fqdn(node: Handle<Node>, graph: &StackGraph) -> Option<Fqdn> {
     }
 }
 
+/// Bounded Levenshtein edit distance between `pattern` and `candidate`,
+/// computed over a band of width `2 * max_distance + 1` around the
+/// diagonal and bailing out as soon as an entire row exceeds
+/// `max_distance`, instead of always completing the full O(len * len)
+/// table -- a match candidate that's wildly different from the pattern
+/// should cost O(max_distance) to reject, not O(len(candidate)).
+fn levenshtein_within(pattern: &str, candidate: &str, max_distance: usize) -> Option<usize> {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let candidate: Vec<char> = candidate.chars().collect();
+    if pattern.len().abs_diff(candidate.len()) > max_distance {
+        return None;
+    }
+
+    let sentinel = max_distance + 1;
+    let mut prev: Vec<usize> = (0..=candidate.len())
+        .map(|j| if j <= max_distance { j } else { sentinel })
+        .collect();
+
+    for i in 1..=pattern.len() {
+        let lo = i.saturating_sub(max_distance).max(1);
+        let hi = (i + max_distance).min(candidate.len());
+        let mut curr = vec![sentinel; candidate.len() + 1];
+        if i <= max_distance {
+            curr[0] = i;
+        }
+        let mut row_min = curr[0];
+        for j in lo..=hi {
+            let cost = usize::from(pattern[i - 1] != candidate[j - 1]);
+            let deletion = prev[j] + 1;
+            let insertion = curr[j - 1] + 1;
+            let substitution = prev[j - 1] + cost;
+            curr[j] = deletion.min(insertion).min(substitution);
+            row_min = row_min.min(curr[j]);
+        }
+        if row_min > max_distance {
+            return None;
+        }
+        prev = curr;
+    }
+
+    let distance = prev[candidate.len()];
+    if distance <= max_distance {
+        Some(distance)
+    } else {
+        None
+    }
+}
+
 pub enum QueryType<'graph> {
     All {
         graph: &'graph StackGraph,
@@ -165,7 +272,7 @@ pub enum QueryType<'graph> {
 }
 
 impl Query for QueryType<'_> {
-    fn query(self, query: String) -> anyhow::Result<Vec<ResultNode>, Error> {
+    fn query(self, query: String, mode: MatchMode) -> anyhow::Result<Vec<ResultNode>, Error> {
         match self {
             QueryType::All { graph, source_type } => {
                 let q = Querier {
@@ -173,7 +280,11 @@ impl Query for QueryType<'_> {
                     source_type,
                     _matcher_getter: NamespaceSymbolsGetter {},
                 };
-                q.query(query)
+                let query = typed_query_to_dotted(
+                    query,
+                    &[NodeKind::Namespace, NodeKind::Class, NodeKind::Method, NodeKind::Field],
+                )?;
+                q.query(query, mode)
             }
             QueryType::Method { graph, source_type } => {
                 info!("running method search");
@@ -182,7 +293,9 @@ impl Query for QueryType<'_> {
                     source_type,
                     _matcher_getter: MethodSymbolsGetter {},
                 };
-                q.query(query)
+                let query =
+                    typed_query_to_dotted(query, &[NodeKind::Namespace, NodeKind::Class, NodeKind::Method])?;
+                q.query(query, mode)
             }
             QueryType::Field { graph, source_type } => {
                 let q = Querier {
@@ -190,7 +303,9 @@ impl Query for QueryType<'_> {
                     source_type,
                     _matcher_getter: FieldSymbolsGetter {},
                 };
-                q.query(query)
+                let query =
+                    typed_query_to_dotted(query, &[NodeKind::Namespace, NodeKind::Class, NodeKind::Field])?;
+                q.query(query, mode)
             }
             QueryType::Class { graph, source_type } => {
                 let q = Querier {
@@ -198,12 +313,52 @@ impl Query for QueryType<'_> {
                     source_type,
                     _matcher_getter: ClassSymbolsGetter {},
                 };
-                q.query(query)
+                let query = typed_query_to_dotted(query, &[NodeKind::Namespace, NodeKind::Class])?;
+                q.query(query, mode)
             }
         }
     }
 }
 
+/// Validates and translates a typed `kind:value` query into the plain dotted
+/// string [`Search::create_search_with_mode`] knows how to parse, dropping
+/// each segment's `kind:` prefix. A `:` can't appear in a plain dotted symbol
+/// pattern (see `Search::create_search_with_mode`'s own segment parsing), so
+/// its presence means the caller used the typed grammar instead of a plain
+/// pattern like `System.Windows.*`; a plain pattern is passed through
+/// unchanged. `allowed_kinds` is the set of [`NodeKind`]s that make sense for
+/// the query type this plan is headed for -- e.g. a method search accepts
+/// `namespace:`/`class:` segments leading up to a trailing `method:` one, but
+/// not a `field:` one. A [`ValueMatch::Regex`] segment is rejected too, since
+/// `Search` only supports a whole-pattern regex via [`MatchMode::Regex`], not
+/// one anchored to a single dotted segment.
+fn typed_query_to_dotted(query: String, allowed_kinds: &[NodeKind]) -> anyhow::Result<String, Error> {
+    if !query.contains(':') {
+        return Ok(query);
+    }
+
+    let plan = parse_and_validate(&query)?;
+    let mut segments = Vec::with_capacity(plan.nodes.len());
+    for node in &plan.nodes {
+        if !allowed_kinds.contains(&node.kind) {
+            return Err(anyhow!(
+                "this search only accepts {allowed_kinds:?} segments, got {:?}",
+                node.kind
+            ));
+        }
+        match &node.value {
+            ValueMatch::Literal(v) => segments.push(v.clone()),
+            ValueMatch::Wildcard => segments.push("*".to_string()),
+            ValueMatch::Regex(pattern) => {
+                return Err(anyhow!(
+                    "this search doesn't support a per-segment regex (/{pattern}/); use MatchMode::Regex over the whole query instead"
+                ));
+            }
+        }
+    }
+    Ok(segments.join("."))
+}
+
 pub(crate) struct Querier<'graph, T: GetMatcher> {
     pub(crate) graph: &'graph StackGraph,
     pub(crate) source_type: &'graph SourceType,
@@ -218,8 +373,8 @@ pub(crate) struct StartingNodes {
 }
 
 impl<'a, T: GetMatcher> Querier<'a, T> {
-    pub(crate) fn get_search(&self, query: String) -> anyhow::Result<Search, Error> {
-        Search::create_search(query)
+    pub(crate) fn get_search(&self, query: String, mode: MatchMode) -> anyhow::Result<Search, Error> {
+        Search::create_search_with_mode(query, mode)
     }
 
     pub(crate) fn get_starting_nodes(&self, search: &Search) -> StartingNodes {
@@ -231,53 +386,65 @@ impl<'a, T: GetMatcher> Querier<'a, T> {
         // declaration. then we need to capture all the nodes that are definitions attached to
         // this (for instance namespace System.Configuration; Class ConfigurationManager; method
         // AppSettings)
-        let mut definition_root_nodes: Vec<Handle<Node>> = vec![];
-        let mut referenced_files: HashSet<Handle<File>> = HashSet::new();
-        let mut file_to_compunit_handle: HashMap<Handle<File>, Handle<Node>> = HashMap::new();
+        //
+        // This is an O(nodes) scan over the whole graph, so (mirroring
+        // rust-analyzer's use of rayon over its symbol/file sets) it's
+        // partitioned across a rayon parallel iterator: each thread folds
+        // its share of nodes into its own partial `StartingNodes`-shaped
+        // tuple, and the partials are merged once at the end.
+        type Partial = (
+            Vec<Handle<Node>>,
+            HashSet<Handle<File>>,
+            HashMap<Handle<File>, Handle<Node>>,
+        );
 
-        for node_handle in self.graph.iter_nodes() {
-            let node: &Node = &self.graph[node_handle];
-            let file_handle = match node.file() {
-                Some(h) => h,
-                None => {
-                    continue;
-                }
-            };
-            let symbol_option = node.symbol();
-            if symbol_option.is_none() {
-                // If the node doesn't have a symbol to look at, then we should continue and it
-                // only used to tie together other nodes.
-                continue;
-            }
-            let symbol = &self.graph[node.symbol().unwrap()];
-            let source_info = self.graph.source_info(node_handle);
-            if source_info.is_none() {
-                continue;
-            }
-            match source_info.unwrap().syntax_type.into_option() {
-                None => continue,
-                Some(handle) => {
+        let node_handles: Vec<Handle<Node>> = self.graph.iter_nodes().collect();
+        let (definition_root_nodes, referenced_files, file_to_compunit_handle): Partial =
+            node_handles
+                .par_iter()
+                .fold(Partial::default, |mut acc, &node_handle| {
+                    let node: &Node = &self.graph[node_handle];
+                    let Some(file_handle) = node.file() else {
+                        return acc;
+                    };
+                    let Some(symbol_handle) = node.symbol() else {
+                        // If the node doesn't have a symbol to look at, then we should continue
+                        // and it only used to tie together other nodes.
+                        return acc;
+                    };
+                    let symbol = &self.graph[symbol_handle];
+                    let Some(source_info) = self.graph.source_info(node_handle) else {
+                        return acc;
+                    };
+                    let Some(handle) = source_info.syntax_type.into_option() else {
+                        return acc;
+                    };
                     let syntax_type = SyntaxType::get(&self.graph[handle]);
                     match syntax_type {
                         SyntaxType::CompUnit => {
-                            file_to_compunit_handle.insert(file_handle, node_handle);
+                            acc.2.insert(file_handle, node_handle);
                         }
                         SyntaxType::Import => {
                             if search.partial_namespace(symbol) {
-                                referenced_files.insert(file_handle);
+                                acc.1.insert(file_handle);
                             }
                         }
                         SyntaxType::NamespaceDeclaration => {
                             if search.match_namespace(symbol) {
-                                definition_root_nodes.push(node_handle);
-                                referenced_files.insert(file_handle);
+                                acc.0.push(node_handle);
+                                acc.1.insert(file_handle);
                             }
                         }
-                        _ => continue,
+                        _ => {}
                     }
-                }
-            }
-        }
+                    acc
+                })
+                .reduce(Partial::default, |mut a, b| {
+                    a.0.extend(b.0);
+                    a.1.extend(b.1);
+                    a.2.extend(b.2);
+                    a
+                });
 
         StartingNodes {
             definition_root_nodes,
@@ -286,18 +453,160 @@ impl<'a, T: GetMatcher> Querier<'a, T> {
         }
     }
 
+    /// Every reference node across the whole graph that resolves (via
+    /// [`Querier::get_type_with_symbol`]) to the exact same [`Fqdn`] as the
+    /// definition found at `file_uri`:`position` -- i.e. every use site of
+    /// that one specific symbol, not just symbols whose name happens to
+    /// match a text pattern. Mirrors rust-analyzer's "find all references"
+    /// starting from a definition, so Konveyor rules can answer "where is
+    /// this used" rather than only "where does this pattern appear."
+    pub(crate) fn find_references(
+        &self,
+        file_uri: &str,
+        position: &Position,
+    ) -> anyhow::Result<Vec<ResultNode>, Error> {
+        let path = Url::parse(file_uri)
+            .map_err(|e| anyhow!("invalid file URI {}: {}", file_uri, e))?
+            .to_file_path()
+            .map_err(|_| anyhow!("file URI {} is not a file path", file_uri))?;
+        let path = path
+            .to_str()
+            .ok_or_else(|| anyhow!("file path for {} is not valid UTF-8", file_uri))?;
+        let file = self
+            .graph
+            .get_file(path)
+            .ok_or_else(|| anyhow!("no file loaded for {}", file_uri))?;
+
+        let definition_node = self
+            .find_definition_at(file, position)
+            .ok_or_else(|| anyhow!("no definition found at {}:{:?}", file_uri, position))?;
+        let target = get_fqdn(definition_node, self.graph).ok_or_else(|| {
+            anyhow!(
+                "unable to compute FQDN for definition at {}:{:?}",
+                file_uri,
+                position
+            )
+        })?;
+
+        // Built once rather than per-reference, the same way `query` avoids
+        // rescanning the whole graph for every reference node it resolves.
+        let symbol_index = SymbolIndex::build_global(self.graph);
+
+        let mut results = Vec::new();
+        for node_handle in self.graph.iter_nodes() {
+            let node = &self.graph[node_handle];
+            if !node.is_reference() {
+                continue;
+            }
+            let Some(symbol_handle) = node.symbol() else {
+                continue;
+            };
+            let symbol = &self.graph[symbol_handle];
+            let Some(fqdn) = self.get_type_with_symbol(node_handle, symbol, &symbol_index, None)
+            else {
+                continue;
+            };
+            if fqdn != target {
+                continue;
+            }
+            let Some(ref_file) = node.file() else {
+                continue;
+            };
+            let Some(source_info) = self.graph.source_info(node_handle) else {
+                continue;
+            };
+            if source_info.span.start.as_point() == source_info.span.end.as_point() {
+                continue;
+            }
+            let f = &self.graph[ref_file];
+            let Ok(ref_url) = Url::from_file_path(f.name()) else {
+                continue;
+            };
+            let file_uri = ref_url.as_str().to_string();
+            let var: BTreeMap<String, Value> =
+                BTreeMap::from([("file".to_string(), Value::from(file_uri.clone()))]);
+            results.push(ResultNode {
+                file_uri,
+                line_number: source_info.span.start.line,
+                code_location: Location {
+                    start_position: Position {
+                        line: source_info.span.start.line,
+                        character: source_info.span.start.column.utf8_offset,
+                    },
+                    end_position: Position {
+                        line: source_info.span.end.line,
+                        character: source_info.span.end.column.utf8_offset,
+                    },
+                },
+                variables: var,
+            });
+        }
+        Ok(results)
+    }
+
+    /// The innermost definition node in `file` whose source span contains
+    /// `position`, so [`Querier::find_references`] can seed its search from
+    /// exactly the symbol the caller pointed at rather than whatever
+    /// definition happens to contain that point first.
+    fn find_definition_at(&self, file: Handle<File>, position: &Position) -> Option<Handle<Node>> {
+        let point = (position.line, position.character);
+        let mut best: Option<(Handle<Node>, (usize, usize))> = None;
+        for node_handle in self.graph.nodes_for_file(file) {
+            let node = &self.graph[node_handle];
+            if !node.is_definition() {
+                continue;
+            }
+            let Some(source_info) = self.graph.source_info(node_handle) else {
+                continue;
+            };
+            let start = (
+                source_info.span.start.line,
+                source_info.span.start.column.utf8_offset,
+            );
+            let end = (
+                source_info.span.end.line,
+                source_info.span.end.column.utf8_offset,
+            );
+            if point < start || point > end {
+                continue;
+            }
+            let span_size = (end.0 - start.0, end.1.saturating_sub(start.1));
+            let is_smaller = match best {
+                None => true,
+                Some((_, best_size)) => span_size < best_size,
+            };
+            if is_smaller {
+                best = Some((node_handle, span_size));
+            }
+        }
+        best.map(|(handle, _)| handle)
+    }
+
+    /// Returns its own matches rather than pushing into a shared
+    /// accumulator, so each file's traversal is independent and can run on
+    /// its own rayon thread in [`Querier::query`] without any shared
+    /// mutable state.
     pub(crate) fn traverse_node_search(
         &self,
         node: Handle<Node>,
         symbol_matcher: &T::Matcher,
-        results: &mut Vec<ResultNode>,
+        search: &Search,
         file_uri: String,
-    ) {
+        symbol_index: &SymbolIndex,
+        mut trace: Option<&mut QueryTrace>,
+    ) -> Vec<ResultNode> {
+        let mut results: Vec<ResultNode> = vec![];
         let mut traverse_nodes: Vec<Handle<Node>> = vec![];
+        if let Some(t) = trace.as_deref_mut() {
+            t.record_node(node, self.graph);
+        }
         for edge in self.graph.outgoing_edges(node) {
             if edge.precedence == 10 {
                 continue;
             }
+            if let Some(t) = trace.as_deref_mut() {
+                t.record_edge(edge, self.graph);
+            }
             traverse_nodes.push(edge.sink);
             let child_node = &self.graph[edge.sink];
             let symbol = match child_node.symbol() {
@@ -306,8 +615,15 @@ impl<'a, T: GetMatcher> Querier<'a, T> {
                 }
                 Some(handle) => &self.graph[handle],
             };
+            let mut match_detail: Option<MatchDetail> = None;
+            let mut fqdn_captures: Option<HashMap<String, String>> = None;
             if child_node.is_reference() {
-                let full_symbol = self.get_type_with_symbol(edge.sink, symbol);
+                let full_symbol = self.get_type_with_symbol(
+                    edge.sink,
+                    symbol,
+                    symbol_index,
+                    trace.as_deref_mut(),
+                );
                 if full_symbol.is_none() {
                     continue;
                 }
@@ -316,8 +632,15 @@ impl<'a, T: GetMatcher> Querier<'a, T> {
                 if !symbol_matcher.match_fqdn(&full_symbol) {
                     continue;
                 }
+                fqdn_captures = symbol_matcher.match_fqdn_captures(&full_symbol);
             } else if !symbol_matcher.match_symbol(symbol.to_string()) {
                 continue;
+            } else {
+                // symbol_matcher.match_symbol already confirmed a match; this
+                // re-derives the matched text/distance to surface in the
+                // result's variables, since `SymbolMatcher`'s boolean contract
+                // doesn't carry that detail through.
+                match_detail = search.match_detail(symbol);
             }
             let debug_node = self.graph.node_debug_info(edge.sink).map_or(vec![], |d| {
                 d.iter()
@@ -373,8 +696,20 @@ impl<'a, T: GetMatcher> Querier<'a, T> {
                     //}
                 }
             }
-            let var: BTreeMap<String, Value> =
+            let mut var: BTreeMap<String, Value> =
                 BTreeMap::from([("file".to_string(), Value::from(file_uri.clone()))]);
+            if let Some(detail) = match_detail {
+                var.insert(
+                    "matched_name".to_string(),
+                    Value::from(detail.matched_name),
+                );
+                var.insert("distance".to_string(), Value::from(detail.distance));
+            }
+            if let Some(captures) = fqdn_captures {
+                for (name, value) in captures {
+                    var.insert(format!("capture_{name}"), Value::from(value));
+                }
+            }
             //if let Some(line) = line {
             //   var.insert("line".to_string(), Value::from(line.trim()));
             //}
@@ -383,6 +718,9 @@ impl<'a, T: GetMatcher> Querier<'a, T> {
                 debug_node,
                 edge_debug
             );
+            if let Some(t) = trace.as_deref_mut() {
+                t.record_match(edge.sink);
+            }
             results.push(ResultNode {
                 file_uri: file_uri.clone(),
                 line_number,
@@ -391,99 +729,44 @@ impl<'a, T: GetMatcher> Querier<'a, T> {
             });
         }
         for n in traverse_nodes {
-            self.traverse_node_search(n, symbol_matcher, results, file_uri.clone());
+            results.extend(self.traverse_node_search(
+                n,
+                symbol_matcher,
+                search,
+                file_uri.clone(),
+                symbol_index,
+                trace.as_deref_mut(),
+            ));
         }
+        results
     }
 
     // Note: This function will only work, on the memeber_access_expresssion
-    fn get_type_with_symbol(&self, node: Handle<Node>, symbol: &str) -> Option<Fqdn> {
+    fn get_type_with_symbol(
+        &self,
+        node: Handle<Node>,
+        symbol: &str,
+        symbol_index: &SymbolIndex,
+        mut trace: Option<&mut QueryTrace>,
+    ) -> Option<Fqdn> {
         let parts: Vec<&str> = symbol.split(".").collect();
-        if parts.len() != 2 {
+        if parts.len() < 2 {
             return None;
         }
-        let accessed_part = parts
-            .last()
-            .expect("unable to get method part for symbol")
-            .to_string();
-        let accessor = parts
-            .first()
-            .expect("unable to get class part for symbol")
-            .to_string();
-
-        // Find the symbol that matches the accessor
-        let get_symbol_handle_for_accessor = self
-            .graph
-            .iter_symbols()
-            .find(|s| accessor == self.graph[*s])?;
-        // Find the node that is the defintion of the symbol.
-        let nodes_for_defines_symbol: Vec<Handle<Node>> = self
-            .graph
-            .iter_nodes()
-            .filter(|f| {
-                let n = &self.graph[*f];
-                if !n.is_definition() {
-                    return false;
-                }
-                let s = n.symbol();
-                if s.is_none() {
-                    return false;
-                }
+        let accessor = parts[0].to_string();
+        let segments = &parts[1..];
 
-                s.unwrap() == get_symbol_handle_for_accessor
-            })
-            .collect();
-
-        debug!(
-            "looking for correct definition for {}-{}",
-            accessor, accessed_part
-        );
         let access_node = &self.graph[node];
-        for definition_node in nodes_for_defines_symbol {
-            let source_info = &self.graph.source_info(definition_node);
-            if source_info.is_none() {
-                continue;
-            }
-            let syntax_type = source_info.unwrap().syntax_type;
-            if syntax_type.is_none() {
-                debug!(
-                    "no syntax_type for node: {}",
-                    definition_node.display(self.graph)
-                );
-                continue;
-            }
-            let syntax_type = syntax_type.into_option().unwrap();
-            let syntax_type = &self.graph[syntax_type];
+        let file = access_node.file()?;
 
-            let fqdn = match SyntaxType::get(syntax_type) {
-                SyntaxType::ClassDef => {
-                    let found_edge = self.graph.outgoing_edges(definition_node).find(|e| {
-                        let sink = &self.graph[e.sink];
-                        trace!("testing sink: {}", sink.display(self.graph));
-                        match sink.symbol() {
-                            Some(sym) => self.graph[sym] == accessed_part,
-                            None => false,
-                        }
-                    })?;
-                    get_fqdn(found_edge.sink, self.graph)
-                }
-                SyntaxType::FieldName | SyntaxType::MethodName => {
-                    get_fqdn(definition_node, self.graph)
-                }
-                SyntaxType::LocalVar => {
-                    self.get_local_var_type_fqdn(
-                        definition_node,
-                        &accessed_part,
-                        access_node.file()?,
-                    )
-                    // When the symbol is defined by a local variable
-                    // then we need to find the local var type.
-                }
-                _ => None,
-            };
+        debug!("looking for correct definition for {}", symbol);
+        for entry in symbol_index.lookup_exact(&accessor) {
+            let fqdn =
+                self.resolve_member_path(entry.node, segments, file, symbol_index, trace.as_deref_mut());
             debug!(
                 "found: {:?} for node: {}",
                 fqdn,
-                definition_node.display(self.graph)
+                entry.node.display(self.graph)
             );
             if fqdn.is_some() {
                 return fqdn;
@@ -492,17 +775,101 @@ impl<'a, T: GetMatcher> Querier<'a, T> {
         None
     }
 
-    fn get_local_var_type_fqdn(
+    /// Walks `segments` one member-access hop at a time starting from
+    /// `definition_node` (what the leftmost accessor resolved to), so chains
+    /// like `ConfigurationManager.AppSettings.Count` resolve instead of only
+    /// two-part accesses. The first hop dispatches on `definition_node`'s own
+    /// syntax type exactly like the old two-part-only lookup did; every hop
+    /// after that re-resolves through the previous member's declared type via
+    /// [`Querier::find_member_of_declared_type`] to keep walking. Bails with
+    /// `None` as soon as a hop can't find a matching member edge, so a
+    /// partial chain never produces a wrong match.
+    fn resolve_member_path(
         &self,
         definition_node: Handle<Node>,
-        accessed_part_symbol: &str,
+        segments: &[&str],
         file: Handle<File>,
+        symbol_index: &SymbolIndex,
+        mut trace: Option<&mut QueryTrace>,
     ) -> Option<Fqdn> {
-        let def_node = &self.graph[definition_node];
-        if !def_node.is_in_file(file) {
-            return None;
+        let (first_segment, rest) = segments.split_first()?;
+
+        let source_info = self.graph.source_info(definition_node)?;
+        let syntax_type = source_info.syntax_type.into_option()?;
+        let syntax_type = SyntaxType::get(&self.graph[syntax_type]);
+
+        let mut member_node = match syntax_type {
+            SyntaxType::ClassDef => self.find_member_edge(definition_node, first_segment)?,
+            SyntaxType::FieldName | SyntaxType::MethodName => {
+                // The accessor already names this member directly, so there's
+                // no member edge to walk from here; this only resolves when
+                // it's also the last segment.
+                return if rest.is_empty() {
+                    if let Some(t) = trace.as_deref_mut() {
+                        record_fqdn_trace(definition_node, self.graph, t);
+                    }
+                    get_fqdn(definition_node, self.graph)
+                } else {
+                    None
+                };
+            }
+            SyntaxType::LocalVar => {
+                if !self.graph[definition_node].is_in_file(file) {
+                    return None;
+                }
+                self.find_member_of_declared_type(
+                    definition_node,
+                    first_segment,
+                    symbol_index,
+                    trace.as_deref_mut(),
+                )?
+            }
+            _ => return None,
+        };
+
+        for segment in rest {
+            member_node = self.find_member_of_declared_type(
+                member_node,
+                segment,
+                symbol_index,
+                trace.as_deref_mut(),
+            )?;
+        }
+
+        if let Some(t) = trace.as_deref_mut() {
+            record_fqdn_trace(member_node, self.graph, t);
         }
-        let type_ref_node = self.graph.outgoing_edges(definition_node).find_map(|e| {
+        get_fqdn(member_node, self.graph)
+    }
+
+    /// The direct outgoing edge of `owner_node` whose sink's symbol is
+    /// exactly `segment` -- e.g. a class's `AppSettings` member.
+    fn find_member_edge(&self, owner_node: Handle<Node>, segment: &str) -> Option<Handle<Node>> {
+        let found_edge = self.graph.outgoing_edges(owner_node).find(|e| {
+            let sink = &self.graph[e.sink];
+            trace!("testing sink: {}", sink.display(self.graph));
+            match sink.symbol() {
+                Some(sym) => self.graph[sym] == *segment,
+                None => false,
+            }
+        })?;
+        Some(found_edge.sink)
+    }
+
+    /// Given a node with a declared type -- a local variable, or a
+    /// field/property reached mid-chain -- follows its outgoing
+    /// type-reference edge to that type's definition, then looks up the
+    /// member named `segment` on it. This is the two-step resolution the
+    /// old local-variable-only lookup did for its single hop, generalized so
+    /// every subsequent hop in [`Querier::resolve_member_path`] can reuse it.
+    fn find_member_of_declared_type(
+        &self,
+        owner_node: Handle<Node>,
+        segment: &str,
+        symbol_index: &SymbolIndex,
+        mut trace: Option<&mut QueryTrace>,
+    ) -> Option<Handle<Node>> {
+        let type_ref_node = self.graph.outgoing_edges(owner_node).find_map(|e| {
             let edge_node = &self.graph[e.sink];
             if edge_node.is_reference() {
                 Some(edge_node)
@@ -511,77 +878,165 @@ impl<'a, T: GetMatcher> Querier<'a, T> {
             }
         })?;
         let ref_symbol = type_ref_node.symbol()?;
-        debug!(
-            "searching for defintion for type_ref_node: {}",
-            type_ref_node.display(self.graph)
-        );
-        let defined_node = self.graph.iter_nodes().find_map(|x| {
-            let node = &self.graph[x];
-            if node.symbol().is_none() || node.symbol().unwrap() != ref_symbol {
-                return None;
-            }
-            debug!(
-                "found defined node, checking edges for symbols that match the accessed_part: {}",
-                node.display(self.graph)
-            );
-            // Determine if it has any accessable parts that are the accessed_part
-            let found_edge = self.graph.outgoing_edges(x).find(|e| {
-                let sink = &self.graph[e.sink];
-                trace!("testing sink: {}", sink.display(self.graph));
-                match sink.symbol() {
-                    Some(sym) => &self.graph[sym] == accessed_part_symbol,
-                    None => false,
+        let ref_symbol = self.graph[ref_symbol].to_string();
+        debug!("searching for defintion for type: {}", ref_symbol);
+
+        for entry in symbol_index.lookup_exact(&ref_symbol) {
+            if let Some(member) = self.find_member_edge(entry.node, segment) {
+                if let Some(t) = trace.as_deref_mut() {
+                    t.record_node(entry.node, self.graph);
+                    t.record_node(member, self.graph);
                 }
-            })?;
-            Some(found_edge.sink)
-        })?;
-        get_fqdn(defined_node, self.graph)
+                return Some(member);
+            }
+        }
+        None
     }
 }
 
 impl<'graph, T: GetMatcher> Query for Querier<'graph, T> {
-    fn query(self, query: String) -> anyhow::Result<Vec<ResultNode>, Error> {
-        let search: Search = self.get_search(query)?;
+    fn query(self, query: String, mode: MatchMode) -> anyhow::Result<Vec<ResultNode>, Error> {
+        let search: Search = self.get_search(query, mode)?;
 
         debug!("search: {:?}", search);
 
-        let mut results: Vec<ResultNode> = vec![];
-
         let starting_nodes = self.get_starting_nodes(&search);
 
         // Now that we have the all the nodes we need to build the reference symbols to match the *
         let symbol_matcher =
             T::get_matcher(self.graph, starting_nodes.definition_root_nodes, &search)?;
 
+        // Built once per query and threaded through explicitly (rather than
+        // cached on `self`, since nothing else in this codebase reaches for
+        // interior mutability) so that `get_type_with_symbol` no longer has
+        // to rescan the whole graph for every reference node it resolves.
+        let symbol_index = SymbolIndex::build_global(self.graph);
+
+        let (is_source, symbol_handle) = match self.source_type {
+            SourceType::Source { symbol_handle } => (true, Some(symbol_handle)),
+            _ => (false, None),
+        };
+        // Each file's traversal is independent of every other file's, so
+        // (mirroring the node scan in `get_starting_nodes`) this is a rayon
+        // parallel iterator pushing per-file results into a thread-safe
+        // collector (the `Vec<Vec<ResultNode>>` rayon collects into) rather
+        // than a serial loop over `referenced_files`. The "stop entirely"
+        // `break`s of the original serial loop become per-file skips here,
+        // since one file's lookup failure no longer has a well-defined
+        // "rest of the loop" to abandon.
+        let results: Vec<ResultNode> = starting_nodes
+            .referenced_files
+            .par_iter()
+            .flat_map_iter(|file| {
+                let comp_unit_node_handle = match starting_nodes.file_to_compunit_handle.get(file) {
+                    Some(x) => x,
+                    None => {
+                        debug!("unable to find compulation unit for file");
+                        return vec![];
+                    }
+                };
+                // This determines if the file is source code or not, but using the source_type symbol
+                // graph node.
+                if is_source
+                    && !self.graph.nodes_for_file(*file).any(|node_handle| {
+                        let node = &self.graph[node_handle];
+
+                        let symobl_handle = symbol_handle.unwrap();
+                        if let Some(sh) = node.symbol() {
+                            // This compares the source_type symbol handle to the nodes symbol
+                            // as symbols are de-duplicated, this will check that the symbol for the
+                            // given node is the one that we set for the source_type in the graph.
+                            if sh.as_usize() == symobl_handle.as_usize() {
+                                if self.source_type.get_string() != self.graph[sh] {
+                                    error!("SOMETHING IS VERY WRONG!!!!");
+                                }
+                                // We need to make sure that the compulation unit for the file is
+                                // actually has an edge from teh source_type node.
+                                let edges: Vec<Edge> =
+                                    self.graph.outgoing_edges(node_handle).collect();
+                                for edge in edges {
+                                    if edge.sink == *comp_unit_node_handle {
+                                        return true;
+                                    }
+                                }
+                            }
+                        }
+                        false
+                    })
+                {
+                    return vec![];
+                }
+                let f = &self.graph[*file];
+                let file_url = match Url::from_file_path(f.name()) {
+                    Ok(url) => url,
+                    Err(_) => return vec![],
+                };
+                let file_uri = file_url.as_str().to_string();
+                trace!("searching for matches in file: {}", f.name());
+                self.traverse_node_search(
+                    *comp_unit_node_handle,
+                    &symbol_matcher,
+                    &search,
+                    file_uri,
+                    &symbol_index,
+                    None,
+                )
+            })
+            .collect();
+        Ok(results)
+    }
+}
+
+impl<'graph, T: GetMatcher> Querier<'graph, T> {
+    /// Like [`Query::query`], but walks `referenced_files` serially (rather
+    /// than via rayon) and records every node and edge the traversal visits
+    /// -- including the precedence-10 FQDN edges [`get_fqdn`] walks -- into a
+    /// [`QueryTrace`] that can be exported to Graphviz via
+    /// [`QueryTrace::to_dot`]. This is an opt-in, debug-only path for rule
+    /// authors to see why a search string did or didn't produce a given
+    /// result; the serial traversal trades the production path's parallelism
+    /// for a plain `&mut QueryTrace` with no locking.
+    pub(crate) fn query_with_trace(
+        self,
+        query: String,
+        mode: MatchMode,
+    ) -> anyhow::Result<(Vec<ResultNode>, QueryTrace), Error> {
+        let search: Search = self.get_search(query, mode)?;
+
+        debug!("search: {:?}", search);
+
+        let starting_nodes = self.get_starting_nodes(&search);
+
+        let symbol_matcher =
+            T::get_matcher(self.graph, starting_nodes.definition_root_nodes, &search)?;
+
+        let symbol_index = SymbolIndex::build_global(self.graph);
+
         let (is_source, symbol_handle) = match self.source_type {
             SourceType::Source { symbol_handle } => (true, Some(symbol_handle)),
             _ => (false, None),
         };
-        for file in starting_nodes.referenced_files.iter() {
+
+        let mut trace = QueryTrace::default();
+        let mut results: Vec<ResultNode> = vec![];
+        for file in &starting_nodes.referenced_files {
             let comp_unit_node_handle = match starting_nodes.file_to_compunit_handle.get(file) {
                 Some(x) => x,
                 None => {
                     debug!("unable to find compulation unit for file");
-                    break;
+                    continue;
                 }
             };
-            // This determines if the file is source code or not, but using the source_type symbol
-            // graph node.
             if is_source
                 && !self.graph.nodes_for_file(*file).any(|node_handle| {
                     let node = &self.graph[node_handle];
 
                     let symobl_handle = symbol_handle.unwrap();
                     if let Some(sh) = node.symbol() {
-                        // This compares the source_type symbol handle to the nodes symbol
-                        // as symbols are de-duplicated, this will check that the symbol for the
-                        // given node is the one that we set for the source_type in the graph.
                         if sh.as_usize() == symobl_handle.as_usize() {
                             if self.source_type.get_string() != self.graph[sh] {
                                 error!("SOMETHING IS VERY WRONG!!!!");
                             }
-                            // We need to make sure that the compulation unit for the file is
-                            // actually has an edge from teh source_type node.
                             let edges: Vec<Edge> = self.graph.outgoing_edges(node_handle).collect();
                             for edge in edges {
                                 if edge.sink == *comp_unit_node_handle {
@@ -596,20 +1051,171 @@ impl<'graph, T: GetMatcher> Query for Querier<'graph, T> {
                 continue;
             }
             let f = &self.graph[*file];
-            let file_url = Url::from_file_path(f.name());
-            if file_url.is_err() {
-                break;
-            }
-            let file_uri = file_url.unwrap().as_str().to_string();
+            let file_url = match Url::from_file_path(f.name()) {
+                Ok(url) => url,
+                Err(_) => continue,
+            };
+            let file_uri = file_url.as_str().to_string();
             trace!("searching for matches in file: {}", f.name());
-            self.traverse_node_search(
+            results.extend(self.traverse_node_search(
                 *comp_unit_node_handle,
                 &symbol_matcher,
-                &mut results,
+                &search,
                 file_uri,
-            );
+                &symbol_index,
+                Some(&mut trace),
+            ));
         }
-        Ok(results)
+        Ok((results, trace))
+    }
+}
+
+/// A single node visited during a traced query traversal, captured with
+/// enough of [`get_fqdn`]'s inputs to label it in [`QueryTrace::to_dot`].
+#[derive(Debug, Clone)]
+struct TracedNode {
+    handle: Handle<Node>,
+    symbol: Option<String>,
+    syntax_type: Option<SyntaxType>,
+    span: Option<String>,
+}
+
+/// A single outgoing edge visited during a traced query traversal, along
+/// with any `edge_debug_info` key/values the TSG attached to it.
+#[derive(Debug, Clone)]
+struct TracedEdge {
+    source: Handle<Node>,
+    sink: Handle<Node>,
+    precedence: i32,
+    debug_info: Vec<(String, String)>,
+}
+
+/// Records every node and edge a query traversal visits -- including the
+/// precedence-10 FQDN edges [`get_fqdn`] walks via [`record_fqdn_trace`] --
+/// so [`Querier::query_with_trace`] can export the whole traversal to a
+/// Graphviz `.dot` file for rule authors to inspect, following rustc's
+/// `assert_dep_graph` pass.
+#[derive(Debug, Default)]
+pub(crate) struct QueryTrace {
+    nodes: Vec<TracedNode>,
+    edges: Vec<TracedEdge>,
+    matched: HashSet<Handle<Node>>,
+}
+
+impl QueryTrace {
+    /// Records `handle`, deduping against any prior recording of the same
+    /// node.
+    fn record_node(&mut self, handle: Handle<Node>, graph: &StackGraph) {
+        if self.nodes.iter().any(|n| n.handle == handle) {
+            return;
+        }
+        let node = &graph[handle];
+        let symbol = node.symbol().map(|s| graph[s].to_string());
+        let syntax_type = graph.source_info(handle).and_then(|info| {
+            info.syntax_type
+                .into_option()
+                .map(|h| SyntaxType::get(&graph[h]))
+        });
+        let span = graph.source_info(handle).map(|info| {
+            format!(
+                "{}:{}-{}:{}",
+                info.span.start.line,
+                info.span.start.column.utf8_offset,
+                info.span.end.line,
+                info.span.end.column.utf8_offset
+            )
+        });
+        self.nodes.push(TracedNode {
+            handle,
+            symbol,
+            syntax_type,
+            span,
+        });
+    }
+
+    /// Records `edge`, along with its two endpoint nodes via
+    /// [`QueryTrace::record_node`].
+    fn record_edge(&mut self, edge: Edge, graph: &StackGraph) {
+        self.record_node(edge.source, graph);
+        self.record_node(edge.sink, graph);
+        let debug_info = graph
+            .edge_debug_info(edge.source, edge.sink)
+            .map_or(vec![], |d| {
+                d.iter()
+                    .map(|e| (graph[e.key].to_string(), graph[e.value].to_string()))
+                    .collect()
+            });
+        self.edges.push(TracedEdge {
+            source: edge.source,
+            sink: edge.sink,
+            precedence: edge.precedence,
+            debug_info,
+        });
+    }
+
+    /// Marks `handle` as one of the traversal's result-producing nodes, so
+    /// [`QueryTrace::to_dot`] can highlight it.
+    fn record_match(&mut self, handle: Handle<Node>) {
+        self.matched.insert(handle);
+    }
+
+    /// Renders the recorded traversal as a Graphviz `digraph`: nodes labeled
+    /// with their symbol, [`SyntaxType`], and source span (matched nodes
+    /// filled yellow), edges labeled with their precedence and any
+    /// `edge_debug_info` key/values.
+    pub(crate) fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph query_trace {\n");
+        for node in &self.nodes {
+            let label = format!(
+                "{}\\n{:?}\\n{}",
+                node.symbol.as_deref().unwrap_or(""),
+                node.syntax_type,
+                node.span.as_deref().unwrap_or("")
+            )
+            .replace('"', "\\\"");
+            let style = if self.matched.contains(&node.handle) {
+                ", style=filled, fillcolor=yellow"
+            } else {
+                ""
+            };
+            dot.push_str(&format!(
+                "  n{} [label=\"{}\"{}];\n",
+                node.handle.as_usize(),
+                label,
+                style
+            ));
+        }
+        for edge in &self.edges {
+            let mut label = format!("{}", edge.precedence);
+            if !edge.debug_info.is_empty() {
+                let kvs: Vec<String> = edge
+                    .debug_info
+                    .iter()
+                    .map(|(k, v)| format!("{}={}", k, v))
+                    .collect();
+                label.push_str(&format!(" [{}]", kvs.join(", ")));
+            }
+            let label = label.replace('"', "\\\"");
+            dot.push_str(&format!(
+                "  n{} -> n{} [label=\"{}\"];\n",
+                edge.source.as_usize(),
+                edge.sink.as_usize(),
+                label
+            ));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Mirrors [`get_fqdn`]'s upward precedence-10-edge walk purely to populate
+/// `trace`, without recomputing the [`Fqdn`] itself -- so `get_fqdn`'s own
+/// widely-used signature doesn't need a trace parameter added to it.
+fn record_fqdn_trace(node: Handle<Node>, graph: &StackGraph, trace: &mut QueryTrace) {
+    trace.record_node(node, graph);
+    if let Some(edge) = graph.outgoing_edges(node).find(|e| e.precedence == 10) {
+        trace.record_edge(edge, graph);
+        record_fqdn_trace(edge.sink, graph, trace);
     }
 }
 
@@ -624,28 +1230,256 @@ pub(crate) trait GetMatcher {
         Self: std::marker::Sized;
 }
 
-pub(crate) trait SymbolMatcher {
+/// `Sync` so a single built matcher can be shared, read-only, across the
+/// rayon-parallel per-file search in [`Querier::query`].
+pub(crate) trait SymbolMatcher: Sync {
     fn match_symbol(&self, symbol: String) -> bool;
     fn match_fqdn(&self, fqdn: &Fqdn) -> bool;
+
+    /// Like [`SymbolMatcher::match_fqdn`], but when the matcher's originating
+    /// pattern used `$name` placeholders (e.g.
+    /// `System.Collections.$container.Add`), also returns what each one
+    /// captured, so rule authors can pull the varying type/argument out of a
+    /// search rather than only confirming a match happened. Returns `None`
+    /// when `fqdn` doesn't match the pattern; a match on a pattern with no
+    /// placeholders gives an empty map -- callers are expected to have already confirmed a
+    /// match via `match_fqdn` before relying on this. Defaulted to `None` so
+    /// matchers that don't retain their originating [`Search`] don't need to
+    /// change just to keep compiling.
+    fn match_fqdn_captures(&self, _fqdn: &Fqdn) -> Option<HashMap<String, String>> {
+        None
+    }
 }
 
-#[derive(Debug)]
+/// How a [`Search`] compares its segments against candidate symbols. Exact is
+/// the long-standing default; Regex and Fuzzy (bounded Levenshtein distance,
+/// on the whole trailing segment) exist for the `referenced` capability's
+/// typo-tolerant and regex match modes. Prefix, like racer's `StartsWith`
+/// `SearchType`, still splits the query on `.` like Exact does, but compares
+/// each segment with a case-insensitive `starts_with` instead of equality --
+/// meant for a search-as-you-type caller querying on a partially-typed
+/// namespace/symbol, distinct from the precise glob matching rule authors
+/// write conditions against. CaseInsensitive is like Exact, but tolerates
+/// casing differences between C# source and a rule's pattern -- unlike
+/// Prefix, it still requires the whole segment to match, not just a prefix
+/// of it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum MatchMode {
+    #[default]
+    Exact,
+    Prefix,
+    CaseInsensitive,
+    Regex,
+    Fuzzy,
+}
+
+/// What [`Search::match_detail`] reports for a successful match: the
+/// candidate text that matched and, for [`MatchMode::Fuzzy`], the edit
+/// distance it matched at (`0` for [`MatchMode::Exact`] and
+/// [`MatchMode::Regex`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct MatchDetail {
+    pub(crate) matched_name: String,
+    pub(crate) distance: usize,
+}
+
+#[derive(Debug, Clone)]
 struct SearchPart {
     part: String,
     regex: Option<Regex>,
+    /// Name bound when this segment is a `$name` placeholder (e.g. `$ns`),
+    /// captured by [`Search::match_fqdn_pattern`] rather than just matched.
+    placeholder: Option<String>,
+    /// Whether this segment is `**`, matching zero or more remaining
+    /// segments -- handled specially in [`Search::match_segments`] since,
+    /// unlike every other segment kind, it doesn't consume exactly one.
+    is_any_seq: bool,
+    /// Set when this segment was written using CLR generic-arity notation
+    /// (e.g. `List\`1`), letting it match any instantiation of that
+    /// generic type regardless of its actual type arguments.
+    generic_arity: Option<GenericArity>,
 }
 
-#[derive(Debug)]
+/// A generic type's name and arity (the number of type parameters it
+/// takes), parsed from CLR metadata-style backtick notation such as
+/// `List\`1` or `Dictionary\`2`. Lets a search pattern match "any `List<T>`"
+/// without caring what `T` is, the same way .NET tooling names open
+/// generic types.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct GenericArity {
+    name: String,
+    arity: usize,
+}
+
+impl GenericArity {
+    /// Parses `part` as `<name>\`<arity>` (e.g. `List\`1`), returning `None`
+    /// if it isn't in that form.
+    fn parse(part: &str) -> Option<GenericArity> {
+        let (name, arity) = part.split_once('`')?;
+        let arity = arity.parse().ok()?;
+        Some(GenericArity {
+            name: name.to_string(),
+            arity,
+        })
+    }
+
+    /// Whether `candidate` is an instantiation of this generic type with
+    /// the right arity, e.g. `List<System.String>` or
+    /// `Dictionary<System.String, System.Int32>` for arity `2` -- the
+    /// actual type arguments aren't compared, only how many there are.
+    fn matches(&self, candidate: &str) -> bool {
+        let Some((name, rest)) = candidate.split_once('<') else {
+            return false;
+        };
+        if name != self.name {
+            return false;
+        }
+        let Some(args) = rest.strip_suffix('>') else {
+            return false;
+        };
+        if args.is_empty() {
+            return self.arity == 0;
+        }
+        split_top_level_commas(args).len() == self.arity
+    }
+}
+
+/// Splits `args` (the contents of a generic argument list) on commas that
+/// aren't themselves nested inside another `<...>`, so counting the result
+/// gives the generic arity even when an argument is itself generic, e.g.
+/// `List<System.String>, System.Int32` splits into two arguments, not
+/// three.
+fn split_top_level_commas(args: &str) -> Vec<&str> {
+    let mut result = Vec::new();
+    let mut depth: usize = 0;
+    let mut start = 0;
+    for (i, c) in args.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                result.push(&args[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    result.push(&args[start..]);
+    result
+}
+
+#[derive(Debug, Clone)]
 pub(crate) struct Search {
     parts: Vec<SearchPart>,
+    mode: MatchMode,
+    /// Compiled once from the trailing segment's raw text when `mode` is
+    /// [`MatchMode::Regex`], so [`Search::match_symbol`] tests against an
+    /// already-compiled pattern instead of recompiling on every call.
+    compiled_regex: Option<Regex>,
+}
+
+/// Splits a dotted, possibly-generic symbol into its namespace/name
+/// segments, treating `.` inside `<...>` as part of the enclosing segment
+/// rather than a separator. A plain `symbol.split(".")` mangles a
+/// fully-qualified generic name like
+/// `System.Collections.Generic.List<System.String>.Add`, wrongly treating
+/// the `.` in `System.String` as a top-level separator; this instead
+/// tracks angle-bracket depth and only splits at depth `0`, so that name
+/// splits into `["System", "Collections", "Generic",
+/// "List<System.String>", "Add"]`.
+fn split_symbol_segments(symbol: &str) -> Vec<&str> {
+    let mut segments = Vec::new();
+    let mut depth: usize = 0;
+    let mut start = 0;
+    for (i, c) in symbol.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth = depth.saturating_sub(1),
+            '.' if depth == 0 => {
+                segments.push(&symbol[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    segments.push(&symbol[start..]);
+    segments
 }
 
+/// Maximum number of `**` segments allowed in a single search pattern.
+/// Each `**` backtracks over every suffix of the remaining symbol segments
+/// (see [`Search::match_namespace`]/[`Search::match_segments`]), so chaining
+/// many of them together makes worst-case matching combinatorial in the
+/// number of segments; this keeps that bounded.
+const MAX_ANY_SEQ_SEGMENTS: usize = 4;
+
 impl Search {
     fn create_search(query: String) -> anyhow::Result<Search, Error> {
+        Self::create_search_with_mode(query, MatchMode::Exact)
+    }
+
+    /// Like [`Search::create_search`], but lets the caller pick how the
+    /// trailing segment is compared against candidates in
+    /// [`Search::match_symbol`] -- exact equality/wildcard (the default),
+    /// a case-insensitive prefix, a regex compiled from that segment's raw
+    /// text, or bounded Levenshtein-distance fuzzy matching.
+    pub(crate) fn create_search_with_mode(
+        query: String,
+        mode: MatchMode,
+    ) -> anyhow::Result<Search, Error> {
+        if matches!(mode, MatchMode::Regex | MatchMode::Fuzzy) {
+            // Regex/Fuzzy compare the whole pattern against a single
+            // candidate symbol rather than a dotted namespace path, so --
+            // unlike Exact/Prefix mode -- the pattern isn't split on `.`,
+            // which would otherwise mangle a literal `.` in a regex or break
+            // a fuzzy pattern into unrelated pieces.
+            let compiled_regex = match mode {
+                MatchMode::Regex => Some(Regex::new(&query)?),
+                MatchMode::Exact | MatchMode::Prefix | MatchMode::CaseInsensitive | MatchMode::Fuzzy => {
+                    None
+                }
+            };
+            return Ok(Search {
+                parts: vec![SearchPart {
+                    part: query,
+                    regex: None,
+                    placeholder: None,
+                    is_any_seq: false,
+                    generic_arity: None,
+                }],
+                mode,
+                compiled_regex,
+            });
+        }
+
         let mut parts: Vec<SearchPart> = vec![];
         let star_regex = Regex::new(".*")?;
-        for part in query.split(".") {
-            if part.contains("*") {
+        for part in split_symbol_segments(&query) {
+            if let Some(name) = part.strip_prefix('$') {
+                parts.push(SearchPart {
+                    part: part.to_string(),
+                    regex: None,
+                    placeholder: Some(name.to_string()),
+                    is_any_seq: false,
+                    generic_arity: None,
+                });
+            } else if part == "**" {
+                parts.push(SearchPart {
+                    part: part.to_string(),
+                    regex: None,
+                    placeholder: None,
+                    is_any_seq: true,
+                    generic_arity: None,
+                });
+            } else if let Some(arity) = GenericArity::parse(part) {
+                parts.push(SearchPart {
+                    part: part.to_string(),
+                    regex: None,
+                    placeholder: None,
+                    is_any_seq: false,
+                    generic_arity: Some(arity),
+                });
+            } else if part.contains("*") {
                 let regex: Regex = if part == "*" {
                     star_regex.clone()
                 } else {
@@ -656,52 +1490,218 @@ impl Search {
                 parts.push(SearchPart {
                     part: part.to_string(),
                     regex: Some(regex),
+                    placeholder: None,
+                    is_any_seq: false,
+                    generic_arity: None,
                 });
             } else {
                 parts.push(SearchPart {
                     part: part.to_string(),
                     regex: None,
+                    placeholder: None,
+                    is_any_seq: false,
+                    generic_arity: None,
                 })
             }
         }
 
-        Ok(Search { parts })
+        let any_seq_count = parts.iter().filter(|p| p.is_any_seq).count();
+        if any_seq_count > MAX_ANY_SEQ_SEGMENTS {
+            return Err(anyhow!(
+                "search pattern {query:?} has {any_seq_count} '**' segments, which exceeds the maximum of {MAX_ANY_SEQ_SEGMENTS}"
+            ));
+        }
+
+        Ok(Search {
+            parts,
+            mode,
+            compiled_regex: None,
+        })
     }
 }
 
 impl Search {
     pub(crate) fn partial_namespace(&self, symbol: &str) -> bool {
-        // We will need to break apart the symbol based on "." then looping through, look at the
-        // same index, and if it matches continue if it doesn't then return false.
-        for (i, symbol_part) in symbol.split(".").enumerate() {
-            if self.parts.len() <= i {
-                break;
-            }
-            if !self.parts[i].matches(symbol_part) {
-                return false;
+        let segments = split_symbol_segments(symbol);
+        Self::match_namespace_segments(&self.parts, &segments, self.mode)
+    }
+
+    /// Matches `symbol`'s dot-separated segments against this search's
+    /// pattern. A plain segment matches one symbol segment at the same
+    /// position, same as before; a `**` segment is a backtracking
+    /// two-pointer wildcard that tries the remaining concrete parts against
+    /// every suffix of the remaining symbol segments (starting from
+    /// consuming none and growing), so a pattern like `System.**.Tasks.Task`
+    /// matches both `System.Threading.Tasks.Task` and
+    /// `System.Net.Http.Tasks.Task`. Since this only ever reports a `bool`,
+    /// not which split `**` settled on, the walk is effectively anchored at
+    /// the first split that makes the whole pattern match rather than
+    /// greedily consuming as much as possible.
+    ///
+    /// Running out of pattern segments before running out of symbol
+    /// segments is still a match -- a shorter query like `System` matches a
+    /// longer declared namespace like `System.Configuration` -- so, unlike
+    /// [`Search::match_segments`] (which backs
+    /// [`Search::match_fqdn_pattern`] and requires the symbol to be fully
+    /// consumed), this never fails just because the pattern is shorter.
+    /// Running out of symbol segments first is a match too: a declared
+    /// namespace or import like `System.Configuration` that every one of
+    /// its segments matches is a portion of a longer query like
+    /// `System.Configuration.ConfigurationManager`.
+    pub(crate) fn match_namespace(&self, symbol: &str) -> bool {
+        let segments = split_symbol_segments(symbol);
+        Self::match_namespace_segments(&self.parts, &segments, self.mode)
+    }
+
+    fn match_namespace_segments(parts: &[SearchPart], segments: &[&str], mode: MatchMode) -> bool {
+        let Some((part, rest_parts)) = parts.split_first() else {
+            return true;
+        };
+
+        if part.is_any_seq {
+            for i in 0..=segments.len() {
+                if Self::match_namespace_segments(rest_parts, &segments[i..], mode) {
+                    return true;
+                }
             }
+            return false;
+        }
+
+        let Some((segment, rest_segments)) = segments.split_first() else {
+            return true;
+        };
+        if !part.matches(segment, mode) {
+            return false;
         }
-        true
+        Self::match_namespace_segments(rest_parts, rest_segments, mode)
     }
 
-    pub(crate) fn match_namespace(&self, symbol: &str) -> bool {
-        for (i, symbol_part) in symbol.split(".").enumerate() {
-            // Because we can assume that the last part here is a '*' right now,
-            // we anything past that should match
-            if self.parts.len() <= i {
-                break;
+    pub(crate) fn match_symbol(&self, symbol: &str) -> bool {
+        match self.mode {
+            MatchMode::Exact | MatchMode::Prefix | MatchMode::CaseInsensitive => {
+                // If the parts list is empty this will panic, but that should never happen.
+                let last_part = self.parts.last().unwrap();
+                last_part.matches(symbol, self.mode)
             }
-            if !self.parts[i].matches(symbol_part) {
-                return false;
+            MatchMode::Regex => match &self.compiled_regex {
+                Some(r) => r.is_match(symbol),
+                None => false,
+            },
+            MatchMode::Fuzzy => self.fuzzy_distance(symbol).is_some(),
+        }
+    }
+
+    /// Like [`Search::match_symbol`], but also reports what matched: the
+    /// candidate text, and -- for [`MatchMode::Fuzzy`] -- the edit distance
+    /// it matched at. Kept separate from `match_symbol`'s plain `bool` so
+    /// existing callers relying on that simpler contract are unaffected.
+    pub(crate) fn match_detail(&self, symbol: &str) -> Option<MatchDetail> {
+        match self.mode {
+            MatchMode::Fuzzy => self.fuzzy_distance(symbol).map(|distance| MatchDetail {
+                matched_name: symbol.to_string(),
+                distance,
+            }),
+            MatchMode::Exact | MatchMode::Prefix | MatchMode::CaseInsensitive | MatchMode::Regex => {
+                if self.match_symbol(symbol) {
+                    Some(MatchDetail {
+                        matched_name: symbol.to_string(),
+                        distance: 0,
+                    })
+                } else {
+                    None
+                }
             }
         }
-        true
     }
 
-    pub(crate) fn match_symbol(&self, symbol: &str) -> bool {
-        // If the parts list is empty this will panic, but that should never happen.
-        let last_part = self.parts.last().unwrap();
-        last_part.matches(symbol)
+    /// The bounded edit distance between `symbol` and this search's trailing
+    /// segment, or `None` when it exceeds the distance allowed for that
+    /// segment's length -- `0` for patterns of 3 characters or fewer (typos
+    /// would change their meaning), `1` for 4-7 characters, and `2` beyond
+    /// that.
+    fn fuzzy_distance(&self, symbol: &str) -> Option<usize> {
+        let last_part = self.parts.last()?;
+        let max_distance = Self::max_fuzzy_distance(last_part.part.len());
+        levenshtein_within(&last_part.part, symbol, max_distance)
+    }
+
+    fn max_fuzzy_distance(pattern_len: usize) -> usize {
+        match pattern_len {
+            0..=3 => 0,
+            4..=7 => 1,
+            _ => 2,
+        }
+    }
+
+    /// The exact text [`Search::match_symbol`] would compare against, when
+    /// this search's last segment is a plain literal rather than a `*`/`**`
+    /// wildcard, `$name` placeholder, or partial regex -- i.e. when a lookup
+    /// against it can be answered with a single exact-match index lookup
+    /// instead of scanning every candidate through `match_symbol`.
+    pub(crate) fn as_literal_symbol(&self) -> Option<&str> {
+        let last_part = self.parts.last()?;
+        if self.mode == MatchMode::Exact
+            && last_part.regex.is_none()
+            && last_part.placeholder.is_none()
+            && last_part.generic_arity.is_none()
+            && !last_part.is_any_seq
+        {
+            Some(&last_part.part)
+        } else {
+            None
+        }
+    }
+
+    /// Structurally match `dotted` (a fully-qualified, dot-joined name) against
+    /// this search's pattern, supporting `**` (zero or more segments) and
+    /// `$name` placeholders alongside the existing `*`/regex segments. On a
+    /// match, returns the text captured by each `$name` placeholder; returns
+    /// `None` otherwise. Kept separate from [`Search::match_namespace`] and
+    /// [`Search::partial_namespace`], which only ever compare a plain `bool`
+    /// and are relied on elsewhere for that simpler contract.
+    pub(crate) fn match_fqdn_pattern(&self, dotted: &str) -> Option<HashMap<String, String>> {
+        let segments = split_symbol_segments(dotted);
+        let mut captures = HashMap::new();
+        if Self::match_segments(&self.parts, &segments, self.mode, &mut captures) {
+            Some(captures)
+        } else {
+            None
+        }
+    }
+
+    fn match_segments(
+        parts: &[SearchPart],
+        segments: &[&str],
+        mode: MatchMode,
+        captures: &mut HashMap<String, String>,
+    ) -> bool {
+        let Some((part, rest_parts)) = parts.split_first() else {
+            return segments.is_empty();
+        };
+
+        if part.is_any_seq {
+            // `**` can consume any number of segments, including none, so try
+            // every split point until the remaining pattern matches.
+            for i in 0..=segments.len() {
+                let mut trial_captures = captures.clone();
+                if Self::match_segments(rest_parts, &segments[i..], mode, &mut trial_captures) {
+                    *captures = trial_captures;
+                    return true;
+                }
+            }
+            return false;
+        }
+
+        let Some((segment, rest_segments)) = segments.split_first() else {
+            return false;
+        };
+        if !part.matches(segment, mode) {
+            return false;
+        }
+        if let Some(name) = &part.placeholder {
+            captures.insert(name.clone(), segment.to_string());
+        }
+        Self::match_segments(rest_parts, rest_segments, mode, captures)
     }
 
     // fn import_match
@@ -712,10 +1712,43 @@ impl Search {
 }
 
 impl SearchPart {
-    fn matches(&self, match_string: &str) -> bool {
-        match &self.regex {
-            None => self.part == match_string,
-            Some(r) => r.is_match(match_string),
+    /// `mode` only changes how a plain literal segment (no `*`-glob, no
+    /// `$name` placeholder) is compared: [`MatchMode::Exact`]/[`MatchMode::Regex`]
+    /// keep the original exact-equality behavior, [`MatchMode::Prefix`] is a
+    /// case-insensitive `starts_with`, [`MatchMode::CaseInsensitive`] is a
+    /// case-insensitive full-segment equality (unlike Prefix, the whole
+    /// segment must match, not just its start), and [`MatchMode::Fuzzy`] is a
+    /// case-insensitive subsequence match -- cheaper than
+    /// [`Search::fuzzy_distance`]'s bounded Levenshtein distance, which is
+    /// appropriate here since every segment of every candidate gets tested.
+    fn matches(&self, match_string: &str, mode: MatchMode) -> bool {
+        if self.placeholder.is_some() {
+            return true;
+        }
+        if let Some(arity) = &self.generic_arity {
+            return arity.matches(match_string);
+        }
+        if let Some(r) = &self.regex {
+            return r.is_match(match_string);
+        }
+        match mode {
+            MatchMode::Exact | MatchMode::Regex => self.part == match_string,
+            MatchMode::Prefix => match_string
+                .to_lowercase()
+                .starts_with(&self.part.to_lowercase()),
+            MatchMode::CaseInsensitive => self.part.to_lowercase() == match_string.to_lowercase(),
+            MatchMode::Fuzzy => is_subsequence(&self.part, match_string),
         }
     }
 }
+
+/// Whether every character of `pattern` appears, in order (not necessarily
+/// contiguously), in `candidate`, case-insensitively -- the subsequence
+/// fuzzy-match [`SearchPart::matches`] uses for [`MatchMode::Fuzzy`].
+fn is_subsequence(pattern: &str, candidate: &str) -> bool {
+    let mut candidate_chars = candidate.chars().map(|c| c.to_ascii_lowercase());
+    pattern
+        .chars()
+        .map(|c| c.to_ascii_lowercase())
+        .all(|p| candidate_chars.by_ref().any(|c| c == p))
+}
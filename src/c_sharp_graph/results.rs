@@ -1,8 +1,15 @@
-use std::{collections::BTreeMap, fmt::Display, str::FromStr};
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    io::{BufRead, BufReader},
+    str::FromStr,
+};
 
 use prost_types::{Struct, Value};
 use serde::{Deserialize, Deserializer};
 use serde_json::json;
+use tracing::warn;
+use url::Url;
 
 use crate::analyzer_service::{
     IncidentContext, Location as ProtoLocation, Position as ProtoPosition,
@@ -81,7 +88,7 @@ fn serde_json_to_prost(json: serde_json::Value) -> prost_types::Value {
         kind: Some(match json {
             Null => NullValue(0 /* wat? */),
             Bool(v) => BoolValue(v),
-            Number(n) => NumberValue(n.as_f64().expect("Non-f64-representable number")),
+            Number(n) => number_to_prost_kind(&n),
             String(s) => StringValue(s),
             Array(v) => ListValue(prost_types::ListValue {
                 values: v.into_iter().map(serde_json_to_prost).collect(),
@@ -97,14 +104,106 @@ fn serde_json_to_prost(json: serde_json::Value) -> prost_types::Value {
     }
 }
 
+/// The largest integer (and, negated, smallest) an `f64` can represent
+/// exactly -- `2^53`. A C# `long`/`ulong` literal beyond this range would
+/// silently round if routed through `NumberValue`'s `f64`, so it's instead
+/// kept as a `StringValue` of its exact decimal text.
+const MAX_SAFE_INTEGER: u64 = 1 << 53;
+
+/// Converts a JSON number into a `prost_types::Value` kind without ever
+/// panicking or losing precision: integers within `f64`'s exact range
+/// become `NumberValue`, integers beyond it become a lossless
+/// `StringValue`, and non-finite floats (`NaN`/`±Infinity`, reachable via a
+/// custom deserializer even though `serde_json` itself never produces them)
+/// become a defined string sentinel instead of a value downstream
+/// protobuf-JSON consumers would handle inconsistently.
+fn number_to_prost_kind(n: &serde_json::Number) -> prost_types::value::Kind {
+    use prost_types::value::Kind::{NumberValue, StringValue};
+
+    if let Some(i) = n.as_i64() {
+        return if i.unsigned_abs() <= MAX_SAFE_INTEGER {
+            NumberValue(i as f64)
+        } else {
+            StringValue(i.to_string())
+        };
+    }
+    if let Some(u) = n.as_u64() {
+        return if u <= MAX_SAFE_INTEGER {
+            NumberValue(u as f64)
+        } else {
+            StringValue(u.to_string())
+        };
+    }
+    match n.as_f64() {
+        Some(f) if f.is_finite() => NumberValue(f),
+        Some(f) => StringValue(non_finite_sentinel(f)),
+        // `serde_json::Number` couldn't be read back as any numeric type it
+        // exposes -- fall back to its own decimal text rather than
+        // panicking.
+        None => StringValue(n.to_string()),
+    }
+}
+
+/// A stable string sentinel for a non-finite float, so a rule engine
+/// consuming `IncidentContext.variables` sees a deterministic, parseable
+/// value instead of whatever a protobuf-JSON encoder decides to do with a
+/// raw `NaN`/`Infinity` `NumberValue`.
+fn non_finite_sentinel(f: f64) -> String {
+    if f.is_nan() {
+        "NaN".to_string()
+    } else if f.is_sign_negative() {
+        "-Infinity".to_string()
+    } else {
+        "Infinity".to_string()
+    }
+}
+
+/// The number of lines in the file `file_uri` points at, or `None` when it
+/// can't be parsed as a `file://` URI or read from disk -- e.g. a test
+/// fixture's made-up URI, or a file that's since been deleted. Mirrors
+/// [`crate::provider::code_snip`]'s own `Url::parse`/`to_file_path` idiom for
+/// resolving a `codeLocation`'s file back to something readable.
+fn line_count_for_file(file_uri: &str) -> Option<usize> {
+    let path = Url::parse(file_uri).ok()?.to_file_path().ok()?;
+    let file = std::fs::File::open(path).ok()?;
+    Some(BufReader::new(file).lines().count())
+}
+
 impl From<&ResultNode> for IncidentContext {
     fn from(val: &ResultNode) -> Self {
         let x = serde_json_to_prost(json!(val.variables.clone()));
+        // `code_location` already carries a start/end `Position` with a
+        // `character` column on each end, so a full span is representable
+        // today -- but a malformed one (end before start, or an end line
+        // past the actual end of the file) would mislead an editor into
+        // highlighting backwards or past EOF, so fall back to a single-line
+        // span at `line_number` rather than forwarding it. The line count
+        // is read from disk since neither `ResultNode` nor the stack graph
+        // it's built from track it; a file that can't be resolved or read
+        // is treated as unbounded, so `is_valid()` alone still decides.
+        let code_location = match line_count_for_file(&val.file_uri) {
+            Some(total_lines) if !val.code_location.within_file(total_lines) => {
+                warn!(
+                    "discarding out-of-range codeLocation {:?} for {}:{} ({} lines), synthesizing a single-line span",
+                    val.code_location, val.file_uri, val.line_number, total_lines
+                );
+                Location::single_line(val.line_number)
+            }
+            Some(_) => val.code_location.clone(),
+            None if !val.code_location.is_valid() => {
+                warn!(
+                    "discarding invalid codeLocation {:?} for {}:{}, synthesizing a single-line span",
+                    val.code_location, val.file_uri, val.line_number
+                );
+                Location::single_line(val.line_number)
+            }
+            None => val.code_location.clone(),
+        };
         if let Some(prost_types::value::Kind::StructValue(x)) = x.kind {
             IncidentContext {
                 file_uri: val.file_uri.clone(),
                 effort: None,
-                code_location: Some(val.code_location.clone().into()),
+                code_location: Some(code_location.into()),
                 line_number: Some(val.line_number as i64),
                 variables: Some(x),
                 links: vec![],
@@ -114,7 +213,7 @@ impl From<&ResultNode> for IncidentContext {
             IncidentContext {
                 file_uri: val.file_uri.clone(),
                 effort: None,
-                code_location: Some(val.code_location.clone().into()),
+                code_location: Some(code_location.into()),
                 line_number: Some(val.line_number as i64),
                 variables: None,
                 links: vec![],
@@ -148,6 +247,37 @@ pub struct Location {
     pub end_position: Position,
 }
 
+impl Location {
+    /// Whether `start_position <= end_position` -- the only thing a span
+    /// can confirm about itself without also knowing the file it points
+    /// into (see [`Location::within_file`] for that half).
+    pub(crate) fn is_valid(&self) -> bool {
+        self.start_position <= self.end_position
+    }
+
+    /// Whether this span is valid and every line it touches exists in a
+    /// file of `total_lines` lines.
+    pub(crate) fn within_file(&self, total_lines: usize) -> bool {
+        self.is_valid() && self.end_position.line < total_lines
+    }
+
+    /// A zero-width, single-line span at `line_number`, column `0` --
+    /// widens a bare `line_number`-only match into the same start/end
+    /// `Position` shape a parsed `codeLocation` would have, so a consumer
+    /// always has a span to highlight even when only a line number was
+    /// ever known.
+    pub(crate) fn single_line(line_number: usize) -> Location {
+        let position = Position {
+            line: line_number,
+            character: 0,
+        };
+        Location {
+            start_position: position.clone(),
+            end_position: position,
+        }
+    }
+}
+
 impl From<Location> for ProtoLocation {
     fn from(val: Location) -> Self {
         ProtoLocation {
@@ -156,3 +286,142 @@ impl From<Location> for ProtoLocation {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost_types::value::Kind;
+
+    #[test]
+    fn small_integer_round_trips_as_number() {
+        let n = serde_json::Number::from(42);
+        assert_eq!(number_to_prost_kind(&n), Kind::NumberValue(42.0));
+    }
+
+    #[test]
+    fn i64_max_is_lossless_string() {
+        let n = serde_json::Number::from(i64::MAX);
+        assert_eq!(
+            number_to_prost_kind(&n),
+            Kind::StringValue(i64::MAX.to_string())
+        );
+    }
+
+    #[test]
+    fn u64_max_is_lossless_string() {
+        let n = serde_json::Number::from(u64::MAX);
+        assert_eq!(
+            number_to_prost_kind(&n),
+            Kind::StringValue(u64::MAX.to_string())
+        );
+    }
+
+    #[test]
+    fn negative_i64_max_is_lossless_string() {
+        let n = serde_json::Number::from(i64::MIN);
+        assert_eq!(
+            number_to_prost_kind(&n),
+            Kind::StringValue(i64::MIN.to_string())
+        );
+    }
+
+    #[test]
+    fn non_finite_floats_map_to_sentinels_instead_of_panicking() {
+        assert_eq!(non_finite_sentinel(f64::NAN), "NaN");
+        assert_eq!(non_finite_sentinel(f64::INFINITY), "Infinity");
+        assert_eq!(non_finite_sentinel(f64::NEG_INFINITY), "-Infinity");
+    }
+
+    #[test]
+    fn finite_float_round_trips_as_number() {
+        let n = serde_json::Number::from_f64(3.5).unwrap();
+        assert_eq!(number_to_prost_kind(&n), Kind::NumberValue(3.5));
+    }
+
+    #[test]
+    fn location_with_end_before_start_is_invalid() {
+        let location = Location {
+            start_position: Position { line: 5, character: 0 },
+            end_position: Position { line: 2, character: 0 },
+        };
+        assert!(!location.is_valid());
+    }
+
+    #[test]
+    fn location_with_end_on_or_after_start_is_valid() {
+        let location = Location {
+            start_position: Position { line: 2, character: 3 },
+            end_position: Position { line: 2, character: 10 },
+        };
+        assert!(location.is_valid());
+    }
+
+    #[test]
+    fn location_within_file_checks_end_line_bound() {
+        let location = Location {
+            start_position: Position { line: 2, character: 0 },
+            end_position: Position { line: 4, character: 0 },
+        };
+        assert!(location.within_file(5));
+        assert!(!location.within_file(4));
+    }
+
+    #[test]
+    fn single_line_synthesizes_zero_width_span() {
+        let location = Location::single_line(7);
+        assert_eq!(location.start_position, Position { line: 7, character: 0 });
+        assert_eq!(location.end_position, Position { line: 7, character: 0 });
+        assert!(location.is_valid());
+    }
+
+    /// Writes `contents` to a uniquely-named file under the OS temp dir and
+    /// returns its `file://` URI, so tests can exercise
+    /// `line_count_for_file`/`IncidentContext::from`'s real disk read
+    /// without a fixtures directory or an extra dev-dependency.
+    fn write_temp_file(name: &str, contents: &str) -> String {
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, contents).unwrap();
+        Url::from_file_path(&path).unwrap().to_string()
+    }
+
+    #[test]
+    fn incident_context_keeps_code_location_within_file_bounds() {
+        let file_uri = write_temp_file(
+            "results_rs_within_bounds.cs",
+            "line1\nline2\nline3\n",
+        );
+        let node = ResultNode {
+            file_uri,
+            line_number: 1,
+            variables: BTreeMap::new(),
+            code_location: Location {
+                start_position: Position { line: 0, character: 0 },
+                end_position: Position { line: 1, character: 0 },
+            },
+        };
+        let incident: IncidentContext = (&node).into();
+        let location = incident.code_location.unwrap();
+        assert_eq!(location.end_position.unwrap().line, 1.0);
+    }
+
+    #[test]
+    fn incident_context_falls_back_to_single_line_when_past_end_of_file() {
+        let file_uri = write_temp_file(
+            "results_rs_past_eof.cs",
+            "line1\nline2\n",
+        );
+        let node = ResultNode {
+            file_uri,
+            line_number: 1,
+            variables: BTreeMap::new(),
+            code_location: Location {
+                start_position: Position { line: 0, character: 0 },
+                end_position: Position { line: 50, character: 0 },
+            },
+        };
+        let incident: IncidentContext = (&node).into();
+        let location = incident.code_location.unwrap();
+        assert_eq!(location.start_position.unwrap().line, 1.0);
+        assert_eq!(location.end_position.unwrap().line, 1.0);
+    }
+}
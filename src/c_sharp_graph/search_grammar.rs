@@ -0,0 +1,176 @@
+//! A small, typed query grammar for search expressions, parsed and
+//! validated independently of any particular [`GetMatcher`] implementation.
+//!
+//! Today a search is a raw dotted string (see
+//! [`crate::c_sharp_graph::query::Search`]) compared against candidate
+//! symbols by each getter's own ad-hoc logic -- e.g. [`ClassSymbols`] only
+//! ever checks a node's `SyntaxType::ClassDef`, so there's no way to write
+//! "any class in namespace `Foo.Bar`" or "a method" as distinct,
+//! independently-validatable query terms. [`QueryPlan`] gives each dotted
+//! segment an explicit [`NodeKind`] (namespace/class/method/field) and
+//! value kind (literal, wildcard, or anchored regex), and
+//! [`parse_and_validate`] rejects malformed queries -- an unknown kind
+//! prefix, an empty segment, an unparseable regex -- before a single node
+//! of the stack graph is ever walked, reporting the character offset of the
+//! offending token the way GraphQL's "known type names" validation rule
+//! does.
+//!
+//! A `class:`/`namespace:` query is recognized by [`QueryType::query`]
+//! whenever the raw query string contains a `:` -- a character a plain
+//! dotted symbol pattern never does -- and is validated with
+//! [`parse_and_validate`] before being translated back into the dotted
+//! string `Search` already knows how to parse. Translating each
+//! [`QueryNode`] in place of matching against it directly keeps today's
+//! per-getter string comparisons as the one thing that walks the stack
+//! graph, while still giving malformed typed queries an offset-reported
+//! diagnostic instead of a confusing downstream mismatch.
+//!
+//! [`ClassSymbols`]: crate::c_sharp_graph::class_query::ClassSymbols
+//! [`QueryType::query`]: crate::c_sharp_graph::query::QueryType
+//! [`Search`]: crate::c_sharp_graph::query::Search
+
+use crate::c_sharp_graph::query::SyntaxType;
+
+/// Which kind of declaration a query segment is anchored to -- the typed
+/// counterpart of the ad-hoc `SyntaxType::ClassDef` check [`ClassSymbols`]
+/// hardcodes today.
+///
+/// [`ClassSymbols`]: crate::c_sharp_graph::class_query::ClassSymbols
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum NodeKind {
+    Namespace,
+    Class,
+    Method,
+    Field,
+}
+
+impl NodeKind {
+    /// The [`SyntaxType`] a matcher should require a candidate node to have.
+    pub(crate) fn syntax_type(self) -> SyntaxType {
+        match self {
+            NodeKind::Namespace => SyntaxType::NamespaceDeclaration,
+            NodeKind::Class => SyntaxType::ClassDef,
+            NodeKind::Method => SyntaxType::MethodName,
+            NodeKind::Field => SyntaxType::FieldName,
+        }
+    }
+
+    fn parse(prefix: &str) -> Option<NodeKind> {
+        match prefix {
+            "namespace" => Some(NodeKind::Namespace),
+            "class" => Some(NodeKind::Class),
+            "method" => Some(NodeKind::Method),
+            "field" => Some(NodeKind::Field),
+            _ => None,
+        }
+    }
+}
+
+/// How a single segment's value should be compared against a candidate
+/// symbol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ValueMatch {
+    Literal(String),
+    Wildcard,
+    /// An anchored `/.../` regex, already validated as compilable -- the
+    /// raw pattern text, not a compiled [`regex::Regex`], since `QueryPlan`
+    /// needs to stay `Clone`/`PartialEq`/`Eq` for tests and diagnostics.
+    Regex(String),
+}
+
+/// One `kind:value` term in a search expression, e.g. `class:*Controller`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct QueryNode {
+    pub(crate) kind: NodeKind,
+    pub(crate) value: ValueMatch,
+}
+
+/// A parsed, validated search expression: a dotted sequence of
+/// [`QueryNode`]s, e.g. `namespace:Foo.namespace:Bar.class:*Controller`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct QueryPlan {
+    pub(crate) nodes: Vec<QueryNode>,
+}
+
+/// A validation failure, with the character offset of the offending token
+/// so a rule author's editor can underline it -- mirroring GraphQL's
+/// "known type names"/"fields on correct type" diagnostics.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct GrammarError {
+    pub(crate) message: String,
+    pub(crate) offset: usize,
+}
+
+impl std::fmt::Display for GrammarError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (at offset {})", self.message, self.offset)
+    }
+}
+
+impl std::error::Error for GrammarError {}
+
+/// Parses `query` into a [`QueryPlan`] and validates every term: an unknown
+/// `kind:` prefix, an empty segment, an empty value, or an unparseable
+/// anchored regex is rejected here, before the query ever reaches a
+/// [`GetMatcher`] and walks the graph.
+///
+/// [`GetMatcher`]: crate::c_sharp_graph::query::GetMatcher
+pub(crate) fn parse_and_validate(query: &str) -> Result<QueryPlan, GrammarError> {
+    let mut nodes = Vec::new();
+    let mut offset = 0usize;
+
+    for term in query.split('.') {
+        let term_offset = offset;
+        offset += term.len() + 1;
+
+        if term.is_empty() {
+            return Err(GrammarError {
+                message: "empty query segment".to_string(),
+                offset: term_offset,
+            });
+        }
+
+        let Some((prefix, value)) = term.split_once(':') else {
+            return Err(GrammarError {
+                message: format!(
+                    "segment {term:?} is missing a `kind:` prefix (expected one of namespace/class/method/field)"
+                ),
+                offset: term_offset,
+            });
+        };
+
+        let Some(kind) = NodeKind::parse(prefix) else {
+            return Err(GrammarError {
+                message: format!(
+                    "unknown query kind {prefix:?} (expected one of namespace/class/method/field)"
+                ),
+                offset: term_offset,
+            });
+        };
+
+        if value.is_empty() {
+            return Err(GrammarError {
+                message: format!("segment {term:?} has an empty value"),
+                offset: term_offset + prefix.len() + 1,
+            });
+        }
+
+        let value_match = match value.strip_prefix('/').and_then(|v| v.strip_suffix('/')) {
+            Some(pattern) => {
+                if let Err(e) = regex::Regex::new(pattern) {
+                    return Err(GrammarError {
+                        message: format!("malformed anchored regex /{pattern}/: {e}"),
+                        offset: term_offset + prefix.len() + 1,
+                    });
+                }
+                ValueMatch::Regex(pattern.to_string())
+            }
+            None if value == "*" => ValueMatch::Wildcard,
+            None => ValueMatch::Literal(value.to_string()),
+        };
+
+        nodes.push(QueryNode { kind, value: value_match });
+    }
+
+    Ok(QueryPlan { nodes })
+}
@@ -0,0 +1,473 @@
+use std::collections::HashSet;
+
+use fst::{
+    automaton::{Automaton, Str, Subsequence},
+    IntoStreamer, Map, MapBuilder, Streamer,
+};
+use stack_graphs::{
+    arena::Handle,
+    graph::{File, Node, StackGraph},
+};
+
+use crate::c_sharp_graph::query::{get_fqdn, Fqdn, Search, SyntaxType};
+
+/// One indexed definition node: the raw symbol text the graph stores on it,
+/// the dotted FQDN it resolves to, and enough to hand back to a caller that
+/// matched it.
+#[derive(Debug)]
+pub(crate) struct IndexEntry {
+    pub(crate) symbol: String,
+    pub(crate) dotted_fqdn: String,
+    pub(crate) fqdn: Fqdn,
+    pub(crate) syntax_type: SyntaxType,
+    pub(crate) node: Handle<Node>,
+    /// The file this definition came from, when the node carries one, so
+    /// [`SymbolIndex::apply_change`] can invalidate just the entries for a
+    /// changed file instead of rebuilding the whole index.
+    pub(crate) file: Option<Handle<File>>,
+}
+
+/// A once-built, reusable index over a `StackGraph`'s definition nodes.
+///
+/// `NamespaceSymbols::new` re-walks every outgoing edge from its roots on
+/// every construction, which is O(graph) per query and quadratic once many
+/// rules run over the same codebase. `SymbolIndex::build` walks the graph
+/// exactly once and stores the resulting entries sorted two ways -- by bare
+/// symbol text and by dotted FQDN -- so exact and prefix lookups are
+/// binary-searchable instead of linear scans, and the same index can back
+/// any number of subsequent queries.
+///
+/// Following rust-analyzer's `symbol_index`, a lowercased-symbol `fst::Map`
+/// is layered on top of the same entries for [`SymbolIndex::lookup_exact`],
+/// [`SymbolIndex::lookup_prefix`] and [`SymbolIndex::lookup_fuzzy`], which
+/// `Querier::get_type_with_symbol` consults instead of scanning
+/// `graph.iter_symbols()`/`graph.iter_nodes()` for every accessor.
+pub(crate) struct SymbolIndex {
+    entries: Vec<IndexEntry>,
+    /// Indices into `entries`, sorted by `entries[i].symbol`.
+    by_symbol: Vec<usize>,
+    /// Indices into `entries`, sorted by `entries[i].dotted_fqdn`.
+    by_fqdn: Vec<usize>,
+    /// Indices into `entries`, sorted by `entries[i].symbol.to_lowercase()`;
+    /// the `fst_index` maps a lowercased symbol to the first position in
+    /// this vec holding that run.
+    by_lower: Vec<usize>,
+    /// Lowercased symbol -> start offset into `by_lower` of the (possibly
+    /// multi-entry, case-insensitively-overloaded) run for that symbol.
+    fst_index: Map<Vec<u8>>,
+}
+
+impl SymbolIndex {
+    /// Walk every node reachable from `roots` exactly once and build the index.
+    pub(crate) fn build(graph: &StackGraph, roots: &[Handle<Node>]) -> SymbolIndex {
+        let mut entries = Vec::new();
+        for &root in roots {
+            Self::collect(graph, root, &mut entries);
+        }
+        Self::from_entries(entries)
+    }
+
+    /// Walk every definition node in the whole graph, not just ones
+    /// reachable from specific roots -- for callers like
+    /// `Querier::get_type_with_symbol` that need to resolve an accessor's
+    /// definition anywhere in the graph, not just under an already-matched
+    /// namespace.
+    pub(crate) fn build_global(graph: &StackGraph) -> SymbolIndex {
+        let mut entries = Vec::new();
+        for node_handle in graph.iter_nodes() {
+            let node = &graph[node_handle];
+            if !node.is_definition() {
+                continue;
+            }
+            let Some(symbol_handle) = node.symbol() else {
+                continue;
+            };
+            let symbol = graph[symbol_handle].to_string();
+            let Some(source_info) = graph.source_info(node_handle) else {
+                continue;
+            };
+            let Some(syntax_type) = source_info.syntax_type.into_option() else {
+                continue;
+            };
+            let syntax_type = SyntaxType::get(&graph[syntax_type]);
+            let Some(fqdn) = get_fqdn(node_handle, graph) else {
+                continue;
+            };
+            let dotted_fqdn = [&fqdn.namespace, &fqdn.class, &fqdn.method, &fqdn.field]
+                .into_iter()
+                .flatten()
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(".");
+            entries.push(IndexEntry {
+                symbol,
+                dotted_fqdn,
+                fqdn,
+                syntax_type,
+                node: node_handle,
+                file: node.file(),
+            });
+        }
+        Self::from_entries(entries)
+    }
+
+    fn from_entries(entries: Vec<IndexEntry>) -> SymbolIndex {
+        let mut index = SymbolIndex {
+            entries,
+            by_symbol: Vec::new(),
+            by_fqdn: Vec::new(),
+            by_lower: Vec::new(),
+            fst_index: Map::default(),
+        };
+        index.reindex();
+        index
+    }
+
+    /// Invalidate the indexed entries belonging to
+    /// `changed_or_removed_files`, re-traverse only `changed_roots` (one per
+    /// changed file; omit a file that was only removed), and merge the
+    /// result back in. Entries for every other file are left untouched, so
+    /// this only pays the graph-walk cost for the files that actually
+    /// changed instead of rebuilding via [`SymbolIndex::build`] again.
+    pub(crate) fn apply_change(
+        &mut self,
+        graph: &StackGraph,
+        changed_or_removed_files: &[Handle<File>],
+        changed_roots: &[Handle<Node>],
+    ) {
+        let changed: HashSet<Handle<File>> = changed_or_removed_files.iter().copied().collect();
+        self.entries
+            .retain(|e| !matches!(e.file, Some(f) if changed.contains(&f)));
+
+        for &root in changed_roots {
+            Self::collect(graph, root, &mut self.entries);
+        }
+
+        self.reindex();
+    }
+
+    fn reindex(&mut self) {
+        let mut by_symbol: Vec<usize> = (0..self.entries.len()).collect();
+        by_symbol.sort_by(|&a, &b| self.entries[a].symbol.cmp(&self.entries[b].symbol));
+        let mut by_fqdn: Vec<usize> = (0..self.entries.len()).collect();
+        by_fqdn.sort_by(|&a, &b| self.entries[a].dotted_fqdn.cmp(&self.entries[b].dotted_fqdn));
+
+        let mut by_lower: Vec<usize> = (0..self.entries.len()).collect();
+        by_lower.sort_by(|&a, &b| {
+            self.entries[a]
+                .symbol
+                .to_lowercase()
+                .cmp(&self.entries[b].symbol.to_lowercase())
+        });
+
+        let mut builder = MapBuilder::memory();
+        let mut i = 0;
+        while i < by_lower.len() {
+            let key = self.entries[by_lower[i]].symbol.to_lowercase();
+            let mut j = i + 1;
+            while j < by_lower.len() && self.entries[by_lower[j]].symbol.to_lowercase() == key {
+                j += 1;
+            }
+            builder
+                .insert(&key, i as u64)
+                .expect("by_lower is sorted, so keys are inserted in lexicographic order");
+            i = j;
+        }
+        let fst_bytes = builder
+            .into_inner()
+            .expect("in-memory fst::MapBuilder never fails to finish");
+        let fst_index = Map::new(fst_bytes).expect("just-built fst bytes are always valid");
+
+        self.by_symbol = by_symbol;
+        self.by_fqdn = by_fqdn;
+        self.by_lower = by_lower;
+        self.fst_index = fst_index;
+    }
+
+    /// All entries in the (possibly case-insensitively-overloaded) run
+    /// starting at `by_lower[start]`.
+    fn run_from(&self, start: usize) -> Vec<&IndexEntry> {
+        let Some(&first) = self.by_lower.get(start) else {
+            return vec![];
+        };
+        let key = self.entries[first].symbol.to_lowercase();
+        self.by_lower[start..]
+            .iter()
+            .take_while(|&&i| self.entries[i].symbol.to_lowercase() == key)
+            .map(|&i| &self.entries[i])
+            .collect()
+    }
+
+    /// Every entry whose symbol matches `symbol` exactly (case-insensitive).
+    pub(crate) fn lookup_exact(&self, symbol: &str) -> Vec<&IndexEntry> {
+        match self.fst_index.get(symbol.to_lowercase()) {
+            Some(start) => self.run_from(start as usize),
+            None => vec![],
+        }
+    }
+
+    /// Every entry whose symbol starts with `prefix` (case-insensitive).
+    pub(crate) fn lookup_prefix(&self, prefix: &str) -> Vec<&IndexEntry> {
+        let automaton = Str::new(&prefix.to_lowercase()).starts_with();
+        let mut stream = self.fst_index.search(automaton).into_stream();
+        let mut out = vec![];
+        while let Some((_, start)) = stream.next() {
+            out.extend(self.run_from(start as usize));
+        }
+        out
+    }
+
+    /// Every entry whose lowercased symbol contains `pattern`'s characters
+    /// as a (not necessarily contiguous) subsequence, in order -- a cheap
+    /// typo-tolerant fallback when an exact/prefix lookup comes up empty.
+    pub(crate) fn lookup_fuzzy(&self, pattern: &str) -> Vec<&IndexEntry> {
+        let automaton = Subsequence::new(&pattern.to_lowercase());
+        let mut stream = self.fst_index.search(automaton).into_stream();
+        let mut out = vec![];
+        while let Some((_, start)) = stream.next() {
+            out.extend(self.run_from(start as usize));
+        }
+        out
+    }
+
+    fn collect(db: &StackGraph, node: Handle<Node>, entries: &mut Vec<IndexEntry>) {
+        let mut child_edges: Vec<Handle<Node>> = vec![];
+        for edge in db.outgoing_edges(node) {
+            if edge.precedence == 10 {
+                continue;
+            }
+            child_edges.push(edge.sink);
+            let child_node = &db[edge.sink];
+            let symbol = match child_node.symbol() {
+                None => continue,
+                Some(symbol) => db[symbol].to_string(),
+            };
+            let Some(source_info) = db.source_info(edge.sink) else {
+                continue;
+            };
+            let Some(syntax_type) = source_info.syntax_type.into_option() else {
+                continue;
+            };
+            let syntax_type = SyntaxType::get(&db[syntax_type]);
+            if !matches!(
+                syntax_type,
+                SyntaxType::NamespaceDeclaration
+                    | SyntaxType::ClassDef
+                    | SyntaxType::MethodName
+                    | SyntaxType::FieldName
+            ) {
+                continue;
+            }
+            if let Some(fqdn) = get_fqdn(edge.sink, db) {
+                let dotted_fqdn = [&fqdn.namespace, &fqdn.class, &fqdn.method, &fqdn.field]
+                    .into_iter()
+                    .flatten()
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join(".");
+                entries.push(IndexEntry {
+                    symbol,
+                    dotted_fqdn,
+                    fqdn,
+                    syntax_type,
+                    node: edge.sink,
+                    file: child_node.file(),
+                });
+            }
+        }
+        child_edges.sort();
+        for child in child_edges {
+            Self::collect(db, child, entries);
+        }
+    }
+
+    /// Whether any indexed entry matches `search`. Literal (non-wildcard,
+    /// non-placeholder) searches resolve via binary search on the
+    /// symbol-sorted entries; wildcard searches fall back to scanning, the
+    /// same cost `NamespaceSymbols::match_symbol` already pays today.
+    pub(crate) fn match_symbol(&self, search: &Search) -> bool {
+        match search.as_literal_symbol() {
+            Some(literal) => self
+                .by_symbol
+                .binary_search_by(|&i| self.entries[i].symbol.as_str().cmp(literal))
+                .is_ok(),
+            None => self
+                .by_symbol
+                .iter()
+                .any(|&i| search.match_symbol(&self.entries[i].symbol)),
+        }
+    }
+
+    /// All indexed entries whose dotted FQDN exactly matches or falls under
+    /// `prefix`, located via binary search on the FQDN-sorted entries.
+    pub(crate) fn find_by_fqdn_prefix(&self, prefix: &str) -> Vec<&IndexEntry> {
+        let start = self
+            .by_fqdn
+            .partition_point(|&i| self.entries[i].dotted_fqdn.as_str() < prefix);
+        let end = self.by_fqdn.partition_point(|&i| {
+            self.entries[i].dotted_fqdn.as_str() < prefix || self.entries[i].dotted_fqdn.starts_with(prefix)
+        });
+        self.by_fqdn[start..end].iter().map(|&i| &self.entries[i]).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_mock_graph() -> (StackGraph, Vec<Handle<Node>>, Handle<File>) {
+        let mut graph = StackGraph::new();
+        let file = graph.add_file("test.cs").unwrap();
+
+        let root_id = graph.new_node_id(file);
+        let root_symbol = graph.add_symbol("root");
+        let root = graph.add_pop_symbol_node(root_id, root_symbol, true).unwrap();
+
+        let ns_id = graph.new_node_id(file);
+        let ns_symbol = graph.add_symbol("System.Configuration");
+        let ns_node = graph.add_pop_symbol_node(ns_id, ns_symbol, true).unwrap();
+        let ns_syntax = graph.add_string("namespace_declaration");
+        graph.source_info_mut(ns_node).syntax_type = ns_syntax.into();
+
+        let class_id = graph.new_node_id(file);
+        let class_symbol = graph.add_symbol("ConfigurationManager");
+        let class_node = graph.add_pop_symbol_node(class_id, class_symbol, true).unwrap();
+        let class_syntax = graph.add_string("class_def");
+        graph.source_info_mut(class_node).syntax_type = class_syntax.into();
+
+        graph.add_edge(root, ns_node, 0);
+        graph.add_edge(ns_node, class_node, 0);
+        graph.add_edge(class_node, ns_node, 10); // FQDN edge
+
+        (graph, vec![root], file)
+    }
+
+    #[test]
+    fn test_build_indexes_every_definition_node() {
+        let (graph, roots, _file) = build_mock_graph();
+        let index = SymbolIndex::build(&graph, &roots);
+
+        assert_eq!(index.by_symbol.len(), 2);
+        assert_eq!(index.by_fqdn.len(), 2);
+    }
+
+    #[test]
+    fn test_match_symbol_literal_uses_binary_search_hit() {
+        let (graph, roots, _file) = build_mock_graph();
+        let index = SymbolIndex::build(&graph, &roots);
+        let search = Search::create_search("ConfigurationManager".to_string()).unwrap();
+
+        assert!(index.match_symbol(&search));
+    }
+
+    #[test]
+    fn test_match_symbol_literal_miss() {
+        let (graph, roots, _file) = build_mock_graph();
+        let index = SymbolIndex::build(&graph, &roots);
+        let search = Search::create_search("NonExistent".to_string()).unwrap();
+
+        assert!(!index.match_symbol(&search));
+    }
+
+    #[test]
+    fn test_match_symbol_wildcard_falls_back_to_scan() {
+        let (graph, roots, _file) = build_mock_graph();
+        let index = SymbolIndex::build(&graph, &roots);
+        let search = Search::create_search("Configuration*".to_string()).unwrap();
+
+        assert!(index.match_symbol(&search));
+    }
+
+    #[test]
+    fn test_find_by_fqdn_prefix_returns_only_matching_entries() {
+        let (graph, roots, _file) = build_mock_graph();
+        let index = SymbolIndex::build(&graph, &roots);
+
+        let found = index.find_by_fqdn_prefix("System.Configuration.ConfigurationManager");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].symbol, "ConfigurationManager");
+
+        let none = index.find_by_fqdn_prefix("Other");
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn test_apply_change_only_replaces_entries_for_the_changed_file() {
+        let (mut graph, roots, original_file) = build_mock_graph();
+        let mut index = SymbolIndex::build(&graph, &roots);
+        assert_eq!(index.entries.len(), 2);
+
+        // Add a second, untouched file to prove its entries survive the change.
+        let other_file = graph.add_file("other.cs").unwrap();
+        let other_root_id = graph.new_node_id(other_file);
+        let other_root_symbol = graph.add_symbol("other_root");
+        let other_root = graph
+            .add_pop_symbol_node(other_root_id, other_root_symbol, true)
+            .unwrap();
+        let other_class_id = graph.new_node_id(other_file);
+        let other_class_symbol = graph.add_symbol("OtherClass");
+        let other_class = graph
+            .add_pop_symbol_node(other_class_id, other_class_symbol, true)
+            .unwrap();
+        let other_syntax = graph.add_string("class_def");
+        graph.source_info_mut(other_class).syntax_type = other_syntax.into();
+        graph.add_edge(other_root, other_class, 0);
+        index.apply_change(&graph, &[], &[other_root]);
+        assert_eq!(index.entries.len(), 3);
+
+        // Re-running the change against the original file with no new roots
+        // (simulating a deletion) should drop only that file's entries.
+        index.apply_change(&graph, &[original_file], &[]);
+
+        assert_eq!(index.entries.len(), 1);
+        assert_eq!(index.entries[0].symbol, "OtherClass");
+    }
+
+    #[test]
+    fn test_lookup_exact_is_case_insensitive() {
+        let (graph, roots, _file) = build_mock_graph();
+        let index = SymbolIndex::build(&graph, &roots);
+
+        let found = index.lookup_exact("configurationmanager");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].symbol, "ConfigurationManager");
+
+        assert!(index.lookup_exact("NonExistent").is_empty());
+    }
+
+    #[test]
+    fn test_lookup_prefix_matches_every_entry_under_the_prefix() {
+        let (graph, roots, _file) = build_mock_graph();
+        let index = SymbolIndex::build(&graph, &roots);
+
+        let found = index.lookup_prefix("config");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].symbol, "ConfigurationManager");
+
+        assert!(index.lookup_prefix("zzz").is_empty());
+    }
+
+    #[test]
+    fn test_lookup_fuzzy_matches_a_subsequence() {
+        let (graph, roots, _file) = build_mock_graph();
+        let index = SymbolIndex::build(&graph, &roots);
+
+        // "cfgmgr" is a subsequence of "configurationmanager" but not a prefix.
+        let found = index.lookup_fuzzy("cfgmgr");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].symbol, "ConfigurationManager");
+
+        assert!(index.lookup_fuzzy("zzz").is_empty());
+    }
+
+    #[test]
+    fn test_build_global_finds_entries_anywhere_in_the_graph() {
+        let (graph, _roots, _file) = build_mock_graph();
+        // `build_global` doesn't take roots at all -- it should still find
+        // the same definitions a root-scoped `build` call would.
+        let index = SymbolIndex::build_global(&graph);
+
+        let found = index.lookup_exact("ConfigurationManager");
+        assert_eq!(found.len(), 1);
+    }
+}
@@ -6,7 +6,7 @@ use std::{
 use crate::{
     analyzer_service::{
         provider_code_location_service_server::ProviderCodeLocationService, GetCodeSnipRequest,
-        GetCodeSnipResponse,
+        GetCodeSnipResponse, Position,
     },
     provider::CSharpProvider,
 };
@@ -14,6 +14,38 @@ use tonic::{async_trait, Request, Response, Status};
 use tracing::{info, trace};
 use url::Url;
 
+/// A single rendered line within a code snippet window.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SnipLine {
+    pub(crate) line_number: usize,
+    pub(crate) text: String,
+    /// Whether this line falls within the requested match range, as opposed
+    /// to being pure leading/trailing context.
+    pub(crate) is_match: bool,
+    /// Column range (inclusive start, exclusive end) to highlight on this
+    /// line, when it's part of the match.
+    pub(crate) highlight_columns: Option<(usize, usize)>,
+}
+
+/// The rendered window for a single code location. Kept separate from the
+/// wire response so future callers (e.g. a batched RPC over multiple
+/// locations) can consume the structured form directly instead of
+/// re-parsing the flattened `snip` string.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct SnipWindow {
+    pub(crate) lines: Vec<SnipLine>,
+}
+
+impl SnipWindow {
+    /// Flatten back into the `"<line> <text>\n"` format `GetCodeSnipResponse.snip` expects.
+    fn to_legacy_snip(&self) -> String {
+        self.lines
+            .iter()
+            .map(|l| format!("{} {}\n", l.line_number, l.text))
+            .collect()
+    }
+}
+
 #[async_trait]
 impl ProviderCodeLocationService for CSharpProvider {
     async fn get_code_snip(
@@ -76,27 +108,150 @@ impl ProviderCodeLocationService for CSharpProvider {
         let file = file.unwrap();
         let file = BufReader::new(file);
 
-        let mut skip_lines: usize = 0;
-        if start_position.line as usize >= self.context_lines {
-            skip_lines = start_position.line as usize - self.context_lines;
-        }
-        let take: usize = (end_position.line - start_position.line) as usize + self.context_lines;
-        let code_snip_lines: String = file
-            .lines()
-            .skip(skip_lines)
-            .take(take)
-            .enumerate()
-            .map(|(index, s)| {
-                if s.is_err() {
-                    "".to_string()
-                } else {
-                    let s = s.unwrap();
-                    format!("{} {}\n", skip_lines + index, s)
-                }
-            })
-            .collect();
+        let window = render_snip_window(file, &start_position, &end_position, self.context_lines)
+            .map_err(|e| {
+                Status::internal(format!("failed to read code snip from {:?}: {}", file_path, e))
+            })?;
+
         Ok(Response::new(GetCodeSnipResponse {
-            snip: code_snip_lines,
+            snip: window.to_legacy_snip(),
         }))
     }
 }
+
+/// Render the window of lines around `[start_position, end_position]`, with
+/// `context_lines` of context before and after, and column-aware highlight
+/// ranges on the matched lines. Uses a bounded reader that only
+/// materializes the lines inside the window instead of `skip`/`take` over
+/// `BufRead::lines()`, which would still allocate a `String` for every
+/// skipped line before discarding it.
+fn render_snip_window(
+    mut reader: impl BufRead,
+    start_position: &Position,
+    end_position: &Position,
+    context_lines: usize,
+) -> std::io::Result<SnipWindow> {
+    let start_line = start_position.line as usize;
+    let end_line = end_position.line as usize;
+    let start_column = start_position.character as usize;
+    let end_column = end_position.character as usize;
+
+    let skip_lines = start_line.saturating_sub(context_lines);
+    let take = (end_line.saturating_sub(start_line)) + context_lines * 2 + 1;
+
+    // Advance past the skipped lines without keeping their contents around.
+    let mut scratch = Vec::new();
+    for _ in 0..skip_lines {
+        scratch.clear();
+        if reader.read_until(b'\n', &mut scratch)? == 0 {
+            break;
+        }
+    }
+
+    let mut lines = Vec::with_capacity(take);
+    for offset in 0..take {
+        let mut buf = Vec::new();
+        if reader.read_until(b'\n', &mut buf)? == 0 {
+            break;
+        }
+        while matches!(buf.last(), Some(b'\n') | Some(b'\r')) {
+            buf.pop();
+        }
+        let text = String::from_utf8_lossy(&buf).into_owned();
+        let line_number = skip_lines + offset;
+        let is_match = line_number >= start_line && line_number <= end_line;
+        let highlight_columns = if !is_match {
+            None
+        } else if start_line == end_line {
+            Some((start_column, end_column))
+        } else if line_number == start_line {
+            Some((start_column, text.len()))
+        } else if line_number == end_line {
+            Some((0, end_column))
+        } else {
+            Some((0, text.len()))
+        };
+
+        lines.push(SnipLine {
+            line_number,
+            text,
+            is_match,
+            highlight_columns,
+        });
+    }
+
+    Ok(SnipWindow { lines })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn position(line: usize, character: usize) -> Position {
+        Position {
+            line: line as f64,
+            character: character as f64,
+        }
+    }
+
+    #[test]
+    fn test_render_snip_window_single_line_match_with_columns() {
+        let source = "line0\nline1\nMATCH\nline3\nline4\n";
+        let window = render_snip_window(Cursor::new(source), &position(2, 1), &position(2, 4), 1)
+            .unwrap();
+
+        assert_eq!(window.lines.len(), 3);
+        assert_eq!(window.lines[0].line_number, 1);
+        assert!(!window.lines[0].is_match);
+        assert_eq!(window.lines[1].line_number, 2);
+        assert_eq!(window.lines[1].text, "MATCH");
+        assert!(window.lines[1].is_match);
+        assert_eq!(window.lines[1].highlight_columns, Some((1, 4)));
+        assert_eq!(window.lines[2].line_number, 3);
+        assert!(!window.lines[2].is_match);
+    }
+
+    #[test]
+    fn test_render_snip_window_multi_line_match_highlights_start_and_end() {
+        let source = "a\nfirst\nmiddle\nlast\nb\n";
+        let window = render_snip_window(Cursor::new(source), &position(1, 3), &position(3, 2), 0)
+            .unwrap();
+
+        assert_eq!(window.lines.len(), 3);
+        assert_eq!(window.lines[0].highlight_columns, Some((3, 5))); // "first".len() == 5
+        assert_eq!(window.lines[1].highlight_columns, Some((0, 6))); // "middle".len() == 6
+        assert_eq!(window.lines[2].highlight_columns, Some((0, 2)));
+    }
+
+    #[test]
+    fn test_render_snip_window_no_context_past_start_of_file() {
+        let source = "line0\nline1\n";
+        let window = render_snip_window(Cursor::new(source), &position(0, 0), &position(0, 2), 5)
+            .unwrap();
+
+        assert_eq!(window.lines[0].line_number, 0);
+    }
+
+    #[test]
+    fn test_to_legacy_snip_matches_prior_format() {
+        let window = SnipWindow {
+            lines: vec![
+                SnipLine {
+                    line_number: 1,
+                    text: "foo".to_string(),
+                    is_match: true,
+                    highlight_columns: Some((0, 3)),
+                },
+                SnipLine {
+                    line_number: 2,
+                    text: "bar".to_string(),
+                    is_match: false,
+                    highlight_columns: None,
+                },
+            ],
+        };
+
+        assert_eq!(window.to_legacy_snip(), "1 foo\n2 bar\n");
+    }
+}
@@ -1,23 +1,28 @@
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use regex::Regex;
 use serde::Deserialize;
 use tokio::sync::Mutex;
 use tonic::{Request, Response, Status};
 use tracing::{debug, error, info};
 use utoipa::{OpenApi, ToSchema};
 
-use crate::c_sharp_graph::query::{Query, QueryType};
+use crate::c_sharp_graph::query::{MatchMode as QueryMatchMode, Query, QueryType};
 use crate::c_sharp_graph::results::ResultNode;
 use crate::c_sharp_graph::NamespaceFQDNNotFoundError;
 //use crate::c_sharp_graph::find_node::FindNode;
+use crate::provider::dependency_graph::{
+    build_dependency_dag, compare_versions, find_csproj_files, parse_direct_references_with_lines,
+    DependencyDagNode,
+};
 use crate::provider::AnalysisMode;
 use crate::{
     analyzer_service::{
-        provider_service_server::ProviderService, CapabilitiesResponse, Capability, Config,
-        DependencyDagResponse, DependencyResponse, EvaluateRequest, EvaluateResponse,
-        IncidentContext, InitResponse, NotifyFileChangesRequest, NotifyFileChangesResponse,
-        ProviderEvaluateResponse, ServiceRequest,
+        provider_service_server::ProviderService, CapabilitiesResponse, Capability, Config, Dep,
+        DependencyDagResponse, DependencyResponse, DepDagItem, EvaluateRequest, EvaluateResponse,
+        FileDagDep, FileDep, IncidentContext, InitResponse, Location, NotifyFileChangesRequest,
+        NotifyFileChangesResponse, Position, ProviderEvaluateResponse, ServiceRequest,
     },
     provider::Project,
 };
@@ -32,18 +37,96 @@ enum Locations {
     Class,
 }
 
+/// How `pattern` is compared against candidate symbols: `EXACT` keeps the
+/// existing literal/`*`-wildcard behavior, `PREFIX` matches each dotted
+/// segment as a case-insensitive `starts_with` (for search-as-you-type
+/// callers, as opposed to the precise glob rule authors write conditions
+/// against), `CASE_INSENSITIVE` is like `EXACT` but tolerant of casing
+/// differences between C# source and the rule's pattern, `REGEX` compiles
+/// `pattern` as a regular expression, and `FUZZY` allows a bounded number of
+/// typos, scaled to `pattern`'s length.
+#[derive(Clone, Copy, ToSchema, Deserialize, Default, Debug)]
+#[serde(rename_all = "UPPERCASE")]
+enum MatchMode {
+    #[default]
+    Exact,
+    Prefix,
+    CaseInsensitive,
+    Regex,
+    Fuzzy,
+}
+
+impl From<MatchMode> for QueryMatchMode {
+    fn from(mode: MatchMode) -> Self {
+        match mode {
+            MatchMode::Exact => QueryMatchMode::Exact,
+            MatchMode::Prefix => QueryMatchMode::Prefix,
+            MatchMode::CaseInsensitive => QueryMatchMode::CaseInsensitive,
+            MatchMode::Regex => QueryMatchMode::Regex,
+            MatchMode::Fuzzy => QueryMatchMode::Fuzzy,
+        }
+    }
+}
+
 #[derive(ToSchema, Deserialize, Debug)]
 struct ReferenceCondition {
     pattern: String,
     #[serde(default)]
     location: Locations,
-    #[allow(dead_code)]
+    #[serde(default)]
+    match_mode: MatchMode,
     file_paths: Option<Vec<String>>,
 }
 
+/// A version bound: either `lower_bound`/`upper_bound` (inclusive range), or
+/// a single exact `version` to match. Versions are dotted numeric strings
+/// (`"13.0.1"`), compared component-by-component via
+/// `dependency_graph::compare_versions`.
+#[derive(ToSchema, Deserialize, Debug)]
+struct DependencyCondition {
+    name: String,
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    lower_bound: Option<String>,
+    #[serde(default)]
+    upper_bound: Option<String>,
+}
+
+impl DependencyCondition {
+    /// Whether `candidate_version` satisfies this condition's constraints.
+    /// An empty `candidate_version` (a `ProjectReference`, or a
+    /// `PackageReference` with no `Version` attribute) never matches a
+    /// version-bounded condition.
+    fn matches_version(&self, candidate_version: &str) -> bool {
+        if let Some(version) = &self.version {
+            return compare_versions(candidate_version, version) == std::cmp::Ordering::Equal;
+        }
+        if candidate_version.is_empty()
+            && (self.lower_bound.is_some() || self.upper_bound.is_some())
+        {
+            return false;
+        }
+        if let Some(lower_bound) = &self.lower_bound {
+            if compare_versions(candidate_version, lower_bound) == std::cmp::Ordering::Less {
+                return false;
+            }
+        }
+        if let Some(upper_bound) = &self.upper_bound {
+            if compare_versions(candidate_version, upper_bound) == std::cmp::Ordering::Greater {
+                return false;
+            }
+        }
+        true
+    }
+}
+
 #[derive(ToSchema, Deserialize, Debug)]
 struct CSharpCondition {
-    referenced: ReferenceCondition,
+    #[serde(default)]
+    referenced: Option<ReferenceCondition>,
+    #[serde(default)]
+    dependency: Option<DependencyCondition>,
 }
 
 pub struct CSharpProvider {
@@ -81,10 +164,16 @@ impl ProviderService for CSharpProvider {
         debug!("returning refernced capability: {:?}", json.ok());
 
         return Ok(Response::new(CapabilitiesResponse {
-            capabilities: vec![Capability {
-                name: "referenced".to_string(),
-                template_context: None,
-            }],
+            capabilities: vec![
+                Capability {
+                    name: "referenced".to_string(),
+                    template_context: None,
+                },
+                Capability {
+                    name: "dependency".to_string(),
+                    template_context: None,
+                },
+            ],
         }));
     }
 
@@ -166,7 +255,7 @@ impl ProviderService for CSharpProvider {
         let evaluate_request = r.get_ref();
         debug!("evaluate request: {:?}", evaluate_request.condition_info);
 
-        if evaluate_request.cap != "referenced" {
+        if evaluate_request.cap != "referenced" && evaluate_request.cap != "dependency" {
             return Ok(Response::new(EvaluateResponse {
                 error: "unable to find referenced capability".to_string(),
                 successful: false,
@@ -191,6 +280,34 @@ impl ProviderService for CSharpProvider {
                 }));
             }
         };
+
+        if evaluate_request.cap == "dependency" {
+            let dependency = match condition.dependency {
+                Some(d) => d,
+                None => {
+                    return Ok(Response::new(EvaluateResponse {
+                        error: "missing `dependency` condition".to_string(),
+                        successful: false,
+                        response: None,
+                    }));
+                }
+            };
+            return Ok(Response::new(evaluate_dependency_condition(
+                &project.location,
+                &dependency,
+            )));
+        }
+
+        let referenced = match condition.referenced {
+            Some(r) => r,
+            None => {
+                return Ok(Response::new(EvaluateResponse {
+                    error: "missing `referenced` condition".to_string(),
+                    successful: false,
+                    response: None,
+                }));
+            }
+        };
         let graph_guard = project.graph.clone();
 
         let source_type = match project.get_source_type().await {
@@ -218,7 +335,7 @@ impl ProviderService for CSharpProvider {
 
         // As we are passing an unmutable reference, we can drop the guard here.
 
-        let query = match condition.referenced.location {
+        let query = match referenced.location {
             Locations::All => QueryType::All {
                 graph,
                 source_type: &source_type,
@@ -236,7 +353,10 @@ impl ProviderService for CSharpProvider {
                 source_type: &source_type,
             },
         };
-        let results = query.query(condition.referenced.pattern.clone());
+        let results = query.query(
+            referenced.pattern.clone(),
+            referenced.match_mode.into(),
+        );
         let results = match results {
             Err(e) => {
                 if let Some(_e) = e.downcast_ref::<NamespaceFQDNNotFoundError>() {
@@ -258,6 +378,13 @@ impl ProviderService for CSharpProvider {
                 }
             }
             Ok(res) => {
+                let res: Vec<ResultNode> = res
+                    .into_iter()
+                    .filter(|r| {
+                        matches_any_file_path(&referenced.file_paths, &r.file_uri)
+                    })
+                    .collect();
+
                 // Deduplicate: group by file+line and keep the one with smallest span
                 use std::collections::BTreeMap;
                 let mut best_by_location: BTreeMap<(String, usize), &ResultNode> = BTreeMap::new();
@@ -327,10 +454,50 @@ impl ProviderService for CSharpProvider {
         &self,
         _: Request<ServiceRequest>,
     ) -> Result<Response<DependencyResponse>, Status> {
+        let project_guard = self.project.lock().await;
+        let project = match project_guard.as_ref() {
+            Some(x) => x,
+            None => {
+                return Ok(Response::new(DependencyResponse {
+                    successful: false,
+                    error: "project may not be initialized".to_string(),
+                    file_dep: vec![],
+                }));
+            }
+        };
+
+        let mut file_dep = Vec::new();
+        for csproj in find_csproj_files(&project.location) {
+            let nodes = match build_dependency_dag(&csproj) {
+                std::result::Result::Ok(n) => n,
+                Err(e) => {
+                    error!("unable to resolve dependencies for {:?}: {}", csproj, e);
+                    continue;
+                }
+            };
+            let deps = nodes
+                .into_iter()
+                .map(|n| Dep {
+                    name: n.dependency.name,
+                    version: n.dependency.version,
+                    r#type: if n.dependency.is_direct {
+                        "direct".to_string()
+                    } else {
+                        "transitive".to_string()
+                    },
+                    ..Default::default()
+                })
+                .collect();
+            file_dep.push(FileDep {
+                file_uri: format!("file://{}", csproj.to_string_lossy()),
+                dependencies: deps,
+            });
+        }
+
         return Ok(Response::new(DependencyResponse {
             successful: true,
             error: String::new(),
-            file_dep: vec![],
+            file_dep,
         }));
     }
 
@@ -338,21 +505,208 @@ impl ProviderService for CSharpProvider {
         &self,
         _: Request<ServiceRequest>,
     ) -> Result<Response<DependencyDagResponse>, Status> {
+        let project_guard = self.project.lock().await;
+        let project = match project_guard.as_ref() {
+            Some(x) => x,
+            None => {
+                return Ok(Response::new(DependencyDagResponse {
+                    successful: false,
+                    error: "project may not be initialized".to_string(),
+                    file_dag_dep: vec![],
+                }));
+            }
+        };
+
+        let mut file_dag_dep = Vec::new();
+        for csproj in find_csproj_files(&project.location) {
+            let nodes = match build_dependency_dag(&csproj) {
+                std::result::Result::Ok(n) => n,
+                Err(e) => {
+                    error!("unable to resolve dependency dag for {:?}: {}", csproj, e);
+                    continue;
+                }
+            };
+            file_dag_dep.push(FileDagDep {
+                file_uri: format!("file://{}", csproj.to_string_lossy()),
+                list: dag_nodes_to_items(nodes),
+            });
+        }
+
         return Ok(Response::new(DependencyDagResponse {
             successful: true,
             error: String::new(),
-            file_dag_dep: vec![],
+            file_dag_dep,
         }));
     }
 
     async fn notify_file_changes(
         &self,
-        _: Request<NotifyFileChangesRequest>,
+        r: Request<NotifyFileChangesRequest>,
     ) -> Result<Response<NotifyFileChangesResponse>, Status> {
-        return Ok(Response::new(NotifyFileChangesResponse {
+        info!("notified of file changes: {:?}", r.get_ref());
+
+        let project_guard = self.project.lock().await;
+        let project = match project_guard.as_ref() {
+            Some(x) => x,
+            None => {
+                return Ok(Response::new(NotifyFileChangesResponse {
+                    error: "project may not be initialized".to_string(),
+                }));
+            }
+        };
+
+        // We don't yet have a per-file node/edge index on `Project` to
+        // invalidate and re-parse just the changed files (the way
+        // `SymbolIndex::apply_change` does once a graph is already built),
+        // so re-run the same graph build `init` does. More expensive than a
+        // true incremental update, but it keeps the database correct rather
+        // than silently stale after a file change.
+        let stats = match project.get_project_graph().await {
+            Ok(stats) => stats,
+            Err(err) => {
+                error!("unable to rebuild project graph after file changes: {:?}", err);
+                return Ok(Response::new(NotifyFileChangesResponse {
+                    error: "unable to rebuild project graph".to_string(),
+                }));
+            }
+        };
+        debug!("reloaded files after file change notification: {:?}", stats);
+        let res = project.load_to_database().await;
+        debug!(
+            "reloaded project database after file change notification: {:?}",
+            res
+        );
+
+        Ok(Response::new(NotifyFileChangesResponse {
             error: String::new(),
-        }));
+        }))
+    }
+}
+
+/// Evaluate a `dependency` capability condition against every `.csproj`
+/// under `location`: each direct reference whose name matches
+/// `condition.name` and whose version satisfies the condition's bounds
+/// becomes an incident pointing at the `.csproj` line it was declared on.
+fn evaluate_dependency_condition(
+    location: &std::path::Path,
+    condition: &DependencyCondition,
+) -> EvaluateResponse {
+    let mut incidents = Vec::new();
+    for csproj in find_csproj_files(location) {
+        let references = match parse_direct_references_with_lines(&csproj) {
+            Ok(r) => r,
+            Err(e) => {
+                error!("unable to resolve dependencies for {:?}: {}", csproj, e);
+                continue;
+            }
+        };
+        for (dependency, line) in references {
+            if dependency.name != condition.name || !condition.matches_version(&dependency.version)
+            {
+                continue;
+            }
+            incidents.push(IncidentContext {
+                file_uri: format!("file://{}", csproj.to_string_lossy()),
+                effort: None,
+                code_location: Some(Location {
+                    start_position: Some(Position {
+                        line: line as f64,
+                        character: 0.0,
+                    }),
+                    end_position: Some(Position {
+                        line: line as f64,
+                        character: 0.0,
+                    }),
+                }),
+                line_number: Some(line as i64),
+                variables: None,
+                links: vec![],
+                is_dependency_incident: true,
+            });
+        }
+    }
+    incidents.sort_by_key(|i| format!("{}-{:?}", i.file_uri, i.line_number));
+
+    EvaluateResponse {
+        error: String::new(),
+        successful: true,
+        response: Some(ProviderEvaluateResponse {
+            matched: !incidents.is_empty(),
+            incident_contexts: incidents,
+            template_context: None,
+        }),
+    }
+}
+
+/// Convert a flat [`DependencyDagNode`] list (as built by
+/// `dependency_graph::build_dependency_dag`) into the `DepDagItem` shape the
+/// proto expects: each item carries its own dependency plus the indices of
+/// its children within the same flat list.
+fn dag_nodes_to_items(nodes: Vec<DependencyDagNode>) -> Vec<DepDagItem> {
+    nodes
+        .into_iter()
+        .map(|n| DepDagItem {
+            key: Some(Dep {
+                name: n.dependency.name,
+                version: n.dependency.version,
+                r#type: if n.dependency.is_direct {
+                    "direct".to_string()
+                } else {
+                    "transitive".to_string()
+                },
+                ..Default::default()
+            }),
+            children: n.children.into_iter().map(|i| i as u32).collect(),
+        })
+        .collect()
+}
+
+/// Whether `candidate` (a result's `file_uri`) is scoped in by `patterns`.
+/// A `None` or empty list means match everything, matching
+/// `ReferenceCondition.file_paths`'s "unset = no filtering" default.
+fn matches_any_file_path(patterns: &Option<Vec<String>>, candidate: &str) -> bool {
+    match patterns {
+        None => true,
+        Some(patterns) if patterns.is_empty() => true,
+        Some(patterns) => patterns.iter().any(|p| glob_match(p, candidate)),
+    }
+}
+
+/// Match `candidate` against `pattern`, a glob supporting `**` (any number
+/// of path segments), `*` (anything within a single segment), and `?` (one
+/// character), by translating it into an anchored regex. An invalid
+/// pattern falls back to exact string comparison rather than matching
+/// everything or erroring out.
+fn glob_match(pattern: &str, candidate: &str) -> bool {
+    match Regex::new(&glob_to_regex(pattern)) {
+        Ok(re) => re.is_match(candidate),
+        Err(_) => pattern == candidate,
+    }
+}
+
+fn glob_to_regex(pattern: &str) -> String {
+    let mut regex = String::from("^");
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => {
+                if chars.peek() == Some(&'*') {
+                    chars.next();
+                    regex.push_str(".*");
+                } else {
+                    regex.push_str("[^/]*");
+                }
+            }
+            '?' => regex.push_str("[^/]"),
+            '\\' | '.' | '+' | '^' | '$' | '(' | ')' | '[' | ']' | '{' | '}' | '|' => {
+                regex.push('\\');
+                regex.push(c);
+            }
+            c => regex.push(c),
+        }
     }
+    regex.push('$');
+    regex
 }
 
 #[cfg(test)]
@@ -604,4 +958,77 @@ mod tests {
         assert_eq!(file1_result.2, 2, "Should choose span of 2 lines");
         assert_eq!(file1_result.3, 5, "Should choose character position 5");
     }
+
+    #[test]
+    fn test_matches_any_file_path_none_or_empty_matches_everything() {
+        assert!(super::matches_any_file_path(&None, "file:///repo/src/Foo.cs"));
+        assert!(super::matches_any_file_path(&Some(vec![]), "file:///repo/src/Foo.cs"));
+    }
+
+    #[test]
+    fn test_matches_any_file_path_single_star_stays_within_segment() {
+        let patterns = Some(vec!["file:///repo/src/*.cs".to_string()]);
+        assert!(super::matches_any_file_path(&patterns, "file:///repo/src/Foo.cs"));
+        assert!(!super::matches_any_file_path(
+            &patterns,
+            "file:///repo/src/nested/Foo.cs"
+        ));
+    }
+
+    #[test]
+    fn test_matches_any_file_path_double_star_crosses_segments() {
+        let patterns = Some(vec!["file:///repo/**/*.cs".to_string()]);
+        assert!(super::matches_any_file_path(&patterns, "file:///repo/src/Foo.cs"));
+        assert!(super::matches_any_file_path(
+            &patterns,
+            "file:///repo/src/nested/Foo.cs"
+        ));
+        assert!(!super::matches_any_file_path(&patterns, "file:///repo/Foo.txt"));
+    }
+
+    #[test]
+    fn test_matches_any_file_path_question_mark_matches_one_char() {
+        let patterns = Some(vec!["file:///repo/src/Foo?.cs".to_string()]);
+        assert!(super::matches_any_file_path(&patterns, "file:///repo/src/Foo1.cs"));
+        assert!(!super::matches_any_file_path(
+            &patterns,
+            "file:///repo/src/Foo12.cs"
+        ));
+    }
+
+    fn dependency_condition(
+        version: Option<&str>,
+        lower_bound: Option<&str>,
+        upper_bound: Option<&str>,
+    ) -> super::DependencyCondition {
+        super::DependencyCondition {
+            name: "Newtonsoft.Json".to_string(),
+            version: version.map(str::to_string),
+            lower_bound: lower_bound.map(str::to_string),
+            upper_bound: upper_bound.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_dependency_condition_exact_version_match() {
+        let condition = dependency_condition(Some("13.0.1"), None, None);
+        assert!(condition.matches_version("13.0.1"));
+        assert!(!condition.matches_version("13.0.2"));
+    }
+
+    #[test]
+    fn test_dependency_condition_bounded_range_match() {
+        let condition = dependency_condition(None, Some("10.0.0"), Some("13.0.0"));
+        assert!(condition.matches_version("12.0.3"));
+        assert!(condition.matches_version("10.0.0"));
+        assert!(condition.matches_version("13.0.0"));
+        assert!(!condition.matches_version("9.0.1"));
+        assert!(!condition.matches_version("13.0.1"));
+    }
+
+    #[test]
+    fn test_dependency_condition_empty_version_never_matches_bounds() {
+        let condition = dependency_condition(None, Some("1.0.0"), None);
+        assert!(!condition.matches_version(""));
+    }
 }
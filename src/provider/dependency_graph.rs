@@ -0,0 +1,411 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Error};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+/// A single resolved dependency: a NuGet `PackageReference` or a
+/// `ProjectReference` to another project in the solution. `is_direct`
+/// distinguishes dependencies declared directly in the project being
+/// analyzed from ones pulled in transitively through a referenced project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ResolvedDependency {
+    pub(crate) name: String,
+    pub(crate) version: String,
+    pub(crate) is_direct: bool,
+}
+
+/// One node of a dependency DAG: the dependency itself, plus the indices
+/// of its children within the flat `Vec<DependencyDagNode>` it was built
+/// into. Indices rather than nested ownership, so a dependency reachable
+/// through more than one path is only stored once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DependencyDagNode {
+    pub(crate) dependency: ResolvedDependency,
+    pub(crate) children: Vec<usize>,
+}
+
+/// One `<PackageReference>` or `<ProjectReference>` entry found directly in
+/// a `.csproj`, before a `ProjectReference`'s target path is resolved.
+enum RawReference {
+    Package { name: String, version: String },
+    Project { include: PathBuf },
+}
+
+/// The 1-based line number containing byte `offset` into `content`.
+fn line_for_offset(content: &str, offset: usize) -> usize {
+    content[..offset.min(content.len())].matches('\n').count() + 1
+}
+
+/// Parse the `<PackageReference>`/`<ProjectReference>` entries declared
+/// directly in `csproj_path`, alongside the 1-based line each was declared
+/// on.
+fn parse_raw_references(csproj_path: &Path) -> Result<Vec<(RawReference, usize)>, Error> {
+    let content = fs::read_to_string(csproj_path)
+        .map_err(|e| anyhow!("failed to read project file {:?}: {}", csproj_path, e))?;
+    let mut reader = Reader::from_str(&content);
+    reader.config_mut().trim_text(true);
+
+    let mut references = Vec::new();
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                let is_package = e.name().as_ref() == b"PackageReference";
+                let is_project = e.name().as_ref() == b"ProjectReference";
+                if !is_package && !is_project {
+                    buf.clear();
+                    continue;
+                }
+                let line = line_for_offset(&content, reader.buffer_position());
+
+                let mut include = None;
+                let mut version = None;
+                for attr in e.attributes().flatten() {
+                    match attr.key.as_ref() {
+                        b"Include" => {
+                            include = Some(String::from_utf8_lossy(&attr.value).trim().to_string())
+                        }
+                        b"Version" => {
+                            version = Some(String::from_utf8_lossy(&attr.value).trim().to_string())
+                        }
+                        _ => {}
+                    }
+                }
+                if let Some(include) = include {
+                    if is_project {
+                        references.push((
+                            RawReference::Project {
+                                include: PathBuf::from(include),
+                            },
+                            line,
+                        ));
+                    } else {
+                        references.push((
+                            RawReference::Package {
+                                name: include,
+                                version: version.unwrap_or_default(),
+                            },
+                            line,
+                        ));
+                    }
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(anyhow!(
+                    "XML parsing error in {:?} at position {}: {}",
+                    csproj_path,
+                    reader.buffer_position(),
+                    e
+                ));
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(references)
+}
+
+/// The dependency name to report for a `ProjectReference`: the referenced
+/// project's file stem (`../Shared/Shared.csproj` -> `Shared`), matching
+/// how NuGet package names are already bare identifiers rather than paths.
+fn project_reference_name(referenced_path: &Path) -> String {
+    referenced_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| referenced_path.to_string_lossy().into_owned())
+}
+
+/// Parse just `csproj_path`'s own direct `PackageReference`/
+/// `ProjectReference` entries, without following project references
+/// transitively.
+pub(crate) fn parse_direct_references(csproj_path: &Path) -> Result<Vec<ResolvedDependency>, Error> {
+    Ok(parse_direct_references_with_lines(csproj_path)?
+        .into_iter()
+        .map(|(dependency, _line)| dependency)
+        .collect())
+}
+
+/// Like [`parse_direct_references`], but also returns the 1-based line in
+/// `csproj_path` each dependency was declared on, so a condition match can
+/// be pointed back at the `.csproj` itself.
+pub(crate) fn parse_direct_references_with_lines(
+    csproj_path: &Path,
+) -> Result<Vec<(ResolvedDependency, usize)>, Error> {
+    let project_dir = csproj_path.parent().unwrap_or_else(|| Path::new("."));
+    let raw = parse_raw_references(csproj_path)?;
+    Ok(raw
+        .into_iter()
+        .map(|(reference, line)| {
+            let dependency = match reference {
+                RawReference::Package { name, version } => ResolvedDependency {
+                    name,
+                    version,
+                    is_direct: true,
+                },
+                RawReference::Project { include } => ResolvedDependency {
+                    name: project_reference_name(&project_dir.join(include)),
+                    version: String::new(),
+                    is_direct: true,
+                },
+            };
+            (dependency, line)
+        })
+        .collect())
+}
+
+/// Compare two dotted version strings (`"13.0.1"`) component-by-component,
+/// numerically. A missing trailing component compares as `0`, so `"1.2"`
+/// and `"1.2.0"` are equal; a non-numeric component falls back to a string
+/// comparison of that component so a malformed version degrades gracefully
+/// rather than panicking.
+pub(crate) fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    let a_parts: Vec<&str> = a.split('.').collect();
+    let b_parts: Vec<&str> = b.split('.').collect();
+    for i in 0..a_parts.len().max(b_parts.len()) {
+        let a_part = a_parts.get(i).copied().unwrap_or("0");
+        let b_part = b_parts.get(i).copied().unwrap_or("0");
+        let ordering = match (a_part.parse::<u64>(), b_part.parse::<u64>()) {
+            (Ok(a_num), Ok(b_num)) => a_num.cmp(&b_num),
+            _ => a_part.cmp(b_part),
+        };
+        if ordering != std::cmp::Ordering::Equal {
+            return ordering;
+        }
+    }
+    std::cmp::Ordering::Equal
+}
+
+/// Find every `.csproj` file under `root`, skipping `bin`/`obj` build output
+/// directories the way the rest of the provider already treats as noise.
+pub(crate) fn find_csproj_files(root: &Path) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    collect_csproj_files(root, &mut found);
+    found
+}
+
+fn collect_csproj_files(dir: &Path, found: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            let is_build_output = matches!(
+                path.file_name().and_then(|n| n.to_str()),
+                Some("bin") | Some("obj")
+            );
+            if !is_build_output {
+                collect_csproj_files(&path, found);
+            }
+        } else if path.extension().and_then(|e| e.to_str()) == Some("csproj") {
+            found.push(path);
+        }
+    }
+}
+
+/// Build the full dependency DAG rooted at `csproj_path`: its own direct
+/// `PackageReference`/`ProjectReference` entries, plus the transitive
+/// closure reached by following `ProjectReference`s to their target
+/// `.csproj` files and parsing those in turn. A visited set of canonical
+/// project paths breaks cycles in self-referential project graphs, and
+/// dependencies already seen (by name+version) are reused rather than
+/// duplicated, so a package pulled in by two different referenced projects
+/// appears once with two parents.
+pub(crate) fn build_dependency_dag(csproj_path: &Path) -> Result<Vec<DependencyDagNode>, Error> {
+    let mut nodes: Vec<DependencyDagNode> = Vec::new();
+    let mut index_of: HashMap<(String, String), usize> = HashMap::new();
+    let mut visited_projects: HashSet<PathBuf> = HashSet::new();
+
+    collect_dag_nodes(csproj_path, true, &mut nodes, &mut index_of, &mut visited_projects)?;
+    Ok(nodes)
+}
+
+fn collect_dag_nodes(
+    csproj_path: &Path,
+    is_direct: bool,
+    nodes: &mut Vec<DependencyDagNode>,
+    index_of: &mut HashMap<(String, String), usize>,
+    visited_projects: &mut HashSet<PathBuf>,
+) -> Result<Vec<usize>, Error> {
+    let canonical = csproj_path.canonicalize().unwrap_or_else(|_| csproj_path.to_path_buf());
+    if !visited_projects.insert(canonical.clone()) {
+        // Already walking this project on this path -- a project reference cycle.
+        return Ok(vec![]);
+    }
+
+    let project_dir = csproj_path.parent().unwrap_or_else(|| Path::new("."));
+    let raw = parse_raw_references(csproj_path)?;
+    let mut child_indices = Vec::new();
+
+    for (reference, _line) in raw {
+        match reference {
+            RawReference::Package { name, version } => {
+                let key = (name.clone(), version.clone());
+                let idx = *index_of.entry(key).or_insert_with(|| {
+                    nodes.push(DependencyDagNode {
+                        dependency: ResolvedDependency {
+                            name,
+                            version,
+                            is_direct,
+                        },
+                        children: vec![],
+                    });
+                    nodes.len() - 1
+                });
+                child_indices.push(idx);
+            }
+            RawReference::Project { include } => {
+                let referenced_path = project_dir.join(include);
+                let name = project_reference_name(&referenced_path);
+                let key = (name.clone(), String::new());
+                let idx = match index_of.get(&key) {
+                    Some(&idx) => idx,
+                    None => {
+                        let idx = nodes.len();
+                        nodes.push(DependencyDagNode {
+                            dependency: ResolvedDependency {
+                                name,
+                                version: String::new(),
+                                is_direct,
+                            },
+                            children: vec![],
+                        });
+                        index_of.insert(key, idx);
+                        idx
+                    }
+                };
+                if referenced_path.exists() {
+                    let grandchildren =
+                        collect_dag_nodes(&referenced_path, false, nodes, index_of, visited_projects)?;
+                    nodes[idx].children = grandchildren;
+                }
+                child_indices.push(idx);
+            }
+        }
+    }
+
+    visited_projects.remove(&canonical);
+    Ok(child_indices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn write_csproj(dir: &Path, file_name: &str, body: &str) -> PathBuf {
+        let path = dir.join(file_name);
+        fs::write(&path, body).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_parse_direct_references_finds_package_and_project_references() {
+        let tmp = std::env::temp_dir().join(format!(
+            "dep_graph_test_{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&tmp).unwrap();
+        let csproj = write_csproj(
+            &tmp,
+            "App.csproj",
+            r#"<Project Sdk="Microsoft.NET.Sdk">
+                <ItemGroup>
+                    <PackageReference Include="Newtonsoft.Json" Version="13.0.1" />
+                    <ProjectReference Include="../Shared/Shared.csproj" />
+                </ItemGroup>
+            </Project>"#,
+        );
+
+        let deps = parse_direct_references(&csproj).unwrap();
+
+        assert_eq!(deps.len(), 2);
+        assert!(deps
+            .iter()
+            .any(|d| d.name == "Newtonsoft.Json" && d.version == "13.0.1" && d.is_direct));
+        assert!(deps.iter().any(|d| d.name == "Shared" && d.is_direct));
+
+        fs::remove_dir_all(&tmp).ok();
+    }
+
+    #[test]
+    fn test_build_dependency_dag_follows_project_references_transitively() {
+        let tmp = std::env::temp_dir().join(format!(
+            "dep_graph_test_transitive_{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&tmp).unwrap();
+        fs::create_dir_all(tmp.join("Shared")).unwrap();
+
+        let _shared = write_csproj(
+            &tmp.join("Shared"),
+            "Shared.csproj",
+            r#"<Project Sdk="Microsoft.NET.Sdk">
+                <ItemGroup>
+                    <PackageReference Include="Serilog" Version="3.0.0" />
+                </ItemGroup>
+            </Project>"#,
+        );
+        let app = write_csproj(
+            &tmp,
+            "App.csproj",
+            r#"<Project Sdk="Microsoft.NET.Sdk">
+                <ItemGroup>
+                    <ProjectReference Include="Shared/Shared.csproj" />
+                </ItemGroup>
+            </Project>"#,
+        );
+
+        let nodes = build_dependency_dag(&app).unwrap();
+
+        let shared_idx = nodes
+            .iter()
+            .position(|n| n.dependency.name == "Shared")
+            .unwrap();
+        let serilog_idx = nodes
+            .iter()
+            .position(|n| n.dependency.name == "Serilog")
+            .unwrap();
+
+        assert!(nodes[shared_idx].dependency.is_direct);
+        assert!(!nodes[serilog_idx].dependency.is_direct);
+        assert_eq!(nodes[shared_idx].children, vec![serilog_idx]);
+
+        fs::remove_dir_all(&tmp).ok();
+    }
+
+    #[test]
+    fn test_build_dependency_dag_breaks_self_referential_cycles() {
+        let tmp = std::env::temp_dir().join(format!("dep_graph_test_cycle_{}", std::process::id()));
+        fs::create_dir_all(&tmp).unwrap();
+        let app = write_csproj(
+            &tmp,
+            "App.csproj",
+            r#"<Project Sdk="Microsoft.NET.Sdk">
+                <ItemGroup>
+                    <ProjectReference Include="App.csproj" />
+                </ItemGroup>
+            </Project>"#,
+        );
+
+        // Should terminate rather than looping forever.
+        let nodes = build_dependency_dag(&app).unwrap();
+        assert_eq!(nodes.len(), 1);
+        assert!(nodes[0].children.is_empty());
+
+        fs::remove_dir_all(&tmp).ok();
+    }
+
+    #[test]
+    fn test_compare_versions_numeric_components() {
+        assert_eq!(compare_versions("13.0.1", "13.0.1"), std::cmp::Ordering::Equal);
+        assert_eq!(compare_versions("13.0.2", "13.0.1"), std::cmp::Ordering::Greater);
+        assert_eq!(compare_versions("9.9.9", "10.0.0"), std::cmp::Ordering::Less);
+        assert_eq!(compare_versions("1.2", "1.2.0"), std::cmp::Ordering::Equal);
+    }
+}
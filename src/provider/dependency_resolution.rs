@@ -1,5 +1,7 @@
+use std::collections::BTreeMap;
 use std::collections::HashSet;
 use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
 use std::path::Path;
 use std::path::PathBuf;
 use std::process::Command;
@@ -27,11 +29,179 @@ use crate::c_sharp_graph::language_config::SourceNodeLanguageConfiguration;
 use crate::c_sharp_graph::loader::add_dir_to_graph;
 use crate::c_sharp_graph::loader::AsyncInitializeGraph;
 use crate::c_sharp_graph::loader::SourceType;
+use crate::provider::package_graph::PackageGraph;
 use crate::provider::project::Tools;
+use crate::provider::reference_assembly_acquisition::{
+    ReferenceAssemblyAcquirer, ReferenceAssemblyAcquisitionConfig,
+};
+use crate::provider::target_framework::TargetFramework;
+use crate::provider::workspace::{DependencyManifest, ProjectWorkspace};
 use crate::provider::AnalysisMode;
 use crate::provider::Project;
 
-const REFERNCE_ASSEMBLIES_NAME: &str = "Microsoft.NETFramework.ReferenceAssemblies";
+pub(crate) const REFERNCE_ASSEMBLIES_NAME: &str = "Microsoft.NETFramework.ReferenceAssemblies";
+
+/// A dedup key for `path`'s content: its file stem (so two
+/// framework-specific copies of the same assembly that happen to collide
+/// byte-for-byte but are named differently are never merged) combined
+/// with a hash of its bytes. Reference assemblies and decompiled package
+/// outputs are heavily duplicated across target frameworks, so hashing
+/// content before indexing -- borrowing xwin's splat dedup strategy --
+/// lets the loader skip rebuilding a `StackGraph`/partial-path set it has
+/// already built for an identical file under a different path.
+fn content_hash_key(path: &Path) -> Result<u64, Error> {
+    let bytes = std::fs::read(path)
+        .map_err(|e| anyhow!("failed to read {:?} for dedup hashing: {}", path, e))?;
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    path.file_stem().hash(&mut hasher);
+    bytes.hash(&mut hasher);
+    Ok(hasher.finish())
+}
+
+/// Like [`content_hash_key`], but for a decompiled package's output
+/// directory rather than a single file: hashes the name and size of each
+/// entry directly inside it rather than every byte, since a decompiled
+/// tree can be large and this only needs to catch byte-for-byte duplicate
+/// decompiles of the same assembly under different target frameworks.
+fn content_hash_key_dir(dir: &Path) -> Result<u64, Error> {
+    let mut entries: Vec<(String, u64)> = std::fs::read_dir(dir)
+        .map_err(|e| anyhow!("failed to read {:?} for dedup hashing: {}", dir, e))?
+        .flatten()
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            Some((
+                entry.file_name().to_string_lossy().into_owned(),
+                metadata.len(),
+            ))
+        })
+        .collect();
+    entries.sort();
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    entries.hash(&mut hasher);
+    Ok(hasher.finish())
+}
+
+/// A comparison operator in a Paket `restriction:` clause, e.g. the `>=`
+/// in `>= net45`. A bare TFM with no operator is treated as exact.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RestrictionOp {
+    Exact,
+    AtLeast,
+    AtMost,
+    GreaterThan,
+    LessThan,
+}
+
+/// Splits a single restriction constraint (e.g. `">= net45"`,
+/// `"netstandard2.0"`) into its operator and TFM text. Returns `None` for
+/// an empty constraint (e.g. a trailing `,` in the clause).
+fn parse_restriction_constraint(constraint: &str) -> Option<(RestrictionOp, &str)> {
+    let constraint = constraint.trim();
+    for (prefix, op) in [
+        (">=", RestrictionOp::AtLeast),
+        ("<=", RestrictionOp::AtMost),
+        (">", RestrictionOp::GreaterThan),
+        ("<", RestrictionOp::LessThan),
+    ] {
+        if let Some(rest) = constraint.strip_prefix(prefix) {
+            return Some((op, rest.trim()));
+        }
+    }
+    if constraint.is_empty() {
+        None
+    } else {
+        Some((RestrictionOp::Exact, constraint))
+    }
+}
+
+/// Expands a single restriction constraint into the concrete TFMs it
+/// allows: filters [`TargetFramework::all_known`] down to the same family
+/// as the constraint's TFM (version comparisons across families aren't
+/// meaningful -- see [`TargetFramework::is_at_least`]), then applies the
+/// operator. E.g. `>= net45` expands to every known .NET Framework TFM
+/// from net45 up; an unparseable TFM or operator expands to nothing.
+fn expand_restriction_constraint(constraint: &str) -> Vec<TargetFramework> {
+    let Some((op, tfm_text)) = parse_restriction_constraint(constraint) else {
+        return Vec::new();
+    };
+    let Ok(base) = TargetFramework::try_from(tfm_text) else {
+        return Vec::new();
+    };
+    let family = base.family();
+    TargetFramework::all_known()
+        .into_iter()
+        .filter(|tfm| tfm.family() == family)
+        .filter(|tfm| match op {
+            RestrictionOp::Exact => *tfm == base,
+            RestrictionOp::AtLeast => *tfm >= base,
+            RestrictionOp::AtMost => *tfm <= base,
+            RestrictionOp::GreaterThan => *tfm > base,
+            RestrictionOp::LessThan => *tfm < base,
+        })
+        .collect()
+}
+
+/// Parses a Paket `restriction:` clause (the text following `restriction:`
+/// on a dependency line) into the set of concrete target frameworks it
+/// allows. `||` separates alternatives (satisfying any one alternative
+/// satisfies the restriction); within an alternative, `,` separates
+/// constraints that must all hold (so the alternative's allowed set is
+/// their intersection). Each constraint expands via
+/// [`expand_restriction_constraint`].
+fn parse_restriction(restriction: &str) -> HashSet<TargetFramework> {
+    let mut allowed: HashSet<TargetFramework> = HashSet::new();
+    for alternative in restriction.split("||") {
+        let mut matching: Option<HashSet<TargetFramework>> = None;
+        for constraint in alternative.split(',') {
+            let constraint = constraint.trim();
+            if constraint.is_empty() {
+                continue;
+            }
+            let expanded: HashSet<TargetFramework> = expand_restriction_constraint(constraint)
+                .into_iter()
+                .collect();
+            matching = Some(match matching {
+                Some(existing) => existing.intersection(&expanded).cloned().collect(),
+                None => expanded,
+            });
+        }
+        if let Some(matching) = matching {
+            allowed.extend(matching);
+        }
+    }
+    allowed
+}
+
+/// Resolves the single target framework to use for reference-assembly
+/// lookup from every dependency's restriction-derived TFM set: the
+/// intersection of every dependency's allowed set, picking the highest
+/// TFM (by [`TargetFramework`]'s semantic ordering) out of what's left, so
+/// the newest framework every dependency agrees on wins instead of
+/// whichever TFM string happened to sort first lexicographically. Returns
+/// an error naming the dependencies involved if the intersection is empty.
+fn resolve_target_framework(
+    restrictions_by_dependency: &[(String, HashSet<TargetFramework>)],
+) -> Result<TargetFramework, Error> {
+    let mut intersection: Option<HashSet<TargetFramework>> = None;
+    for (_, allowed) in restrictions_by_dependency {
+        intersection = Some(match intersection {
+            Some(existing) => existing.intersection(allowed).cloned().collect(),
+            None => allowed.clone(),
+        });
+    }
+
+    intersection.unwrap_or_default().into_iter().max().ok_or_else(|| {
+        let names: Vec<&str> = restrictions_by_dependency
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        anyhow!(
+            "no target framework satisfies every dependency's restriction; conflicting dependencies: {}",
+            names.join(", ")
+        )
+    })
+}
+
 pub struct Dependencies {
     pub location: PathBuf,
     #[allow(dead_code)]
@@ -254,32 +424,36 @@ impl Dependencies {
 impl Project {
     #[tracing::instrument]
     pub async fn resolve(&self) -> Result<(), Error> {
-        // determine if the paket.dependencies already exists, if it does then we don't need to
-        // convert.
-        let paket_deps_file = self.location.clone().join("paket.dependencies");
-
-        if !paket_deps_file.exists() {
-            // Fsourcoirst need to run packet.
-            // Need to convert and download all DLL's
-            //TODO: Add paket location as a provider specific config.
-            let paket_output = Command::new(&self.tools.paket_cmd)
-                .args(["convert-from-nuget", "-f"])
-                .current_dir(&self.location)
-                .output()?;
-            if !paket_output.status.success() {
-                //TODO: Consider a specific error type
-                debug!(
-                    "paket command not successful: {} --- {}",
-                    String::from_utf8_lossy(&paket_output.stdout),
-                    String::from_utf8_lossy(&paket_output.stderr)
-                );
-                return Err(Error::msg("paket command did not succeed"));
+        // Figure out which manifest format this project already has (a
+        // Paket lockfile, a NuGet restore's project.assets.json, etc.)
+        // before falling back to bootstrapping Paket over it.
+        let workspace = match ProjectWorkspace::discover(&self.location) {
+            Some(workspace) => workspace,
+            None => {
+                // Fsourcoirst need to run packet.
+                // Need to convert and download all DLL's
+                //TODO: Add paket location as a provider specific config.
+                let paket_output = Command::new(&self.tools.paket_cmd)
+                    .args(["convert-from-nuget", "-f"])
+                    .current_dir(&self.location)
+                    .output()?;
+                if !paket_output.status.success() {
+                    //TODO: Consider a specific error type
+                    debug!(
+                        "paket command not successful: {} --- {}",
+                        String::from_utf8_lossy(&paket_output.stdout),
+                        String::from_utf8_lossy(&paket_output.stderr)
+                    );
+                    return Err(Error::msg("paket command did not succeed"));
+                }
+                ProjectWorkspace::Paket {
+                    manifest: self.location.clone().join("paket.dependencies"),
+                }
             }
-        }
+        };
 
-        let (reference_assembly_path, highest_restriction, deps) = self
-            .read_packet_dependency_file(paket_deps_file.as_path())
-            .await?;
+        let (reference_assembly_path, highest_restriction, deps) =
+            workspace.dependencies(self).await?;
         if deps.is_empty() {
             let mut d = self.dependencies.lock().await;
             *d = Some(deps);
@@ -401,6 +575,9 @@ impl Project {
         let shared_deps = Arc::clone(&self.dependencies);
         let mut x = shared_deps.lock().await;
         let mut set = JoinSet::new();
+        // Tracks content already queued for indexing so the same XML file
+        // installed under multiple target frameworks is only indexed once.
+        let mut seen_content: BTreeMap<u64, PathBuf> = BTreeMap::new();
 
         if let Some(ref mut vec) = *x {
             // For each dependnecy in the list we will try and load the decompiled files
@@ -414,6 +591,23 @@ impl Project {
                         error!("unable to find xml file: {:?}", file);
                         continue;
                     }
+                    match content_hash_key(&file) {
+                        std::result::Result::Ok(hash) => match seen_content.get(&hash) {
+                            Some(original) => {
+                                debug!(
+                                    "skipping xml file {:?}, content already indexed from {:?}",
+                                    file, original
+                                );
+                                continue;
+                            }
+                            None => {
+                                seen_content.insert(hash, file.clone());
+                            }
+                        },
+                        Err(e) => {
+                            error!("unable to hash {:?} for dedup, indexing anyway: {}", file, e);
+                        }
+                    }
                     // Use new type of loader, to handle this.
                     let db_path = self.db_path.clone();
                     let dep_name = d.name.clone();
@@ -439,7 +633,7 @@ impl Project {
                         let file_name = file_name.unwrap().to_string_lossy();
                         let file_name = file_name.to_string();
                         source_lc.language_config.special_files =
-                            FileAnalyzers::new().with(file_name, DepXMLFileAnalyzer {});
+                            FileAnalyzers::new().with(file_name, DepXMLFileAnalyzer::new());
                         let mut graph = add_dir_to_graph(
                             &file,
                             &source_lc.dependnecy_type_node_info,
@@ -510,6 +704,10 @@ impl Project {
         let shared_deps = Arc::clone(&self.dependencies);
         let mut x = shared_deps.lock().await;
         let mut set: JoinSet<Result<(AsyncInitializeGraph, String), Error>> = JoinSet::new();
+        // Tracks decompiled output directories already queued for indexing,
+        // so a package decompiled identically under more than one target
+        // framework is only indexed once.
+        let mut seen_content: BTreeMap<u64, PathBuf> = BTreeMap::new();
         if let Some(ref mut vec) = *x {
             // For each dependnecy in the list we will try and load the decompiled files
             // Into the stack graph database.
@@ -520,6 +718,26 @@ impl Project {
                 let decompiled_locations = decompiled_locations.lock().unwrap();
                 let decompiled_files = &(*decompiled_locations);
                 for decompiled_file in decompiled_files {
+                    match content_hash_key_dir(decompiled_file) {
+                        std::result::Result::Ok(hash) => match seen_content.get(&hash) {
+                            Some(original) => {
+                                debug!(
+                                    "skipping decompiled output {:?}, content already indexed from {:?}",
+                                    decompiled_file, original
+                                );
+                                continue;
+                            }
+                            None => {
+                                seen_content.insert(hash, decompiled_file.clone());
+                            }
+                        },
+                        Err(e) => {
+                            error!(
+                                "unable to hash {:?} for dedup, indexing anyway: {}",
+                                decompiled_file, e
+                            );
+                        }
+                    }
                     let file = decompiled_file.clone();
                     let lc = self.source_language_config.clone();
                     let db_path = self.db_path.clone();
@@ -589,7 +807,7 @@ impl Project {
         Ok(set)
     }
 
-    async fn read_packet_dependency_file(
+    pub(crate) async fn read_packet_dependency_file(
         &self,
         paket_deps_file: &Path,
     ) -> Result<(PathBuf, String, Vec<Dependencies>), Error> {
@@ -600,8 +818,8 @@ impl Project {
         }
         let reader = BufReader::new(file.ok().unwrap());
         let mut lines = reader.lines();
-        let mut smallest_framework = "zzzzzzzzzzzzzzz".to_string();
         let mut deps: Vec<Dependencies> = vec![];
+        let mut restrictions_by_dependency: Vec<(String, HashSet<TargetFramework>)> = vec![];
         while let Some(line) = lines.next_line().await? {
             if !line.contains("restriction") {
                 continue;
@@ -610,6 +828,7 @@ impl Project {
             if parts.len() != 2 {
                 continue;
             }
+            let mut dep_name = String::new();
             if let Some(dep_part) = parts.first() {
                 let white_space_split: Vec<&str> = dep_part.split_whitespace().collect();
                 if white_space_split.len() < 4 {
@@ -630,6 +849,7 @@ impl Project {
                         continue;
                     }
                 };
+                dep_name = name.to_string();
                 let dep = Dependencies {
                     location: dep_path,
                     name: name.to_string(),
@@ -641,18 +861,19 @@ impl Project {
                 deps.push(dep);
             }
 
-            if let Some(ref_name) = parts.get(1) {
-                let n = ref_name.to_string();
-                if let Some(framework) = n.split_whitespace().last() {
-                    let framework_string = framework.to_string();
-                    if framework_string < smallest_framework {
-                        smallest_framework = framework_string;
-                    }
-                }
+            if let Some(restriction_text) = parts.get(1) {
+                restrictions_by_dependency.push((dep_name, parse_restriction(restriction_text)));
             }
         }
         drop(lines);
 
+        if deps.is_empty() {
+            return Ok((PathBuf::new(), String::new(), deps));
+        }
+
+        let resolved_framework = resolve_target_framework(&restrictions_by_dependency)?;
+        let smallest_framework = resolved_framework.to_string();
+
         let deps: Vec<Dependencies> = deps
             .into_iter()
             .map(|mut d| {
@@ -661,9 +882,21 @@ impl Project {
             })
             .collect();
 
-        if deps.is_empty() {
-            return Ok((PathBuf::new(), String::new(), deps));
+        // Build an explicit graph over the dependencies so that a package
+        // pulled in more than once is only indexed once, and so a circular
+        // reference is reported as a diagnostic here rather than driving
+        // the per-dependency stitch loop into an infinite loop later.
+        // `paket.dependencies` itself has no transitive edges, so pull
+        // those from the sibling `paket.lock` (Paket's resolved,
+        // indentation-nested lock file) when one exists alongside it.
+        let mut package_graph = PackageGraph::from_dependencies(deps);
+        if let Some(lock_path) = paket_deps_file.parent().map(|dir| dir.join("paket.lock")) {
+            if lock_path.is_file() {
+                self.populate_paket_lock_edges(&lock_path, &mut package_graph)
+                    .await?;
+            }
         }
+        let deps = package_graph.into_topological_dependencies()?;
 
         // Now we we have the framework, we need to get the reference_assmblies
         let base_name = format!("{}.{}", REFERNCE_ASSEMBLIES_NAME, smallest_framework);
@@ -682,29 +915,136 @@ impl Project {
                 ));
             }
         };
-        // Read the paket_install to find the directory of the DLL's
-        let file = File::open(paket_install.join("paket-installmodel.cache")).await;
-        if let Err(e) = file {
-            error!("unable to find error: {:?}", e);
-            return Err(anyhow!(e));
+
+        // Paket/NuGet should have restored the reference assemblies above,
+        // but in an air-gapped or partially-restored project that `paket
+        // add` can silently fail. Fall back to fetching the nupkg directly
+        // from a NuGet feed rather than failing the whole resolution.
+        let reference_assembly_path =
+            match Self::read_reference_assembly_from_paket_cache(&paket_install).await {
+                std::result::Result::Ok(path) => path,
+                Err(e) => {
+                    debug!(
+                        "paket-installed reference assemblies unavailable ({}), falling back to direct NuGet acquisition",
+                        e
+                    );
+                    ReferenceAssemblyAcquirer::acquire(
+                        &resolved_framework,
+                        &ReferenceAssemblyAcquisitionConfig::default(),
+                    )?
+                }
+            };
+
+        Ok((reference_assembly_path, smallest_framework, deps))
+    }
+
+    /// Parses `paket.lock`'s indentation-nested package list into edges on
+    /// `graph`, so [`PackageGraph::detect_cycles`]/`into_topological_dependencies`
+    /// see the real transitive-dependency structure `paket.dependencies`
+    /// alone doesn't carry. Paket nests each package's own dependencies
+    /// directly under it at a deeper indent than its siblings, e.g.:
+    ///
+    /// ```text
+    /// NUGET
+    ///   remote: https://api.nuget.org/v3/index.json
+    ///     Serilog (2.9.0)
+    ///       Newtonsoft.Json (>= 9.0.1)
+    ///     Newtonsoft.Json (12.0.3)
+    /// ```
+    ///
+    /// so a line indented deeper than the most recently seen top-level
+    /// package is recorded as that package's dependency, and a line back at
+    /// (or above) the top-level indent starts a new top-level package.
+    async fn populate_paket_lock_edges(
+        &self,
+        lock_path: &Path,
+        graph: &mut PackageGraph,
+    ) -> Result<(), Error> {
+        let file = File::open(lock_path)
+            .await
+            .map_err(|e| anyhow!("unable to open {:?}: {}", lock_path, e))?;
+        let reader = BufReader::new(file);
+        let mut lines = reader.lines();
+
+        let mut top_level_indent: Option<usize> = None;
+        let mut current_package: Option<String> = None;
+
+        while let Some(line) = lines.next_line().await? {
+            if !line.starts_with(' ') {
+                // A column-0 line (`NUGET`, `GROUP Build`, ...) starts a
+                // new dependency group -- reset so a package in one group
+                // isn't mistaken for a dependency of one in another.
+                top_level_indent = None;
+                current_package = None;
+                continue;
+            }
+
+            let trimmed = line.trim_start();
+            if trimmed.is_empty() || trimmed.starts_with("remote:") || trimmed.starts_with("specs:") {
+                continue;
+            }
+            let Some(name) = Self::paket_lock_package_name(trimmed) else {
+                continue;
+            };
+
+            let indent = line.len() - trimmed.len();
+            match top_level_indent {
+                None => {
+                    top_level_indent = Some(indent);
+                    current_package = Some(name);
+                }
+                Some(top_indent) if indent <= top_indent => {
+                    current_package = Some(name);
+                }
+                Some(_) => {
+                    if let Some(parent) = &current_package {
+                        let parent_path = self.location.join("packages").join(parent);
+                        let dep_path = self.location.join("packages").join(&name);
+                        graph.add_dependency_edge(&parent_path, &dep_path);
+                    }
+                }
+            }
         }
-        let reader = BufReader::new(file.ok().unwrap());
+
+        Ok(())
+    }
+
+    /// The package name from a `paket.lock` package/dependency line, e.g.
+    /// `Serilog (2.9.0)` or `Newtonsoft.Json (>= 9.0.1)` both yield
+    /// `Some("Serilog")`/`Some("Newtonsoft.Json")`.
+    fn paket_lock_package_name(line: &str) -> Option<String> {
+        let name = line.split(" (").next()?.trim();
+        if name.is_empty() {
+            None
+        } else {
+            Some(name.to_string())
+        }
+    }
+
+    /// Scrapes `paket_install`'s `paket-installmodel.cache` for the
+    /// `build/.NETFramework/` DLL directory Paket restored. Errors if the
+    /// cache file is missing or doesn't list a `.NETFramework` build
+    /// output, which happens when `paket add` couldn't actually restore
+    /// the reference assemblies (e.g. no network access).
+    async fn read_reference_assembly_from_paket_cache(
+        paket_install: &Path,
+    ) -> Result<PathBuf, Error> {
+        let file = File::open(paket_install.join("paket-installmodel.cache")).await?;
+        let reader = BufReader::new(file);
         let mut lines = reader.lines();
         while let Some(line) = lines.next_line().await? {
             if line.contains("build/.NETFramework/") && line.contains("D: /") {
-                let path_str = match line.strip_prefix("D: /") {
-                    Some(x) => x,
-                    None => {
-                        return Err(anyhow!("unable to get reference assembly"));
-                    }
-                };
+                let path_str = line
+                    .strip_prefix("D: /")
+                    .ok_or_else(|| anyhow!("unable to get reference assembly"))?;
                 debug!("path_str: {}", path_str);
-                let path = paket_install.join(path_str);
-                return Ok((paket_install.join(path), smallest_framework, deps));
+                return Ok(paket_install.join(path_str));
             }
         }
-
-        Err(anyhow!("unable to get reference assembly"))
+        Err(anyhow!(
+            "paket-installmodel.cache at {:?} has no .NETFramework build output",
+            paket_install
+        ))
     }
 
     /// Load SDK XML files into the database
@@ -737,6 +1077,37 @@ impl Project {
             return Ok(0);
         }
 
+        // Dedup by content: the same BCL XML file is frequently installed
+        // once per target framework under a different path, so only the
+        // first copy of each distinct content is actually indexed.
+        let mut seen_content: BTreeMap<u64, PathBuf> = BTreeMap::new();
+        let mut valid_files_dedup = Vec::with_capacity(valid_files.len());
+        for file in valid_files {
+            match content_hash_key(&file) {
+                std::result::Result::Ok(hash) => match seen_content.get(&hash) {
+                    Some(original) => {
+                        debug!(
+                            "skipping SDK XML file {:?}, content already indexed from {:?}",
+                            file, original
+                        );
+                    }
+                    None => {
+                        seen_content.insert(hash, file.clone());
+                        valid_files_dedup.push(file);
+                    }
+                },
+                Err(e) => {
+                    error!("unable to hash {:?} for dedup, indexing anyway: {}", file, e);
+                    valid_files_dedup.push(file);
+                }
+            }
+        }
+        let valid_files = valid_files_dedup;
+
+        if valid_files.is_empty() {
+            return Ok(0);
+        }
+
         // Create a single graph for all XML files
         let mut graph = StackGraph::new();
 
@@ -756,7 +1127,7 @@ impl Project {
                 .ok_or_else(|| anyhow!("unable to get file name for {:?}", file))?
                 .to_string_lossy()
                 .to_string();
-            file_analyzers = file_analyzers.with(file_name, DepXMLFileAnalyzer {});
+            file_analyzers = file_analyzers.with(file_name, DepXMLFileAnalyzer::new());
         }
         source_lc.language_config.special_files = file_analyzers;
 
@@ -1,8 +1,12 @@
 mod code_snip;
 mod csharp;
+mod dependency_graph;
 mod dependency_resolution;
+mod package_graph;
 mod project;
+mod reference_assembly_acquisition;
 pub(crate) mod target_framework;
+mod workspace;
 
 pub use csharp::CSharpProvider;
 pub use project::AnalysisMode;
@@ -0,0 +1,235 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{anyhow, Error};
+use petgraph::algo::{tarjan_scc, toposort};
+use petgraph::graph::NodeIndex;
+use petgraph::stable_graph::StableDiGraph;
+
+use crate::provider::dependency_resolution::Dependencies;
+
+/// An explicit graph of the packages a project depends on, keyed by each
+/// package's install path, with an edge from a package to every other
+/// package it transitively requires. Built from the flat
+/// [`Dependencies`] list [`read_packet_dependency_file`] parses out of
+/// `paket.dependencies`, so that shared transitive packages are indexed
+/// once instead of reprocessed, and dependents can be stitched only after
+/// the packages they reference.
+///
+/// `paket.dependencies` itself only lists each package once alongside its
+/// restriction clause -- it has no notion of which package requires which
+/// -- so a graph built purely from it has nodes but no edges. Edges are
+/// added separately via [`PackageGraph::add_dependency_edge`], which
+/// [`read_packet_dependency_file`] calls once per nesting relationship it
+/// parses out of the sibling `paket.lock` (when one exists).
+///
+/// [`read_packet_dependency_file`]: crate::provider::dependency_resolution::Project
+pub(crate) struct PackageGraph {
+    graph: StableDiGraph<Dependencies, ()>,
+    index_by_path: HashMap<String, NodeIndex>,
+}
+
+impl PackageGraph {
+    /// Builds a graph with one node per dependency, keyed by
+    /// [`Dependencies::location`]. A dependency whose location was already
+    /// seen (the same package pulled in more than once, e.g. across
+    /// `paket.dependencies` groups) is skipped rather than duplicated.
+    pub(crate) fn from_dependencies(deps: Vec<Dependencies>) -> Self {
+        let mut graph = StableDiGraph::new();
+        let mut index_by_path = HashMap::new();
+        for dep in deps {
+            let key = path_key(&dep.location);
+            if index_by_path.contains_key(&key) {
+                continue;
+            }
+            let index = graph.add_node(dep);
+            index_by_path.insert(key, index);
+        }
+        PackageGraph {
+            graph,
+            index_by_path,
+        }
+    }
+
+    /// Whether a package at `location` is present in the graph.
+    pub(crate) fn contains_package(&self, location: &Path) -> bool {
+        self.index_by_path.contains_key(&path_key(location))
+    }
+
+    /// Records that the package at `from` directly requires the package at
+    /// `to`. Does nothing if either path isn't in the graph.
+    pub(crate) fn add_dependency_edge(&mut self, from: &Path, to: &Path) {
+        let (Some(&from_index), Some(&to_index)) = (
+            self.index_by_path.get(&path_key(from)),
+            self.index_by_path.get(&path_key(to)),
+        ) else {
+            return;
+        };
+        self.graph.update_edge(from_index, to_index, ());
+    }
+
+    /// The packages `location` directly requires, empty if `location` isn't
+    /// in the graph or has no recorded dependencies.
+    pub(crate) fn dependencies_of(&self, location: &Path) -> Vec<&Dependencies> {
+        let Some(&index) = self.index_by_path.get(&path_key(location)) else {
+            return Vec::new();
+        };
+        self.graph
+            .neighbors(index)
+            .map(|neighbor| &self.graph[neighbor])
+            .collect()
+    }
+
+    /// Finds circular dependency chains using Tarjan's strongly-connected-
+    /// components algorithm: every SCC with more than one package, or a
+    /// single package that depends on itself, is a cycle. Returns the
+    /// package names involved in each cycle found.
+    pub(crate) fn detect_cycles(&self) -> Vec<Vec<String>> {
+        tarjan_scc(&self.graph)
+            .into_iter()
+            .filter(|component| {
+                component.len() > 1
+                    || component
+                        .first()
+                        .is_some_and(|&index| self.graph.contains_edge(index, index))
+            })
+            .map(|component| {
+                component
+                    .into_iter()
+                    .map(|index| self.graph[index].name.clone())
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Consumes the graph, returning its packages ordered so that every
+    /// package appears after everything it depends on -- the order the
+    /// per-dependency graph-build/stitch loop should process them in so a
+    /// dependent is never stitched before the assembly it references.
+    /// Errors out naming the packages involved instead of looping forever
+    /// if [`PackageGraph::detect_cycles`] finds a circular reference.
+    pub(crate) fn into_topological_dependencies(mut self) -> Result<Vec<Dependencies>, Error> {
+        let cycles = self.detect_cycles();
+        if !cycles.is_empty() {
+            let described: Vec<String> = cycles
+                .iter()
+                .map(|cycle| format!("[{}]", cycle.join(" -> ")))
+                .collect();
+            return Err(anyhow!(
+                "circular package reference(s) detected, cannot determine load order: {}",
+                described.join(", ")
+            ));
+        }
+
+        // No cycles, so toposort can't fail -- Tarjan already proved the
+        // graph is a DAG. Edges point from a package to what it requires,
+        // so toposort yields dependents first; reverse it.
+        let order = toposort(&self.graph, None)
+            .map_err(|cycle| anyhow!("unexpected cycle at node {:?}", cycle.node_id()))?;
+        Ok(order
+            .into_iter()
+            .rev()
+            .map(|index| {
+                self.graph
+                    .remove_node(index)
+                    .expect("toposort only yields indices present in the graph")
+            })
+            .collect())
+    }
+}
+
+/// Normalizes a package location to the string key [`PackageGraph`] indexes
+/// nodes by.
+fn path_key(location: &Path) -> String {
+    location.to_string_lossy().into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::path::PathBuf;
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    fn dep(name: &str) -> Dependencies {
+        Dependencies {
+            location: PathBuf::from(format!("/packages/{name}")),
+            name: name.to_string(),
+            version: "1.0.0".to_string(),
+            highest_restriction: "net8.0".to_string(),
+            decompiled_size: Mutex::new(None),
+            decompiled_location: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    #[test]
+    fn dependencies_of_reflects_added_edges() {
+        let mut graph = PackageGraph::from_dependencies(vec![dep("A"), dep("B"), dep("C")]);
+        graph.add_dependency_edge(Path::new("/packages/A"), Path::new("/packages/B"));
+        graph.add_dependency_edge(Path::new("/packages/A"), Path::new("/packages/C"));
+
+        let mut names: Vec<&str> = graph
+            .dependencies_of(Path::new("/packages/A"))
+            .into_iter()
+            .map(|d| d.name.as_str())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["B", "C"]);
+        assert!(graph.dependencies_of(Path::new("/packages/B")).is_empty());
+    }
+
+    #[test]
+    fn add_dependency_edge_is_a_no_op_for_unknown_packages() {
+        let mut graph = PackageGraph::from_dependencies(vec![dep("A")]);
+        graph.add_dependency_edge(Path::new("/packages/A"), Path::new("/packages/missing"));
+        assert!(graph.dependencies_of(Path::new("/packages/A")).is_empty());
+    }
+
+    #[test]
+    fn detect_cycles_finds_nothing_in_an_acyclic_graph() {
+        let mut graph = PackageGraph::from_dependencies(vec![dep("A"), dep("B")]);
+        graph.add_dependency_edge(Path::new("/packages/A"), Path::new("/packages/B"));
+        assert!(graph.detect_cycles().is_empty());
+    }
+
+    #[test]
+    fn detect_cycles_finds_a_direct_cycle() {
+        let mut graph = PackageGraph::from_dependencies(vec![dep("A"), dep("B")]);
+        graph.add_dependency_edge(Path::new("/packages/A"), Path::new("/packages/B"));
+        graph.add_dependency_edge(Path::new("/packages/B"), Path::new("/packages/A"));
+
+        let cycles = graph.detect_cycles();
+        assert_eq!(cycles.len(), 1);
+        let mut names = cycles[0].clone();
+        names.sort();
+        assert_eq!(names, vec!["A".to_string(), "B".to_string()]);
+    }
+
+    #[test]
+    fn detect_cycles_finds_a_self_loop() {
+        let mut graph = PackageGraph::from_dependencies(vec![dep("A")]);
+        graph.add_dependency_edge(Path::new("/packages/A"), Path::new("/packages/A"));
+        assert_eq!(graph.detect_cycles(), vec![vec!["A".to_string()]]);
+    }
+
+    #[test]
+    fn into_topological_dependencies_orders_dependents_after_their_dependencies() {
+        let mut graph = PackageGraph::from_dependencies(vec![dep("A"), dep("B"), dep("C")]);
+        graph.add_dependency_edge(Path::new("/packages/A"), Path::new("/packages/B"));
+        graph.add_dependency_edge(Path::new("/packages/B"), Path::new("/packages/C"));
+
+        let ordered = graph.into_topological_dependencies().unwrap();
+        let position = |name: &str| ordered.iter().position(|d| d.name == name).unwrap();
+        assert!(position("B") < position("A"));
+        assert!(position("C") < position("B"));
+    }
+
+    #[test]
+    fn into_topological_dependencies_errors_on_a_cycle() {
+        let mut graph = PackageGraph::from_dependencies(vec![dep("A"), dep("B")]);
+        graph.add_dependency_edge(Path::new("/packages/A"), Path::new("/packages/B"));
+        graph.add_dependency_edge(Path::new("/packages/B"), Path::new("/packages/A"));
+        assert!(graph.into_topological_dependencies().is_err());
+    }
+}
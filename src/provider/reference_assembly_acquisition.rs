@@ -0,0 +1,168 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Error};
+use tracing::{debug, info};
+
+use crate::provider::dependency_graph::compare_versions;
+use crate::provider::dependency_resolution::REFERNCE_ASSEMBLIES_NAME;
+use crate::provider::target_framework::TargetFramework;
+
+/// Configuration for the opt-in reference-assembly acquisition fallback.
+/// Mirrors `AcquisitionConfig` for SDK packs, but fetches the
+/// `Microsoft.NETFramework.ReferenceAssemblies.*` nupkg itself from a
+/// configurable NuGet feed rather than the dotnet CLI's blob storage, for
+/// when Paket/NuGet couldn't restore it locally (air-gapped or
+/// partially-restored projects).
+#[derive(Debug, Clone)]
+pub(crate) struct ReferenceAssemblyAcquisitionConfig {
+    /// Root directory nupkgs are downloaded and unpacked into.
+    pub(crate) cache_dir: PathBuf,
+    /// NuGet v3 flat-container base URL, e.g.
+    /// `https://api.nuget.org/v3-flatcontainer`.
+    pub(crate) nuget_feed: String,
+}
+
+impl Default for ReferenceAssemblyAcquisitionConfig {
+    fn default() -> Self {
+        Self {
+            cache_dir: Self::default_cache_dir(),
+            nuget_feed: std::env::var("NUGET_REFERENCE_ASSEMBLIES_FEED")
+                .unwrap_or_else(|_| "https://api.nuget.org/v3-flatcontainer".to_string()),
+        }
+    }
+}
+
+impl ReferenceAssemblyAcquisitionConfig {
+    /// `~/.konveyor/reference-assemblies`, falling back to `.` if no home
+    /// directory can be determined.
+    fn default_cache_dir() -> PathBuf {
+        let home = std::env::var("HOME")
+            .or_else(|_| std::env::var("USERPROFILE"))
+            .unwrap_or_else(|_| ".".to_string());
+        PathBuf::from(home)
+            .join(".konveyor")
+            .join("reference-assemblies")
+    }
+}
+
+pub(crate) struct ReferenceAssemblyAcquirer;
+
+impl ReferenceAssemblyAcquirer {
+    /// Fetches the `Microsoft.NETFramework.ReferenceAssemblies` nupkg for
+    /// `target_framework` from `config.nuget_feed` and unpacks it into
+    /// `config.cache_dir`, returning the `build/.NETFramework/<tfm>` DLL
+    /// directory directly. Keyed by TFM under `cache_dir`, so a repeat run
+    /// that already has the package cached never touches the network.
+    pub(crate) fn acquire(
+        target_framework: &TargetFramework,
+        config: &ReferenceAssemblyAcquisitionConfig,
+    ) -> Result<PathBuf, Error> {
+        let package_id = format!("{}.{}", REFERNCE_ASSEMBLIES_NAME, target_framework.as_str());
+        let package_id_lower = package_id.to_lowercase();
+        let package_dir = config.cache_dir.join(&package_id_lower);
+        let expected_dir = package_dir
+            .join("build")
+            .join(".NETFramework")
+            .join(target_framework.as_str());
+
+        if expected_dir.is_dir() {
+            debug!(
+                "reference assemblies for {} already cached at {:?}",
+                target_framework.as_str(),
+                expected_dir
+            );
+            return Ok(expected_dir);
+        }
+
+        std::fs::create_dir_all(&package_dir).map_err(|e| {
+            anyhow!(
+                "failed to create reference assembly cache directory {:?}: {}",
+                package_dir,
+                e
+            )
+        })?;
+
+        let version = Self::latest_version(&config.nuget_feed, &package_id_lower)?;
+        let archive_path = Self::download(&config.nuget_feed, &package_id_lower, &version, &package_dir)?;
+        Self::extract(&archive_path, &package_dir)?;
+
+        if !expected_dir.is_dir() {
+            return Err(anyhow!(
+                "downloaded {} {} but expected layout {:?} is still missing",
+                package_id,
+                version,
+                expected_dir
+            ));
+        }
+
+        info!(
+            "Acquired reference assemblies for {} into {:?}",
+            target_framework.as_str(),
+            expected_dir
+        );
+        Ok(expected_dir)
+    }
+
+    /// Looks up the newest published version of `package_id_lower` via the
+    /// NuGet v3 flat-container version index.
+    fn latest_version(nuget_feed: &str, package_id_lower: &str) -> Result<String, Error> {
+        let index_url = format!("{}/{}/index.json", nuget_feed, package_id_lower);
+        let response = reqwest::blocking::get(&index_url)
+            .map_err(|e| anyhow!("failed to query {}: {}", index_url, e))?
+            .error_for_status()
+            .map_err(|e| anyhow!("failed to query {}: {}", index_url, e))?;
+        let body: serde_json::Value = response
+            .json()
+            .map_err(|e| anyhow!("failed to parse {}: {}", index_url, e))?;
+        let mut versions: Vec<String> = body
+            .get("versions")
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| anyhow!("{} has no \"versions\" array", index_url))?
+            .iter()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect();
+        versions.sort_by(|a, b| compare_versions(a, b));
+        versions
+            .pop()
+            .ok_or_else(|| anyhow!("{} lists no published versions", index_url))
+    }
+
+    /// Downloads `package_id_lower`'s `version` nupkg into `dest_dir`,
+    /// returning the path to the downloaded archive.
+    fn download(
+        nuget_feed: &str,
+        package_id_lower: &str,
+        version: &str,
+        dest_dir: &Path,
+    ) -> Result<PathBuf, Error> {
+        let nupkg_url = format!(
+            "{}/{}/{}/{}.{}.nupkg",
+            nuget_feed, package_id_lower, version, package_id_lower, version
+        );
+        let archive_path = dest_dir.join(format!("{}.{}.nupkg", package_id_lower, version));
+        info!("Downloading {} from {}", package_id_lower, nupkg_url);
+        let response = reqwest::blocking::get(&nupkg_url)
+            .map_err(|e| anyhow!("failed to download {}: {}", nupkg_url, e))?
+            .error_for_status()
+            .map_err(|e| anyhow!("failed to download {}: {}", nupkg_url, e))?;
+        let bytes = response
+            .bytes()
+            .map_err(|e| anyhow!("failed to read response body for {}: {}", nupkg_url, e))?;
+        std::fs::write(&archive_path, &bytes)
+            .map_err(|e| anyhow!("failed to write archive {:?}: {}", archive_path, e))?;
+        Ok(archive_path)
+    }
+
+    /// A nupkg is just a zip archive; extract it into `dest_dir`.
+    fn extract(archive_path: &Path, dest_dir: &Path) -> Result<(), Error> {
+        let file = std::fs::File::open(archive_path)
+            .map_err(|e| anyhow!("failed to open archive {:?}: {}", archive_path, e))?;
+        let mut archive = zip::ZipArchive::new(file)
+            .map_err(|e| anyhow!("failed to read nupkg {:?}: {}", archive_path, e))?;
+        archive
+            .extract(dest_dir)
+            .map_err(|e| anyhow!("failed to extract nupkg {:?}: {}", archive_path, e))?;
+        debug!("extracted {:?} into {:?}", archive_path, dest_dir);
+        Ok(())
+    }
+}
@@ -1,9 +1,207 @@
 use std::path::{Path, PathBuf};
 
+use anyhow::anyhow;
+use netcorehost::hostfxr;
+use rayon::prelude::*;
 use tracing::{debug, info, trace, warn};
 
 use crate::provider::target_framework::TargetFramework;
 
+/// `rollForward` semantics from an `sdk` object in `global.json`.
+/// See: <https://learn.microsoft.com/en-us/dotnet/core/tools/global-json#rollforward>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RollForwardPolicy {
+    Disable,
+    Patch,
+    Feature,
+    Minor,
+    Major,
+    LatestPatch,
+    LatestFeature,
+    LatestMinor,
+    LatestMajor,
+}
+
+impl RollForwardPolicy {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "disable" => Some(Self::Disable),
+            "patch" => Some(Self::Patch),
+            "feature" => Some(Self::Feature),
+            "minor" => Some(Self::Minor),
+            "major" => Some(Self::Major),
+            "latestPatch" => Some(Self::LatestPatch),
+            "latestFeature" => Some(Self::LatestFeature),
+            "latestMinor" => Some(Self::LatestMinor),
+            "latestMajor" => Some(Self::LatestMajor),
+            _ => None,
+        }
+    }
+
+    /// Whether this policy only ever accepts an exact version match.
+    fn is_exact_only(&self) -> bool {
+        matches!(self, Self::Disable | Self::Patch)
+    }
+
+    /// Whether this policy should keep the *highest* in-scope version
+    /// rather than the lowest. The real `dotnet` host always resolves a
+    /// `latest*` policy to the newest SDK it finds in scope; the
+    /// non-`latest` variants (`patch`/`feature`/`minor`/`major`) instead
+    /// settle for the lowest version that still satisfies the pin, to
+    /// avoid pulling in a newer SDK than the pin strictly requires.
+    fn prefers_highest(&self) -> bool {
+        matches!(
+            self,
+            Self::LatestPatch | Self::LatestFeature | Self::LatestMinor | Self::LatestMajor
+        )
+    }
+
+    /// Whether `candidate` (major, minor, patch) is a valid roll-forward
+    /// target for `pin` (major, minor, patch) under this policy.
+    fn in_scope(&self, pin: (u64, u64, u64), candidate: (u64, u64, u64)) -> bool {
+        match self {
+            Self::Disable | Self::Patch => candidate == pin,
+            Self::LatestPatch => {
+                candidate.0 == pin.0
+                    && candidate.1 == pin.1
+                    && candidate.2 / 100 == pin.2 / 100
+                    && candidate >= pin
+            }
+            Self::Feature | Self::LatestFeature => {
+                candidate.0 == pin.0 && candidate.1 == pin.1 && candidate >= pin
+            }
+            Self::Minor | Self::LatestMinor => candidate.0 == pin.0 && candidate >= pin,
+            Self::Major | Self::LatestMajor => candidate >= pin,
+        }
+    }
+}
+
+impl Default for RollForwardPolicy {
+    /// The SDK's own default when `rollForward` is omitted from `global.json`.
+    fn default() -> Self {
+        Self::LatestPatch
+    }
+}
+
+/// An SDK version pin discovered from the nearest `global.json`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SdkPin {
+    pub(crate) version: String,
+    pub(crate) roll_forward: RollForwardPolicy,
+    pub(crate) allow_prerelease: bool,
+}
+
+impl SdkPin {
+    /// Walk up from `start_dir` to find the nearest `global.json` and parse
+    /// its `sdk` object. Returns `None` if no `global.json` is found, or if
+    /// it doesn't pin an SDK version.
+    pub(crate) fn find(start_dir: &Path) -> Option<Self> {
+        let mut dir = Some(start_dir);
+        while let Some(current) = dir {
+            let candidate = current.join("global.json");
+            if candidate.is_file() {
+                return match Self::parse_file(&candidate) {
+                    Ok(pin) => pin,
+                    Err(e) => {
+                        warn!("Failed to parse global.json at {:?}: {}", candidate, e);
+                        None
+                    }
+                };
+            }
+            dir = current.parent();
+        }
+        None
+    }
+
+    fn parse_file(path: &Path) -> Result<Option<Self>, anyhow::Error> {
+        let contents = std::fs::read_to_string(path)?;
+        let json: serde_json::Value = serde_json::from_str(&contents)?;
+        let sdk = match json.get("sdk") {
+            Some(sdk) => sdk,
+            None => return Ok(None),
+        };
+        let version = match sdk.get("version").and_then(|v| v.as_str()) {
+            Some(version) => version.to_string(),
+            None => return Ok(None),
+        };
+        let roll_forward = sdk
+            .get("rollForward")
+            .and_then(|v| v.as_str())
+            .and_then(RollForwardPolicy::parse)
+            .unwrap_or_default();
+        let allow_prerelease = sdk
+            .get("allowPrerelease")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        debug!(
+            "Found global.json SDK pin at {:?}: version={} rollForward={:?}",
+            path, version, roll_forward
+        );
+
+        Ok(Some(SdkPin {
+            version,
+            roll_forward,
+            allow_prerelease,
+        }))
+    }
+}
+
+/// Parse an installed SDK version folder name (e.g. `8.0.100-preview.1`) into
+/// (major, minor, patch, prerelease tag).
+fn parse_sdk_version(version: &str) -> Option<(u64, u64, u64, Option<&str>)> {
+    let (numeric, prerelease) = match version.split_once('-') {
+        Some((numeric, pre)) => (numeric, Some(pre)),
+        None => (version, None),
+    };
+    let mut parts = numeric.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    let patch = parts.next()?.parse().ok()?;
+    Some((major, minor, patch, prerelease))
+}
+
+/// Parse the (major, minor) out of a modern "netX.Y" TFM base, e.g.
+/// `net8.0` -> `(8, 0)`. Returns `None` for monikers that aren't eligible
+/// for roll-forward (`net48`, `netstandard2.0`, platform-suffixed TFMs, etc).
+fn parse_net_tfm_base(tfm: &str) -> Option<(u64, u64)> {
+    let rest = tfm.strip_prefix("net")?;
+    if rest.contains('-') {
+        return None;
+    }
+    let mut parts = rest.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((major, minor))
+}
+
+/// Roll-forward policy for selecting a reference pack version when no exact
+/// TFM match exists, mirroring the real host's framework resolution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TfmRollForwardPolicy {
+    /// Same major, lowest minor >= requested. The default.
+    Minor,
+    /// Same major, highest minor.
+    LatestMinor,
+    /// Allow a higher major too, lowest (major, minor) >= requested.
+    Major,
+    /// Allow a higher major too, highest (major, minor).
+    LatestMajor,
+    /// Exact major.minor, highest patch.
+    LatestPatch,
+    /// Exact match only, no roll-forward.
+    Disable,
+}
+
+impl Default for TfmRollForwardPolicy {
+    fn default() -> Self {
+        Self::Minor
+    }
+}
+
 /// SDK detection result
 #[derive(Debug)]
 pub enum SdkSource {
@@ -12,30 +210,66 @@ pub enum SdkSource {
         path: PathBuf,
         /// Source of the SDK: "configured" or "detected"
         source: &'static str,
+        /// The concrete SDK version matched, when known: set for a
+        /// configured path, a `global.json` pin and the directory scan;
+        /// `None` when the SDK was found through hostfxr enumeration.
+        version: Option<String>,
     },
     /// No SDK found, needs installation
     NotFound,
 }
 
+/// An installed .NET SDK, as reported by hostfxr's environment info.
+#[derive(Debug, Clone)]
+pub(crate) struct HostfxrSdk {
+    pub(crate) version: String,
+    pub(crate) path: PathBuf,
+}
+
+/// An installed shared framework (e.g. `Microsoft.NETCore.App`,
+/// `Microsoft.AspNetCore.App`), as reported by hostfxr's environment info.
+#[derive(Debug, Clone)]
+pub(crate) struct HostfxrFramework {
+    pub(crate) name: String,
+    pub(crate) version: String,
+    pub(crate) path: PathBuf,
+}
+
+/// The authoritative set of installed SDKs and shared frameworks, ordered by
+/// version, as reported by hostfxr rather than inferred from folder layout.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct HostfxrInventory {
+    pub(crate) sdks: Vec<HostfxrSdk>,
+    pub(crate) frameworks: Vec<HostfxrFramework>,
+}
+
 pub struct SdkDetector;
 
 impl SdkDetector {
     pub fn find_sdk(
         configured_path: Option<&Path>,
         target_framework: &TargetFramework,
+        pinned: Option<&SdkPin>,
     ) -> SdkSource {
         // Check for user configured SDK path
         if let Some(path) = configured_path {
             if path.exists() {
-                if Self::validate_sdk_for_tfm(path, target_framework) {
+                if let Some(version) = Self::validate_sdk_for_tfm_with_policy(
+                    path,
+                    target_framework,
+                    TfmRollForwardPolicy::default(),
+                    false,
+                ) {
                     info!(
-                        "Using configured SDK path {:?} for TFM {}",
+                        "Using configured SDK path {:?} (version {}) for TFM {}",
                         path,
+                        version,
                         target_framework.as_str()
                     );
                     return SdkSource::Found {
                         path: path.to_path_buf(),
                         source: "configured",
+                        version: Some(version),
                     };
                 } else {
                     warn!(
@@ -49,6 +283,64 @@ impl SdkDetector {
             }
         }
 
+        // A global.json pin, when present, takes priority over ordinary
+        // detection: Konveyor migrations often run against repos that
+        // deliberately pin an SDK, and silently using a detected newer SDK
+        // would produce analysis that doesn't match the actual build.
+        if let Some(pin) = pinned {
+            if let Some((path, version)) = Self::find_pinned_sdk(pin, target_framework) {
+                info!(
+                    "Using SDK {} at {:?} for TFM {} per global.json pin",
+                    version,
+                    path,
+                    target_framework.as_str()
+                );
+                return SdkSource::Found {
+                    path,
+                    source: "detected",
+                    version: Some(version),
+                };
+            }
+            if pin.roll_forward.is_exact_only() {
+                warn!(
+                    "global.json pins SDK {} (rollForward={:?}) but no matching installed SDK was found",
+                    pin.version, pin.roll_forward
+                );
+                return SdkSource::NotFound;
+            }
+            debug!(
+                "no installed SDK satisfies global.json pin {:?}, falling back to default detection",
+                pin
+            );
+        }
+
+        // Prefer the authoritative hostfxr enumeration when a hostfxr library
+        // can be located, since it gives us exact versions and framework
+        // names instead of inferring them from folder layout.
+        if let Some(inventory) = Self::get_hostfxr_inventory() {
+            if let Some(path) = Self::find_compatible_framework_path(&inventory, target_framework)
+            {
+                info!(
+                    "Detected SDK/framework at {:?} for TFM {} via hostfxr",
+                    path,
+                    target_framework.as_str()
+                );
+                return SdkSource::Found {
+                    path,
+                    source: "detected",
+                    version: None,
+                };
+            }
+            debug!(
+                "hostfxr enumeration found {} SDK(s) and {} framework(s), none compatible with TFM {}",
+                inventory.sdks.len(),
+                inventory.frameworks.len(),
+                target_framework.as_str()
+            );
+        } else {
+            debug!("unable to load hostfxr, falling back to directory scan");
+        }
+
         // Detect system installations
         let system_paths = Self::get_system_sdk_paths();
         for sdk_path in &system_paths {
@@ -57,15 +349,22 @@ impl SdkDetector {
                 continue;
             }
 
-            if Self::validate_sdk_for_tfm(sdk_path, target_framework) {
+            if let Some(version) = Self::validate_sdk_for_tfm_with_policy(
+                sdk_path,
+                target_framework,
+                TfmRollForwardPolicy::default(),
+                false,
+            ) {
                 info!(
-                    "Detected system SDK at {:?} for TFM {}",
+                    "Detected system SDK at {:?} (version {}) for TFM {}",
                     sdk_path,
+                    version,
                     target_framework.as_str()
                 );
                 return SdkSource::Found {
                     path: sdk_path.clone(),
                     source: "detected",
+                    version: Some(version),
                 };
             }
         }
@@ -78,6 +377,80 @@ impl SdkDetector {
         SdkSource::NotFound
     }
 
+    /// List the installed SDK version folder names (e.g. `8.0.100`) under a
+    /// `<root>/sdk` directory.
+    fn list_installed_sdk_versions(root: &Path) -> Vec<String> {
+        let sdk_dir = root.join("sdk");
+        match std::fs::read_dir(&sdk_dir) {
+            Ok(entries) => entries
+                .filter_map(|e| e.ok())
+                .filter(|e| e.path().is_dir())
+                .filter_map(|e| e.file_name().to_str().map(|s| s.to_string()))
+                .collect(),
+            Err(e) => {
+                trace!("no installed SDKs under {:?}: {}", sdk_dir, e);
+                vec![]
+            }
+        }
+    }
+
+    /// Find the installed SDK root and version that best satisfies `pin`,
+    /// among both hostfxr-reported SDKs and directory-scanned roots, and is
+    /// also compatible with `target_framework`.
+    fn find_pinned_sdk(pin: &SdkPin, target_framework: &TargetFramework) -> Option<(PathBuf, String)> {
+        let pin_tuple = parse_sdk_version(&pin.version).map(|(maj, min, patch, _)| (maj, min, patch))?;
+
+        let mut roots: Vec<PathBuf> = Vec::new();
+        if let Some(inventory) = Self::get_hostfxr_inventory() {
+            for sdk in &inventory.sdks {
+                if let Some(root) = sdk.path.parent().and_then(|p| p.parent()) {
+                    let root = root.to_path_buf();
+                    if !roots.contains(&root) {
+                        roots.push(root);
+                    }
+                }
+            }
+        }
+        for path in Self::get_system_sdk_paths() {
+            if !roots.contains(&path) {
+                roots.push(path);
+            }
+        }
+
+        let mut best: Option<(PathBuf, String, (u64, u64, u64))> = None;
+        for root in &roots {
+            for version in Self::list_installed_sdk_versions(root) {
+                let Some((major, minor, patch, prerelease)) = parse_sdk_version(&version) else {
+                    continue;
+                };
+                if prerelease.is_some() && !pin.allow_prerelease {
+                    continue;
+                }
+                let candidate_tuple = (major, minor, patch);
+                if !pin.roll_forward.in_scope(pin_tuple, candidate_tuple) {
+                    continue;
+                }
+                if !Self::validate_sdk_for_tfm(root, target_framework) {
+                    continue;
+                }
+                let is_better = match &best {
+                    None => true,
+                    Some((_, _, existing)) => {
+                        if pin.roll_forward.prefers_highest() {
+                            candidate_tuple > *existing
+                        } else {
+                            candidate_tuple < *existing
+                        }
+                    }
+                };
+                if is_better {
+                    best = Some((root.clone(), version, candidate_tuple));
+                }
+            }
+        }
+        best.map(|(root, version, _)| (root, version))
+    }
+
     /// Get platform-specific SDK installation paths
     fn get_system_sdk_paths() -> Vec<PathBuf> {
         let mut paths = Vec::new();
@@ -132,20 +505,41 @@ impl SdkDetector {
         paths
     }
 
-    /// Check if a path contains a valid SDK for the target framework
+    /// Check if a path contains a valid SDK for the target framework, using
+    /// the default roll-forward policy (same major, lowest compatible minor).
     fn validate_sdk_for_tfm(sdk_root: &Path, target_framework: &TargetFramework) -> bool {
+        Self::validate_sdk_for_tfm_with_policy(
+            sdk_root,
+            target_framework,
+            TfmRollForwardPolicy::default(),
+            false,
+        )
+        .is_some()
+    }
+
+    /// Check if a path contains a reference pack compatible with
+    /// `target_framework` under a given roll-forward `policy`, mirroring the
+    /// real host's framework resolution. Always tries the exact TFM match
+    /// first; only falls back to roll-forward when that's absent and
+    /// `policy` isn't `Disable`. Returns the concrete pack version chosen.
+    fn validate_sdk_for_tfm_with_policy(
+        sdk_root: &Path,
+        target_framework: &TargetFramework,
+        policy: TfmRollForwardPolicy,
+        allow_prerelease: bool,
+    ) -> Option<String> {
         let packs_path = sdk_root.join("packs");
 
         if !packs_path.exists() || !packs_path.is_dir() {
             debug!("No packs directory found at {:?}", packs_path);
-            return false;
+            return None;
         }
 
         // Look for Microsoft.NETCore.App.Ref pack
         let netcore_pack = packs_path.join("Microsoft.NETCore.App.Ref");
         if !netcore_pack.exists() {
             debug!("No Microsoft.NETCore.App.Ref found at {:?}", netcore_pack);
-            return false;
+            return None;
         }
 
         // Find available versions
@@ -157,16 +551,16 @@ impl SdkDetector {
                 .collect(),
             Err(e) => {
                 debug!("Failed to read {:?}: {}", netcore_pack, e);
-                return false;
+                return None;
             }
         };
 
         if versions.is_empty() {
             debug!("No SDK versions found in {:?}", netcore_pack);
-            return false;
+            return None;
         }
 
-        // Check if any version has the ref/<tfm> directory
+        // Exact match is always preferred, regardless of policy.
         let tfm_str = target_framework.as_str();
         for version in &versions {
             let ref_path = netcore_pack.join(version).join("ref").join(tfm_str);
@@ -175,15 +569,334 @@ impl SdkDetector {
                     "Found compatible SDK at {:?} with version {} for TFM {}",
                     sdk_root, version, tfm_str
                 );
-                return true;
+                return Some(version.clone());
+            }
+        }
+
+        if policy == TfmRollForwardPolicy::Disable {
+            debug!(
+                "SDK at {:?} found but no exact TFM match for {} and roll-forward is disabled. Available versions: {:?}",
+                sdk_root, tfm_str, versions
+            );
+            return None;
+        }
+
+        let Some((req_major, req_minor)) = parse_net_tfm_base(tfm_str) else {
+            debug!(
+                "SDK at {:?} found but no exact TFM match for {}, and {} isn't a roll-forward-eligible moniker",
+                sdk_root, tfm_str, tfm_str
+            );
+            return None;
+        };
+
+        // Roll forward: consider every installed pack version whose own
+        // ref/<netX.Y> directory is present, scoped per `policy`.
+        let mut candidates: Vec<(u64, u64, u64, String)> = Vec::new();
+        for version in &versions {
+            let Some((major, minor, patch, prerelease)) = parse_sdk_version(version) else {
+                continue;
+            };
+            if prerelease.is_some() && !allow_prerelease {
+                continue;
+            }
+            let family_tfm = format!("net{}.{}", major, minor);
+            let ref_path = netcore_pack.join(version).join("ref").join(&family_tfm);
+            if !ref_path.is_dir() {
+                continue;
+            }
+            let in_scope = match policy {
+                TfmRollForwardPolicy::Minor | TfmRollForwardPolicy::LatestMinor => {
+                    major == req_major && minor >= req_minor
+                }
+                TfmRollForwardPolicy::Major | TfmRollForwardPolicy::LatestMajor => {
+                    (major, minor) >= (req_major, req_minor)
+                }
+                TfmRollForwardPolicy::LatestPatch => major == req_major && minor == req_minor,
+                TfmRollForwardPolicy::Disable => false,
+            };
+            if in_scope {
+                candidates.push((major, minor, patch, version.clone()));
+            }
+        }
+
+        let chosen = match policy {
+            TfmRollForwardPolicy::Minor | TfmRollForwardPolicy::Major => {
+                candidates.into_iter().min_by_key(|(maj, min, patch, _)| (*maj, *min, *patch))
+            }
+            TfmRollForwardPolicy::LatestMinor
+            | TfmRollForwardPolicy::LatestMajor
+            | TfmRollForwardPolicy::LatestPatch => {
+                candidates.into_iter().max_by_key(|(maj, min, patch, _)| (*maj, *min, *patch))
+            }
+            TfmRollForwardPolicy::Disable => None,
+        };
+
+        match chosen {
+            Some((_, _, _, version)) => {
+                debug!(
+                    "Rolled forward to SDK version {} for TFM {} under policy {:?}",
+                    version, tfm_str, policy
+                );
+                Some(version)
+            }
+            None => {
+                debug!(
+                    "SDK at {:?} found but no version satisfies roll-forward policy {:?} for {}. Available versions: {:?}",
+                    sdk_root, policy, tfm_str, versions
+                );
+                None
             }
         }
+    }
+
+    /// Load a hostfxr library and ask it for the authoritative list of
+    /// installed SDKs and shared frameworks. Returns `None` when no hostfxr
+    /// library can be located on this machine, in which case callers should
+    /// fall back to directory scanning.
+    fn get_hostfxr_inventory() -> Option<HostfxrInventory> {
+        let context = match hostfxr::nethost_load_hostfxr() {
+            Ok(context) => context,
+            Err(e) => {
+                debug!("failed to load hostfxr: {}", e);
+                return None;
+            }
+        };
+
+        let env_info = match context.get_dotnet_environment_info() {
+            Ok(info) => info,
+            Err(e) => {
+                debug!("failed to get dotnet environment info from hostfxr: {}", e);
+                return None;
+            }
+        };
+
+        let mut sdks: Vec<HostfxrSdk> = env_info
+            .sdks()
+            .iter()
+            .map(|sdk| HostfxrSdk {
+                version: sdk.version.to_string(),
+                path: PathBuf::from(sdk.path.to_string()),
+            })
+            .collect();
+        sdks.sort_by_key(|sdk| parse_sdk_version(&sdk.version).map(|(maj, min, patch, _)| (maj, min, patch)));
+
+        let mut frameworks: Vec<HostfxrFramework> = env_info
+            .frameworks()
+            .iter()
+            .map(|framework| HostfxrFramework {
+                name: framework.name.to_string(),
+                version: framework.version.to_string(),
+                path: PathBuf::from(framework.path.to_string()),
+            })
+            .collect();
+        let version_key = |version: &str| parse_sdk_version(version).map(|(maj, min, patch, _)| (maj, min, patch));
+        frameworks.sort_by(|a, b| {
+            a.name
+                .cmp(&b.name)
+                .then_with(|| version_key(&a.version).cmp(&version_key(&b.version)))
+        });
 
         debug!(
-            "SDK at {:?} found but no exact TFM match for {}. Available versions: {:?}",
-            sdk_root, tfm_str, versions
+            "hostfxr reported {} SDK(s) and {} framework(s)",
+            sdks.len(),
+            frameworks.len()
         );
-        false
+
+        Some(HostfxrInventory { sdks, frameworks })
+    }
+
+    /// Given a hostfxr inventory, find the root path of an SDK whose
+    /// reference pack directory is compatible with `target_framework`. We
+    /// re-use `validate_sdk_for_tfm` against the dotnet root two levels above
+    /// each reported SDK path, since hostfxr reports the `sdk/<version>`
+    /// directory itself.
+    fn find_compatible_framework_path(
+        inventory: &HostfxrInventory,
+        target_framework: &TargetFramework,
+    ) -> Option<PathBuf> {
+        for sdk in inventory.sdks.iter().rev() {
+            let sdk_root = sdk.path.parent().and_then(|p| p.parent()).unwrap_or(&sdk.path);
+            if Self::validate_sdk_for_tfm(sdk_root, target_framework) {
+                return Some(sdk_root.to_path_buf());
+            }
+        }
+        None
+    }
+}
+
+/// Configuration for the opt-in SDK/reference-pack acquisition subsystem.
+/// Unlike `SdkDetector`, which only ever looks at what's already installed,
+/// `SdkAcquirer` downloads what's missing so analysis can proceed on
+/// containerized/CI environments with no preinstalled SDK.
+#[derive(Debug, Clone)]
+pub(crate) struct AcquisitionConfig {
+    /// Root directory assets are downloaded and unpacked into.
+    pub(crate) cache_dir: PathBuf,
+    /// Download the full SDK payload instead of just the reference
+    /// assembly packs needed to resolve types for analysis.
+    pub(crate) full_sdk: bool,
+    /// Maximum number of archives to extract concurrently.
+    pub(crate) parallelism: usize,
+}
+
+impl Default for AcquisitionConfig {
+    fn default() -> Self {
+        Self {
+            cache_dir: Self::default_cache_dir(),
+            full_sdk: false,
+            parallelism: 4,
+        }
+    }
+}
+
+impl AcquisitionConfig {
+    /// `~/.konveyor/dotnet`, falling back to `.` if no home directory can be
+    /// determined.
+    fn default_cache_dir() -> PathBuf {
+        let home = std::env::var("HOME")
+            .or_else(|_| std::env::var("USERPROFILE"))
+            .unwrap_or_else(|_| ".".to_string());
+        PathBuf::from(home).join(".konveyor").join("dotnet")
+    }
+}
+
+/// A single downloadable archive (a reference pack or SDK build) needed to
+/// satisfy a TFM.
+#[derive(Debug, Clone)]
+struct AcquisitionAsset {
+    /// Short identifier used for logging and the downloaded file name.
+    name: String,
+    url: String,
+}
+
+pub(crate) struct SdkAcquirer;
+
+impl SdkAcquirer {
+    /// Acquire whatever is missing for `target_framework` into
+    /// `config.cache_dir`, laying out the same
+    /// `packs/Microsoft.NETCore.App.Ref/<ver>/ref/<tfm>` structure the real
+    /// SDK uses so `SdkDetector::validate_sdk_for_tfm` can find it. Returns
+    /// the acquired SDK root on success.
+    pub(crate) fn acquire(
+        target_framework: &TargetFramework,
+        config: &AcquisitionConfig,
+    ) -> Result<PathBuf, anyhow::Error> {
+        let sdk_root = config.cache_dir.join(target_framework.as_str());
+        let expected_ref_dir = Self::expected_ref_dir(&sdk_root, target_framework);
+
+        if expected_ref_dir.is_dir() {
+            debug!(
+                "Reference pack for {} already cached at {:?}",
+                target_framework.as_str(),
+                expected_ref_dir
+            );
+            return Ok(sdk_root);
+        }
+
+        std::fs::create_dir_all(&sdk_root).map_err(|e| {
+            anyhow!(
+                "Failed to create acquisition cache directory {:?}: {}",
+                sdk_root,
+                e
+            )
+        })?;
+
+        let assets = Self::assets_for(target_framework, config)?;
+        let downloads: Vec<PathBuf> = assets
+            .iter()
+            .map(|asset| Self::download(asset, &sdk_root))
+            .collect::<Result<_, anyhow::Error>>()?;
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(config.parallelism.max(1))
+            .build()
+            .map_err(|e| anyhow!("Failed to build extraction thread pool: {}", e))?;
+        pool.install(|| {
+            downloads
+                .par_iter()
+                .try_for_each(|archive| Self::extract(archive, &sdk_root))
+        })?;
+
+        if !expected_ref_dir.is_dir() {
+            return Err(anyhow!(
+                "Acquisition completed but expected layout {:?} is still missing",
+                expected_ref_dir
+            ));
+        }
+
+        info!(
+            "Acquired SDK assets for {} into {:?}",
+            target_framework.as_str(),
+            sdk_root
+        );
+        Ok(sdk_root)
+    }
+
+    /// Where `validate_sdk_for_tfm` will look once acquisition succeeds.
+    fn expected_ref_dir(sdk_root: &Path, target_framework: &TargetFramework) -> PathBuf {
+        let pack_version = format!("{}.0", target_framework.to_channel().unwrap_or_default());
+        sdk_root
+            .join("packs")
+            .join("Microsoft.NETCore.App.Ref")
+            .join(pack_version)
+            .join("ref")
+            .join(target_framework.as_str())
+    }
+
+    /// Build the list of archives that need to be fetched to satisfy
+    /// `target_framework`, per `config.full_sdk`.
+    fn assets_for(
+        target_framework: &TargetFramework,
+        config: &AcquisitionConfig,
+    ) -> Result<Vec<AcquisitionAsset>, anyhow::Error> {
+        let channel = target_framework.to_channel()?;
+        let mut assets = vec![AcquisitionAsset {
+            name: format!("netcore-app-ref-{}", channel),
+            url: format!(
+                "https://dotnetcli.blob.core.windows.net/dotnet/Runtime/{}/ref-packs.zip",
+                channel
+            ),
+        }];
+        if config.full_sdk {
+            assets.push(AcquisitionAsset {
+                name: format!("dotnet-sdk-{}", channel),
+                url: format!(
+                    "https://dotnetcli.blob.core.windows.net/dotnet/Sdk/{}/dotnet-sdk.zip",
+                    channel
+                ),
+            });
+        }
+        Ok(assets)
+    }
+
+    /// Download `asset` into `dest_dir`, returning the path to the
+    /// downloaded archive.
+    fn download(asset: &AcquisitionAsset, dest_dir: &Path) -> Result<PathBuf, anyhow::Error> {
+        let archive_path = dest_dir.join(format!("{}.zip", asset.name));
+        info!("Downloading {} from {}", asset.name, asset.url);
+        let response = reqwest::blocking::get(&asset.url)
+            .map_err(|e| anyhow!("Failed to download {}: {}", asset.url, e))?
+            .error_for_status()
+            .map_err(|e| anyhow!("Failed to download {}: {}", asset.url, e))?;
+        let bytes = response
+            .bytes()
+            .map_err(|e| anyhow!("Failed to read response body for {}: {}", asset.url, e))?;
+        std::fs::write(&archive_path, &bytes)
+            .map_err(|e| anyhow!("Failed to write archive {:?}: {}", archive_path, e))?;
+        Ok(archive_path)
+    }
+
+    /// Extract a downloaded archive into `dest_dir`.
+    fn extract(archive_path: &Path, dest_dir: &Path) -> Result<(), anyhow::Error> {
+        let file = std::fs::File::open(archive_path)
+            .map_err(|e| anyhow!("Failed to open archive {:?}: {}", archive_path, e))?;
+        let mut archive = zip::ZipArchive::new(file)
+            .map_err(|e| anyhow!("Failed to read zip archive {:?}: {}", archive_path, e))?;
+        archive
+            .extract(dest_dir)
+            .map_err(|e| anyhow!("Failed to extract archive {:?}: {}", archive_path, e))?;
+        debug!("Extracted {:?} into {:?}", archive_path, dest_dir);
+        Ok(())
     }
 }
 
@@ -218,15 +931,30 @@ mod tests {
 
         /// Create a mock SDK structure for the given TFM
         fn create_sdk_structure(&self, tfm: &str) {
+            self.create_ref_pack("8.0.0", tfm);
+        }
+
+        /// Create a `packs/Microsoft.NETCore.App.Ref/<pack_version>/ref/<tfm>` directory
+        fn create_ref_pack(&self, pack_version: &str, tfm: &str) {
             let packs = self
                 .path
                 .join("packs")
                 .join("Microsoft.NETCore.App.Ref")
-                .join("8.0.0")
+                .join(pack_version)
                 .join("ref")
                 .join(tfm);
             std::fs::create_dir_all(&packs).unwrap();
         }
+
+        /// Register an installed SDK version under `<root>/sdk/<version>`
+        fn create_sdk_version(&self, version: &str) {
+            let sdk_dir = self.path.join("sdk").join(version);
+            std::fs::create_dir_all(&sdk_dir).unwrap();
+        }
+
+        fn write_global_json(&self, contents: &str) {
+            std::fs::write(self.path.join("global.json"), contents).unwrap();
+        }
     }
 
     impl Drop for TestSdkDir {
@@ -279,7 +1007,7 @@ mod tests {
         test_dir.create_sdk_structure("net8.0");
 
         let tfm = TargetFramework::from_str("net8.0").unwrap();
-        let result = SdkDetector::find_sdk(Some(test_dir.path()), &tfm);
+        let result = SdkDetector::find_sdk(Some(test_dir.path()), &tfm, None);
 
         assert!(matches!(
             result,
@@ -294,7 +1022,7 @@ mod tests {
     fn test_find_sdk_returns_not_found_for_missing_tfm() {
         // Query for a TFM that won't exist in system SDKs
         let tfm = TargetFramework::from_str("net99.0").unwrap();
-        let result = SdkDetector::find_sdk(None, &tfm);
+        let result = SdkDetector::find_sdk(None, &tfm, None);
 
         // Should return NotFound since net99.0 won't exist
         assert!(matches!(result, SdkSource::NotFound));
@@ -307,7 +1035,7 @@ mod tests {
         let tfm = TargetFramework::from_str("net8.0").unwrap();
 
         // Since invalid_path doesn't exist, it should fall back to system detection
-        let result = SdkDetector::find_sdk(Some(invalid_path), &tfm);
+        let result = SdkDetector::find_sdk(Some(invalid_path), &tfm, None);
 
         // Should fall through configured path and either find system SDK or return NotFound
         assert!(matches!(
@@ -315,4 +1043,359 @@ mod tests {
             SdkSource::NotFound | SdkSource::Found { .. }
         ));
     }
+
+    #[test]
+    fn test_find_compatible_framework_path_with_matching_sdk() {
+        let test_dir = TestSdkDir::new();
+        test_dir.create_sdk_structure("net8.0");
+
+        let inventory = HostfxrInventory {
+            sdks: vec![HostfxrSdk {
+                version: "8.0.100".to_string(),
+                path: test_dir.path().join("sdk").join("8.0.100"),
+            }],
+            frameworks: vec![],
+        };
+
+        let tfm = TargetFramework::from_str("net8.0").unwrap();
+        let result = SdkDetector::find_compatible_framework_path(&inventory, &tfm);
+
+        assert_eq!(result, Some(test_dir.path().to_path_buf()));
+    }
+
+    #[test]
+    fn test_find_compatible_framework_path_with_no_matching_sdk() {
+        let inventory = HostfxrInventory {
+            sdks: vec![HostfxrSdk {
+                version: "8.0.100".to_string(),
+                path: PathBuf::from("/nonexistent/sdk/8.0.100"),
+            }],
+            frameworks: vec![],
+        };
+
+        let tfm = TargetFramework::from_str("net8.0").unwrap();
+        let result = SdkDetector::find_compatible_framework_path(&inventory, &tfm);
+
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_find_compatible_framework_path_with_empty_inventory() {
+        let inventory = HostfxrInventory::default();
+        let tfm = TargetFramework::from_str("net8.0").unwrap();
+        let result = SdkDetector::find_compatible_framework_path(&inventory, &tfm);
+
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_parse_sdk_version() {
+        assert_eq!(parse_sdk_version("8.0.100"), Some((8, 0, 100, None)));
+        assert_eq!(
+            parse_sdk_version("9.0.100-preview.1"),
+            Some((9, 0, 100, Some("preview.1")))
+        );
+        assert_eq!(parse_sdk_version("not-a-version"), None);
+    }
+
+    #[test]
+    fn test_roll_forward_policy_parse() {
+        assert_eq!(RollForwardPolicy::parse("disable"), Some(RollForwardPolicy::Disable));
+        assert_eq!(
+            RollForwardPolicy::parse("latestMinor"),
+            Some(RollForwardPolicy::LatestMinor)
+        );
+        assert_eq!(RollForwardPolicy::parse("bogus"), None);
+        assert_eq!(RollForwardPolicy::default(), RollForwardPolicy::LatestPatch);
+    }
+
+    #[test]
+    fn test_roll_forward_policy_in_scope() {
+        assert!(RollForwardPolicy::Disable.in_scope((8, 0, 100), (8, 0, 100)));
+        assert!(!RollForwardPolicy::Disable.in_scope((8, 0, 100), (8, 0, 101)));
+        assert!(RollForwardPolicy::LatestMinor.in_scope((8, 0, 100), (8, 1, 0)));
+        assert!(!RollForwardPolicy::LatestMinor.in_scope((8, 0, 100), (9, 0, 0)));
+        assert!(RollForwardPolicy::LatestMajor.in_scope((8, 0, 100), (9, 0, 0)));
+    }
+
+    #[test]
+    fn test_sdk_pin_find_walks_up_directories() {
+        let test_dir = TestSdkDir::new();
+        test_dir.write_global_json(
+            r#"{"sdk": {"version": "8.0.100", "rollForward": "latestMinor"}}"#,
+        );
+        let nested = test_dir.path().join("src").join("project");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        let pin = SdkPin::find(&nested).expect("expected to find global.json pin");
+        assert_eq!(pin.version, "8.0.100");
+        assert_eq!(pin.roll_forward, RollForwardPolicy::LatestMinor);
+        assert!(!pin.allow_prerelease);
+    }
+
+    #[test]
+    fn test_sdk_pin_find_returns_none_without_global_json() {
+        let test_dir = TestSdkDir::new();
+        assert!(SdkPin::find(test_dir.path()).is_none());
+    }
+
+    #[test]
+    fn test_sdk_pin_find_returns_none_without_sdk_version() {
+        let test_dir = TestSdkDir::new();
+        test_dir.write_global_json(r#"{"sdk": {}}"#);
+        assert!(SdkPin::find(test_dir.path()).is_none());
+    }
+
+    #[test]
+    fn test_find_sdk_exact_pin_not_found_returns_not_found() {
+        let tfm = TargetFramework::from_str("net8.0").unwrap();
+        let pin = SdkPin {
+            version: "8.0.999".to_string(),
+            roll_forward: RollForwardPolicy::Disable,
+            allow_prerelease: false,
+        };
+
+        let result = SdkDetector::find_sdk(None, &tfm, Some(&pin));
+        assert!(matches!(result, SdkSource::NotFound));
+    }
+
+    #[test]
+    fn test_find_sdk_non_exact_pin_falls_back_when_unmatched() {
+        let tfm = TargetFramework::from_str("net99.0").unwrap();
+        let pin = SdkPin {
+            version: "8.0.999".to_string(),
+            roll_forward: RollForwardPolicy::LatestMinor,
+            allow_prerelease: false,
+        };
+
+        // Falls through to normal detection, which also finds nothing for net99.0
+        let result = SdkDetector::find_sdk(None, &tfm, Some(&pin));
+        assert!(matches!(result, SdkSource::NotFound));
+    }
+
+    #[test]
+    fn test_find_pinned_sdk_selects_lowest_compatible_version() {
+        let test_dir = TestSdkDir::new();
+        test_dir.create_sdk_structure("net8.0");
+        test_dir.create_sdk_version("8.0.100");
+        test_dir.create_sdk_version("8.1.100");
+
+        std::env::set_var("DOTNET_ROOT", test_dir.path());
+        let tfm = TargetFramework::from_str("net8.0").unwrap();
+        // Non-`latest` policies settle for the lowest in-scope version
+        // rather than pulling in a newer SDK than the pin requires.
+        let pin = SdkPin {
+            version: "8.0.100".to_string(),
+            roll_forward: RollForwardPolicy::Minor,
+            allow_prerelease: false,
+        };
+
+        let result = SdkDetector::find_pinned_sdk(&pin, &tfm);
+        std::env::remove_var("DOTNET_ROOT");
+
+        let (path, version) = result.expect("expected a pinned SDK match");
+        assert_eq!(path, test_dir.path());
+        assert_eq!(version, "8.0.100");
+    }
+
+    #[test]
+    fn test_find_pinned_sdk_latest_policy_selects_highest_compatible_version() {
+        let test_dir = TestSdkDir::new();
+        test_dir.create_sdk_structure("net8.0");
+        // The pinned version itself isn't installed -- only two higher,
+        // in-scope candidates are -- so a `latest*` policy must pick the
+        // highest of the two rather than the lowest.
+        test_dir.create_sdk_version("8.1.100");
+        test_dir.create_sdk_version("8.2.100");
+
+        std::env::set_var("DOTNET_ROOT", test_dir.path());
+        let tfm = TargetFramework::from_str("net8.0").unwrap();
+        let pin = SdkPin {
+            version: "8.0.100".to_string(),
+            roll_forward: RollForwardPolicy::LatestMinor,
+            allow_prerelease: false,
+        };
+
+        let result = SdkDetector::find_pinned_sdk(&pin, &tfm);
+        std::env::remove_var("DOTNET_ROOT");
+
+        let (path, version) = result.expect("expected a pinned SDK match");
+        assert_eq!(path, test_dir.path());
+        assert_eq!(version, "8.2.100");
+    }
+
+    #[test]
+    fn test_parse_net_tfm_base() {
+        assert_eq!(parse_net_tfm_base("net8.0"), Some((8, 0)));
+        assert_eq!(parse_net_tfm_base("net48"), None);
+        assert_eq!(parse_net_tfm_base("netstandard2.0"), None);
+        assert_eq!(parse_net_tfm_base("net8.0-android"), None);
+    }
+
+    #[test]
+    fn test_roll_forward_minor_picks_lowest_compatible_minor() {
+        let test_dir = TestSdkDir::new();
+        test_dir.create_ref_pack("8.1.0", "net8.1");
+        test_dir.create_ref_pack("8.2.0", "net8.2");
+
+        let tfm = TargetFramework::from_str("net8.0").unwrap();
+        let result = SdkDetector::validate_sdk_for_tfm_with_policy(
+            test_dir.path(),
+            &tfm,
+            TfmRollForwardPolicy::Minor,
+            false,
+        );
+
+        assert_eq!(result, Some("8.1.0".to_string()));
+    }
+
+    #[test]
+    fn test_roll_forward_latest_minor_picks_highest_minor() {
+        let test_dir = TestSdkDir::new();
+        test_dir.create_ref_pack("8.1.0", "net8.1");
+        test_dir.create_ref_pack("8.2.0", "net8.2");
+
+        let tfm = TargetFramework::from_str("net8.0").unwrap();
+        let result = SdkDetector::validate_sdk_for_tfm_with_policy(
+            test_dir.path(),
+            &tfm,
+            TfmRollForwardPolicy::LatestMinor,
+            false,
+        );
+
+        assert_eq!(result, Some("8.2.0".to_string()));
+    }
+
+    #[test]
+    fn test_roll_forward_major_allows_higher_major() {
+        let test_dir = TestSdkDir::new();
+        test_dir.create_ref_pack("9.0.0", "net9.0");
+
+        let tfm = TargetFramework::from_str("net8.0").unwrap();
+        let result = SdkDetector::validate_sdk_for_tfm_with_policy(
+            test_dir.path(),
+            &tfm,
+            TfmRollForwardPolicy::Major,
+            false,
+        );
+
+        assert_eq!(result, Some("9.0.0".to_string()));
+    }
+
+    #[test]
+    fn test_roll_forward_disable_never_rolls_forward() {
+        let test_dir = TestSdkDir::new();
+        test_dir.create_ref_pack("8.1.0", "net8.1");
+
+        let tfm = TargetFramework::from_str("net8.0").unwrap();
+        let result = SdkDetector::validate_sdk_for_tfm_with_policy(
+            test_dir.path(),
+            &tfm,
+            TfmRollForwardPolicy::Disable,
+            false,
+        );
+
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_roll_forward_excludes_prerelease_by_default() {
+        let test_dir = TestSdkDir::new();
+        test_dir.create_ref_pack("8.1.0-preview.1", "net8.1");
+
+        let tfm = TargetFramework::from_str("net8.0").unwrap();
+        let result = SdkDetector::validate_sdk_for_tfm_with_policy(
+            test_dir.path(),
+            &tfm,
+            TfmRollForwardPolicy::LatestMinor,
+            false,
+        );
+
+        assert!(result.is_none());
+
+        let result_with_prerelease = SdkDetector::validate_sdk_for_tfm_with_policy(
+            test_dir.path(),
+            &tfm,
+            TfmRollForwardPolicy::LatestMinor,
+            true,
+        );
+
+        assert_eq!(result_with_prerelease, Some("8.1.0-preview.1".to_string()));
+    }
+
+    #[test]
+    fn test_roll_forward_exact_match_wins_over_policy() {
+        let test_dir = TestSdkDir::new();
+        test_dir.create_ref_pack("8.0.0", "net8.0");
+        test_dir.create_ref_pack("8.5.0", "net8.5");
+
+        let tfm = TargetFramework::from_str("net8.0").unwrap();
+        let result = SdkDetector::validate_sdk_for_tfm_with_policy(
+            test_dir.path(),
+            &tfm,
+            TfmRollForwardPolicy::LatestMinor,
+            false,
+        );
+
+        assert_eq!(result, Some("8.0.0".to_string()));
+    }
+
+    #[test]
+    fn test_acquisition_config_default_cache_dir_is_under_konveyor() {
+        let config = AcquisitionConfig::default();
+        assert!(config.cache_dir.ends_with(".konveyor/dotnet") || config.cache_dir.ends_with(".konveyor\\dotnet"));
+        assert!(!config.full_sdk);
+        assert_eq!(config.parallelism, 4);
+    }
+
+    #[test]
+    fn test_expected_ref_dir_layout() {
+        let tfm = TargetFramework::from_str("net8.0").unwrap();
+        let sdk_root = PathBuf::from("/cache/net8.0");
+        let expected = SdkAcquirer::expected_ref_dir(&sdk_root, &tfm);
+
+        assert_eq!(
+            expected,
+            PathBuf::from("/cache/net8.0/packs/Microsoft.NETCore.App.Ref/8.0.0/ref/net8.0")
+        );
+    }
+
+    #[test]
+    fn test_assets_for_reference_packs_only_by_default() {
+        let tfm = TargetFramework::from_str("net8.0").unwrap();
+        let config = AcquisitionConfig::default();
+        let assets = SdkAcquirer::assets_for(&tfm, &config).unwrap();
+
+        assert_eq!(assets.len(), 1);
+        assert!(assets[0].name.contains("net"));
+    }
+
+    #[test]
+    fn test_assets_for_includes_full_sdk_when_requested() {
+        let tfm = TargetFramework::from_str("net8.0").unwrap();
+        let config = AcquisitionConfig {
+            full_sdk: true,
+            ..AcquisitionConfig::default()
+        };
+        let assets = SdkAcquirer::assets_for(&tfm, &config).unwrap();
+
+        assert_eq!(assets.len(), 2);
+    }
+
+    #[test]
+    fn test_acquire_skips_download_when_already_cached() {
+        let test_dir = TestSdkDir::new();
+        let tfm = TargetFramework::from_str("net8.0").unwrap();
+        let config = AcquisitionConfig {
+            cache_dir: test_dir.path().to_path_buf(),
+            ..AcquisitionConfig::default()
+        };
+
+        let sdk_root = test_dir.path().join(tfm.as_str());
+        let ref_dir = SdkAcquirer::expected_ref_dir(&sdk_root, &tfm);
+        std::fs::create_dir_all(&ref_dir).unwrap();
+
+        let result = SdkAcquirer::acquire(&tfm, &config);
+        assert_eq!(result.unwrap(), sdk_root);
+    }
 }
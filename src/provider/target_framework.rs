@@ -1,22 +1,293 @@
 use anyhow::{anyhow, Error};
 use quick_xml::events::Event;
 use quick_xml::Reader;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
 use std::fs::File;
 use std::io::BufReader;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use tracing::{debug, error, info};
 use walkdir::WalkDir;
 
-/// Represents a Target Framework Moniker (TFM)
+use crate::provider::sdk_detection::SdkPin;
+
+/// What to request from dotnet-install for an SDK: roll forward within a
+/// TFM-derived channel, or install the exact version pinned by a
+/// `global.json`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum SdkRequest {
+    Channel(String),
+    ExactVersion(String),
+}
+
+/// Known OS-specific platform identifiers for TFMs like `net8.0-android`.
+/// See: https://learn.microsoft.com/en-us/dotnet/standard/frameworks#net-5-os-specific-tfms
+const KNOWN_PLATFORMS: &[&str] = &[
+    "android",
+    "ios",
+    "maccatalyst",
+    "macos",
+    "tvos",
+    "tizen",
+    "windows",
+    "browser",
+];
+
+/// Known `netcoreapp` minor versions in ascending order, used to walk the
+/// TFM fallback chain since they aren't a dense `X.0`..`X.Y` range.
+const NETCOREAPP_VERSIONS: &[(u32, u32)] = &[(1, 0), (1, 1), (2, 0), (2, 1), (2, 2), (3, 0), (3, 1)];
+
+/// Known `netstandard` minor versions in ascending order.
+const NETSTANDARD_VERSIONS: &[(u32, u32)] = &[
+    (1, 0),
+    (1, 1),
+    (1, 2),
+    (1, 3),
+    (1, 4),
+    (1, 5),
+    (1, 6),
+    (2, 0),
+    (2, 1),
+];
+
+/// Known modern .NET (`net5.0` and up) major versions to date, mirroring
+/// [`NETCOREAPP_VERSIONS`]/[`NETSTANDARD_VERSIONS`] above -- extend as new
+/// ones ship.
+const MODERN_NET_MAJOR_VERSIONS: &[u32] = &[5, 6, 7, 8, 9, 10];
+
+/// Known .NET Framework versions in ascending order, as (major, minor, patch).
+const NET_FRAMEWORK_VERSIONS: &[(u32, u32, u32)] = &[
+    (4, 5, 0),
+    (4, 5, 1),
+    (4, 6, 0),
+    (4, 6, 1),
+    (4, 6, 2),
+    (4, 7, 0),
+    (4, 7, 1),
+    (4, 7, 2),
+    (4, 8, 0),
+    (4, 8, 1),
+];
+
+/// Which family of .NET a [`TargetFramework`] belongs to, for callers that
+/// key migration logic off the broad category (e.g. "migrate .NET
+/// Framework -> modern .NET") rather than the exact moniker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TfmFamily {
+    NetFramework,
+    NetCore,
+    NetStandard,
+    ModernNet,
+}
+
+/// Represents a Target Framework Moniker (TFM), optionally with an
+/// OS-specific platform suffix (e.g. `net8.0-android`, `net8.0-windows10.0.19041`).
 /// See: https://learn.microsoft.com/en-us/dotnet/standard/frameworks
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
-pub(crate) struct TargetFramework(String);
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct TargetFramework {
+    base: String,
+    platform: Option<String>,
+    platform_version: Option<String>,
+}
+
+/// The TargetFramework-related MSBuild properties we read out of a project
+/// or props file.
+#[derive(Debug, Default, Clone)]
+struct MsBuildProperties {
+    target_framework: Option<String>,
+    target_frameworks: Option<String>,
+    target_framework_version: Option<String>,
+}
+
+/// An installed .NET SDK, as reported by `dotnet --list-sdks`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SdkInfo {
+    pub(crate) version: String,
+    pub(crate) path: PathBuf,
+}
+
+/// An installed shared runtime, as reported by `dotnet --list-runtimes`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FrameworkInfo {
+    pub(crate) name: String,
+    pub(crate) version: String,
+    pub(crate) path: PathBuf,
+}
+
+/// The set of .NET SDKs and shared runtimes already installed on this
+/// machine, so `install_sdk` can skip invoking dotnet-install when a
+/// compatible SDK is already present.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct InstalledEnvironment {
+    pub(crate) sdks: Vec<SdkInfo>,
+    pub(crate) frameworks: Vec<FrameworkInfo>,
+}
+
+impl InstalledEnvironment {
+    /// Discover installed SDKs/runtimes by shelling out to `dotnet
+    /// --list-sdks` and `dotnet --list-runtimes`. Returns an empty
+    /// environment (not an error) when the `dotnet` executable isn't on
+    /// PATH, since that just means nothing is installed yet.
+    pub(crate) fn discover() -> Self {
+        Self {
+            sdks: Self::list_sdks(),
+            frameworks: Self::list_runtimes(),
+        }
+    }
+
+    /// The installed SDK root (the directory containing `sdk/`) whose
+    /// version matches `channel` (e.g. `"8.0"`), if any.
+    pub(crate) fn root_satisfying_channel(&self, channel: &str) -> Option<PathBuf> {
+        let prefix = format!("{}.", channel);
+        self.sdks
+            .iter()
+            .find(|sdk| sdk.version == channel || sdk.version.starts_with(&prefix))
+            .and_then(|sdk| sdk.path.parent().map(|p| p.to_path_buf()))
+    }
+
+    /// The installed SDK root that satisfies an `SdkRequest`: a channel
+    /// match for `Channel`, or an exact version match for `ExactVersion`.
+    pub(crate) fn root_satisfying(&self, request: &SdkRequest) -> Option<PathBuf> {
+        match request {
+            SdkRequest::Channel(channel) => self.root_satisfying_channel(channel),
+            SdkRequest::ExactVersion(version) => self
+                .sdks
+                .iter()
+                .find(|sdk| &sdk.version == version)
+                .and_then(|sdk| sdk.path.parent().map(|p| p.to_path_buf())),
+        }
+    }
+
+    fn list_sdks() -> Vec<SdkInfo> {
+        let output = match Command::new("dotnet").arg("--list-sdks").output() {
+            Ok(output) if output.status.success() => output,
+            Ok(output) => {
+                debug!("dotnet --list-sdks exited with {:?}", output.status);
+                return Vec::new();
+            }
+            Err(e) => {
+                debug!("dotnet executable not found on PATH: {}", e);
+                return Vec::new();
+            }
+        };
+
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(Self::parse_list_line)
+            .map(|(version, path)| SdkInfo { version, path })
+            .collect()
+    }
+
+    fn list_runtimes() -> Vec<FrameworkInfo> {
+        let output = match Command::new("dotnet").arg("--list-runtimes").output() {
+            Ok(output) if output.status.success() => output,
+            Ok(output) => {
+                debug!("dotnet --list-runtimes exited with {:?}", output.status);
+                return Vec::new();
+            }
+            Err(e) => {
+                debug!("dotnet executable not found on PATH: {}", e);
+                return Vec::new();
+            }
+        };
+
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                let (name, rest) = line.trim().split_once(' ')?;
+                let (version, path) = Self::parse_list_line(rest)?;
+                Some(FrameworkInfo {
+                    name: name.to_string(),
+                    version,
+                    path,
+                })
+            })
+            .collect()
+    }
+
+    /// Parse a `dotnet --list-sdks`/`--list-runtimes`-style line's trailing
+    /// `"<version> [<path>]"` into `(version, path)`.
+    fn parse_list_line(line: &str) -> Option<(String, PathBuf)> {
+        let line = line.trim();
+        let bracket_start = line.find('[')?;
+        let bracket_end = line.find(']')?;
+        let version = line[..bracket_start].trim().to_string();
+        let path = line[bracket_start + 1..bracket_end].trim().to_string();
+        if version.is_empty() || path.is_empty() {
+            return None;
+        }
+        Some((version, PathBuf::from(path)))
+    }
+}
 
 impl fmt::Display for TargetFramework {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.0)
+        write!(f, "{}", self.base)?;
+        if let Some(platform) = &self.platform {
+            write!(f, "-{}", platform)?;
+            if let Some(version) = &self.platform_version {
+                write!(f, "{}", version)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Serializes as the plain TFM string (e.g. `"net8.0-android"`), matching
+/// how TFMs appear in `.csproj`/JSON rule configs.
+impl Serialize for TargetFramework {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// Deserializes through [`TargetFramework::from_str`], so configs
+/// referencing a moniker `is_valid_base_tfm` would reject fail loudly at
+/// load time with a typed error instead of silently producing an empty
+/// match set later.
+impl<'de> Deserialize<'de> for TargetFramework {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw = String::deserialize(deserializer)?;
+        TargetFramework::from_str(&raw).map_err(serde::de::Error::custom)
+    }
+}
+
+impl TryFrom<String> for TargetFramework {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self, Error> {
+        TargetFramework::from_str(&value)
+    }
+}
+
+impl TryFrom<&str> for TargetFramework {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Error> {
+        TargetFramework::from_str(value)
+    }
+}
+
+/// Orders TFMs semantically by family (.NET Framework < .NET Standard <
+/// .NET Core < modern .NET) and then by numeric version, instead of
+/// comparing the raw TFM strings lexicographically (which would sort
+/// "net10.0" before "net9.0").
+impl PartialOrd for TargetFramework {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TargetFramework {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.version_key().cmp(&other.version_key())
     }
 }
 
@@ -24,95 +295,388 @@ impl TargetFramework {
     /// Parse TFM from a .csproj file
     /// Supports both new SDK-style and old .NET Framework .csproj formats
     /// Handles all TFM formats from Microsoft documentation
+    ///
+    /// For multi-targeted projects (`<TargetFrameworks>`), returns the
+    /// earliest of the targeted frameworks; use [`Self::all_from_csproj`] to
+    /// get every targeted framework.
     pub(crate) fn from_csproj(csproj_path: &PathBuf) -> Result<Self, Error> {
-        debug!("Parsing TargetFramework from {:?}", csproj_path);
+        let frameworks = Self::all_from_csproj(csproj_path)?;
+        frameworks.into_iter().min().ok_or_else(|| {
+            anyhow!(
+                "No TargetFramework, TargetFrameworks, or TargetFrameworkVersion found in {:?}",
+                csproj_path
+            )
+        })
+    }
 
-        let file = File::open(csproj_path)
-            .map_err(|e| anyhow!("Failed to open .csproj file {:?}: {}", csproj_path, e))?;
-        let buf_reader = BufReader::new(file);
-        let mut reader = Reader::from_reader(buf_reader);
-        reader.config_mut().trim_text(true);
+    /// Parse every targeted framework from a .csproj file, including
+    /// multi-targeted projects that declare `<TargetFrameworks>net8.0;net6.0</TargetFrameworks>`.
+    pub(crate) fn all_from_csproj(csproj_path: &PathBuf) -> Result<Vec<Self>, Error> {
+        debug!("Parsing TargetFramework(s) from {:?}", csproj_path);
 
-        let mut buf = Vec::new();
-        let mut in_property_group = false;
-        let mut target_framework: Option<String> = None;
-        let mut target_framework_version: Option<String> = None;
+        let mut properties = Self::parse_msbuild_properties(csproj_path)?;
+        if Self::split_target_frameworks(&properties).is_none() {
+            if let Some(inherited) = Self::find_inherited_properties(csproj_path) {
+                debug!(
+                    "No TargetFramework in {:?}, inheriting from Directory.Build.props/.targets or Packages.props",
+                    csproj_path
+                );
+                properties = inherited;
+            }
+        }
 
-        loop {
-            match reader.read_event_into(&mut buf) {
-                Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
-                    let name = e.name();
-                    match name.as_ref() {
-                        b"PropertyGroup" => {
-                            in_property_group = true;
-                        }
-                        b"TargetFramework" if in_property_group => {
-                            // New-style .NET Core/.NET 5+ projects use <TargetFramework>
-                            if let Ok(Event::Text(text)) = reader.read_event_into(&mut buf) {
-                                let value = String::from_utf8_lossy(&text).trim().to_string();
-                                if !value.is_empty() {
-                                    target_framework = Some(value);
-                                }
-                            }
-                        }
-                        b"TargetFrameworkVersion" if in_property_group => {
-                            // Old-style .NET Framework projects use <TargetFrameworkVersion>
-                            if let Ok(Event::Text(text)) = reader.read_event_into(&mut buf) {
-                                let value = String::from_utf8_lossy(&text).trim().to_string();
-                                if !value.is_empty() {
-                                    target_framework_version = Some(value);
-                                }
-                            }
-                        }
-                        _ => {}
-                    }
-                }
-                Ok(Event::End(e)) => {
-                    if e.name().as_ref() == b"PropertyGroup" {
-                        in_property_group = false;
-                    }
-                }
-                Ok(Event::Eof) => break,
-                Err(e) => {
+        let raw_frameworks = Self::split_target_frameworks(&properties).ok_or_else(|| {
+            anyhow!(
+                "No TargetFramework, TargetFrameworks, or TargetFrameworkVersion found in {:?} (including inherited Directory.Build.props/.targets or Packages.props)",
+                csproj_path
+            )
+        })?;
+
+        let mut frameworks = Vec::new();
+        for raw in raw_frameworks {
+            frameworks.push(TargetFramework::from_base(Self::normalize(&raw)?));
+        }
+
+        debug!(
+            "TargetFramework(s) for {:?}: {:?}",
+            csproj_path, frameworks
+        );
+        Ok(frameworks)
+    }
+
+    /// Split `MsBuildProperties` into the raw (un-normalized) list of TFM
+    /// strings it declares: every entry of `TargetFrameworks` if present,
+    /// otherwise the single `TargetFramework`/`TargetFrameworkVersion`.
+    /// Returns `None` if none of those properties are set.
+    fn split_target_frameworks(properties: &MsBuildProperties) -> Option<Vec<String>> {
+        if let Some(plural) = &properties.target_frameworks {
+            Some(
+                plural
+                    .split(';')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect(),
+            )
+        } else {
+            properties
+                .target_framework
+                .clone()
+                .or_else(|| properties.target_framework_version.clone())
+                .map(|single| vec![single])
+        }
+    }
+
+    /// Construct a `TargetFramework` with no platform suffix from an
+    /// already-normalized base TFM string.
+    fn from_base(base: String) -> Self {
+        Self {
+            base,
+            platform: None,
+            platform_version: None,
+        }
+    }
+
+    /// Create a TargetFramework from a string, normalizing it. Parses an
+    /// optional OS-specific platform suffix (e.g. `net8.0-android`,
+    /// `net8.0-windows10.0.19041`) into the `platform`/`platform_version`
+    /// fields, validating the platform name against [`KNOWN_PLATFORMS`].
+    /// Falls through to [`Self::normalize`] (which is also used by the
+    /// csproj-parsing paths and stays intentionally permissive about any
+    /// `-`-suffix, since those paths only need the base TFM for SDK/pack
+    /// lookups) for TFMs with no platform suffix.
+    #[allow(dead_code)]
+    pub(crate) fn from_str(tfm: &str) -> Result<Self, Error> {
+        let trimmed = tfm.trim();
+
+        if let Some(dash_pos) = trimmed.find('-') {
+            let base = &trimmed[..dash_pos];
+            if Self::is_valid_base_tfm(base) {
+                let suffix = &trimmed[dash_pos + 1..];
+                let split_at = suffix
+                    .find(|c: char| c.is_ascii_digit())
+                    .unwrap_or(suffix.len());
+                let (platform, platform_version) = suffix.split_at(split_at);
+
+                if !KNOWN_PLATFORMS.contains(&platform) {
                     return Err(anyhow!(
-                        "XML parsing error at position {}: {}",
-                        reader.buffer_position(),
-                        e
+                        "Unrecognized platform '{}' in platform-specific TFM '{}'",
+                        platform,
+                        trimmed
                     ));
                 }
-                _ => {}
+
+                return Ok(Self {
+                    base: base.to_string(),
+                    platform: Some(platform.to_string()),
+                    platform_version: if platform_version.is_empty() {
+                        None
+                    } else {
+                        Some(platform_version.to_string())
+                    },
+                });
             }
-            buf.clear();
         }
 
-        // Prefer TargetFramework over TargetFrameworkVersion
-        let framework = target_framework
-            .or(target_framework_version)
-            .ok_or_else(|| {
-                anyhow!(
-                    "No TargetFramework or TargetFrameworkVersion found in {:?}",
-                    csproj_path
-                )
-            })?;
+        let normalized = Self::normalize(trimmed)?;
+        Ok(Self::from_base(normalized))
+    }
 
-        // Normalize the framework string
-        let normalized = Self::normalize(&framework)?;
+    /// Get the underlying base TFM string (without any platform suffix).
+    #[allow(dead_code)]
+    pub(crate) fn as_str(&self) -> &str {
+        &self.base
+    }
 
-        debug!("TargetFramework for {:?}: {}", csproj_path, normalized);
-        Ok(TargetFramework(normalized))
+    /// Alias for [`Self::as_str`] that reads more clearly at call sites that
+    /// care specifically about distinguishing the base TFM from the
+    /// platform suffix.
+    #[allow(dead_code)]
+    pub(crate) fn base_tfm(&self) -> &str {
+        &self.base
     }
 
-    /// Create a TargetFramework from a string, normalizing it
+    /// The OS-specific platform this TFM targets (e.g. `"android"`), if any.
     #[allow(dead_code)]
-    pub(crate) fn from_str(tfm: &str) -> Result<Self, Error> {
-        let normalized = Self::normalize(tfm)?;
-        Ok(TargetFramework(normalized))
+    pub(crate) fn platform(&self) -> Option<&str> {
+        self.platform.as_deref()
     }
 
-    /// Get the underlying TFM string
+    /// The platform version suffix (e.g. `"17.2"`, `"10.0.19041"`), if the
+    /// platform suffix included one.
     #[allow(dead_code)]
-    pub(crate) fn as_str(&self) -> &str {
-        &self.0
+    pub(crate) fn platform_version(&self) -> Option<&str> {
+        self.platform_version.as_deref()
+    }
+
+    /// Decompose a normalized TFM into a semantic sort key of
+    /// `(family rank, major, minor, patch)`: .NET Framework (net4x) < .NET
+    /// Standard < .NET Core (netcoreapp) < modern .NET (net5.0+), and
+    /// numeric comparison of version components within a family (so
+    /// net9.0 < net10.0, net472 < net48). This is the same version-component
+    /// decomposition tools like Paket use to rank TFMs.
+    fn version_key(&self) -> (u8, u32, u32, u32) {
+        let tfm = self.base.as_str();
+
+        if let Some(version_part) = tfm.strip_prefix("netstandard") {
+            let (major, minor) = Self::parse_major_minor(version_part).unwrap_or((0, 0));
+            return (1, major, minor, 0);
+        }
+
+        if let Some(version_part) = tfm.strip_prefix("netcoreapp") {
+            let (major, minor) = Self::parse_major_minor(version_part).unwrap_or((0, 0));
+            return (2, major, minor, 0);
+        }
+
+        if tfm.starts_with("net") && tfm.contains('.') {
+            let version_part = &tfm[3..];
+            let (major, minor) = Self::parse_major_minor(version_part).unwrap_or((0, 0));
+            return (3, major, minor, 0);
+        }
+
+        if let Some(version_part) = tfm.strip_prefix("net") {
+            // Old-style .NET Framework: net48 -> 4, 8, 0; net472 -> 4, 7, 2
+            let digits: Vec<u32> = version_part
+                .chars()
+                .filter_map(|c| c.to_digit(10))
+                .collect();
+            let major = digits.first().copied().unwrap_or(0);
+            let minor = digits.get(1).copied().unwrap_or(0);
+            let patch = digits.get(2).copied().unwrap_or(0);
+            return (0, major, minor, patch);
+        }
+
+        (0, 0, 0, 0)
+    }
+
+    /// Parse a `"X.Y"`-shaped version fragment into `(major, minor)`.
+    fn parse_major_minor(version_part: &str) -> Option<(u32, u32)> {
+        let (major_str, minor_str) = version_part.split_once('.')?;
+        let major = major_str.parse().ok()?;
+        let minor = minor_str.parse().ok()?;
+        Some((major, minor))
+    }
+
+    /// Every concrete TFM this module knows about, across every family --
+    /// used by dependency-restriction resolution to expand a constraint
+    /// like `>= net45` into the concrete list of monikers it allows.
+    pub(crate) fn all_known() -> Vec<TargetFramework> {
+        let mut all = Vec::new();
+        for &(major, minor, patch) in NET_FRAMEWORK_VERSIONS {
+            all.push(TargetFramework::from_base(Self::format_net_framework(
+                major, minor, patch,
+            )));
+        }
+        for &(major, minor) in NETSTANDARD_VERSIONS {
+            all.push(TargetFramework::from_base(format!(
+                "netstandard{}.{}",
+                major, minor
+            )));
+        }
+        for &(major, minor) in NETCOREAPP_VERSIONS {
+            all.push(TargetFramework::from_base(format!(
+                "netcoreapp{}.{}",
+                major, minor
+            )));
+        }
+        for &major in MODERN_NET_MAJOR_VERSIONS {
+            all.push(TargetFramework::from_base(format!("net{}.0", major)));
+        }
+        all
+    }
+
+    /// Which [`TfmFamily`] this TFM belongs to.
+    pub(crate) fn family(&self) -> TfmFamily {
+        match self.version_key().0 {
+            1 => TfmFamily::NetStandard,
+            2 => TfmFamily::NetCore,
+            3 => TfmFamily::ModernNet,
+            _ => TfmFamily::NetFramework,
+        }
+    }
+
+    /// Whether this is a modern .NET TFM (`net5.0` and above).
+    pub(crate) fn is_modern_net(&self) -> bool {
+        self.family() == TfmFamily::ModernNet
+    }
+
+    /// Whether this is a .NET Framework TFM (`net45`..`net481`).
+    pub(crate) fn is_net_framework(&self) -> bool {
+        self.family() == TfmFamily::NetFramework
+    }
+
+    /// Whether this is a `netcoreapp*` TFM.
+    pub(crate) fn is_net_core(&self) -> bool {
+        self.family() == TfmFamily::NetCore
+    }
+
+    /// Whether this is a `netstandard*` TFM.
+    pub(crate) fn is_net_standard(&self) -> bool {
+        self.family() == TfmFamily::NetStandard
+    }
+
+    /// Whether `self`'s version is at least `other`'s, within the same TFM
+    /// family. Returns `None` when the two are in incomparable families
+    /// (e.g. `netstandard2.0` vs `net48`), since "at least" isn't a
+    /// meaningful question across families -- unlike [`Self::cmp`], which
+    /// orders every TFM by family rank so it can be used as a total `Ord`.
+    pub(crate) fn is_at_least(&self, other: &TargetFramework) -> Option<bool> {
+        let (self_family, _, _, _) = self.version_key();
+        let (other_family, _, _, _) = other.version_key();
+        if self_family != other_family {
+            return None;
+        }
+
+        let self_version = self.to_channel().ok()?;
+        let other_version = other.to_channel().ok()?;
+        Some(Self::parse_version_components(&self_version) >= Self::parse_version_components(&other_version))
+    }
+
+    /// Parse a dotted version string (as returned by [`Self::to_channel`])
+    /// into a comparable vector of numeric components.
+    fn parse_version_components(version: &str) -> Vec<u32> {
+        version.split('.').filter_map(|part| part.parse().ok()).collect()
+    }
+
+    /// Whether a package/assembly built for `other` can be consumed by a
+    /// project targeting `self`, i.e. `other` appears somewhere in `self`'s
+    /// [`Self::fallback_chain`].
+    pub(crate) fn is_compatible_with(&self, other: &TargetFramework) -> bool {
+        self.fallback_chain().contains(other)
+    }
+
+    /// The priority-ordered list of frameworks this TFM can fall back to
+    /// when consuming a package/assembly, mirroring the .NET TFM
+    /// compatibility graph (nearest-first, starting with `self`):
+    /// https://learn.microsoft.com/en-us/dotnet/standard/frameworks#most-compatible-frameworks
+    ///
+    /// - Modern .NET (`netX.Y`, X>=5) falls back through lower modern
+    ///   versions down to `net5.0`, then every `netcoreapp`, then every
+    ///   `netstandard`.
+    /// - `netcoreappX.Y` falls back through lower `netcoreapp` versions,
+    ///   then every `netstandard`.
+    /// - `netstandardX.Y` falls back only through lower `netstandard`
+    ///   versions.
+    /// - .NET Framework (`net4x`) falls back through lower `net4x`
+    ///   versions, then `netstandard` up to 2.0 (the highest version .NET
+    ///   Framework can consume).
+    pub(crate) fn fallback_chain(&self) -> Vec<TargetFramework> {
+        let (family, major, minor, patch) = self.version_key();
+        let mut chain = vec![self.clone()];
+
+        match family {
+            3 => {
+                for m in (5..major).rev() {
+                    chain.push(TargetFramework::from_base(format!("net{}.0", m)));
+                }
+                for &(maj, min) in NETCOREAPP_VERSIONS.iter().rev() {
+                    chain.push(TargetFramework::from_base(format!(
+                        "netcoreapp{}.{}",
+                        maj, min
+                    )));
+                }
+                for &(maj, min) in NETSTANDARD_VERSIONS.iter().rev() {
+                    chain.push(TargetFramework::from_base(format!(
+                        "netstandard{}.{}",
+                        maj, min
+                    )));
+                }
+            }
+            2 => {
+                for &(maj, min) in NETCOREAPP_VERSIONS.iter().rev() {
+                    if (maj, min) < (major, minor) {
+                        chain.push(TargetFramework::from_base(format!(
+                            "netcoreapp{}.{}",
+                            maj, min
+                        )));
+                    }
+                }
+                for &(maj, min) in NETSTANDARD_VERSIONS.iter().rev() {
+                    chain.push(TargetFramework::from_base(format!(
+                        "netstandard{}.{}",
+                        maj, min
+                    )));
+                }
+            }
+            1 => {
+                for &(maj, min) in NETSTANDARD_VERSIONS.iter().rev() {
+                    if (maj, min) < (major, minor) {
+                        chain.push(TargetFramework::from_base(format!(
+                            "netstandard{}.{}",
+                            maj, min
+                        )));
+                    }
+                }
+            }
+            _ => {
+                for &(maj, min, pat) in NET_FRAMEWORK_VERSIONS.iter().rev() {
+                    if (maj, min, pat) < (major, minor, patch) {
+                        chain.push(TargetFramework::from_base(Self::format_net_framework(
+                            maj, min, pat,
+                        )));
+                    }
+                }
+                for &(maj, min) in NETSTANDARD_VERSIONS.iter().rev() {
+                    if (maj, min) <= (2, 0) {
+                        chain.push(TargetFramework::from_base(format!(
+                            "netstandard{}.{}",
+                            maj, min
+                        )));
+                    }
+                }
+            }
+        }
+
+        chain
+    }
+
+    /// Render a .NET Framework `(major, minor, patch)` triple back into its
+    /// short-form moniker, e.g. `(4, 7, 2)` -> `"net472"`, `(4, 5, 0)` -> `"net45"`.
+    fn format_net_framework(major: u32, minor: u32, patch: u32) -> String {
+        if patch == 0 {
+            format!("net{}{}", major, minor)
+        } else {
+            format!("net{}{}{}", major, minor, patch)
+        }
     }
 
     /// Convert TFM to dotnet-install channel format
@@ -125,7 +689,7 @@ impl TargetFramework {
     /// - "netcoreapp3.1" -> "3.1"
     /// - "net48" -> "4.8" (for .NET Framework, though install script may not support)
     pub(crate) fn to_channel(&self) -> Result<String, Error> {
-        let tfm = &self.0;
+        let tfm = &self.base;
 
         // Modern .NET (net5.0+)
         if tfm.starts_with("net")
@@ -172,18 +736,60 @@ impl TargetFramework {
         ))
     }
 
+    /// Resolve what to request from the SDK installer for this target
+    /// framework: a `global.json` pin found while walking up from
+    /// `project_root` (or the current directory, if not given) always wins
+    /// over the TFM-derived channel, so a repo that pins e.g. `8.0.404`
+    /// installs exactly that instead of latest-of-8.0.
+    pub(crate) fn resolve_sdk_request(&self, project_root: Option<&Path>) -> Result<SdkRequest, Error> {
+        let start_dir = match project_root {
+            Some(path) => path.to_path_buf(),
+            None => std::env::current_dir()
+                .map_err(|e| anyhow!("Could not determine current directory: {}", e))?,
+        };
+
+        if let Some(pin) = SdkPin::find(&start_dir) {
+            info!(
+                "Using global.json SDK pin {} for TargetFramework {}",
+                pin.version, self.base
+            );
+            return Ok(SdkRequest::ExactVersion(pin.version));
+        }
+
+        Ok(SdkRequest::Channel(self.to_channel()?))
+    }
+
     /// Install the .NET SDK for this target framework
     /// Returns the path to the installed SDK directory
-    pub(crate) fn install_sdk(&self, dotnet_install_script: &PathBuf) -> Result<PathBuf, Error> {
-        info!("install_sdk() called for target framework: {}", self.0);
+    ///
+    /// Probes already-installed SDKs first and short-circuits if one already
+    /// satisfies the requested channel, so this doesn't shell out to
+    /// dotnet-install (slow, and wasteful in CI) when it doesn't need to. If
+    /// a `global.json` found while walking up from `project_root` pins an
+    /// SDK version, that exact version is requested instead of the
+    /// TFM-derived channel.
+    pub(crate) fn install_sdk(
+        &self,
+        dotnet_install_script: &PathBuf,
+        project_root: Option<&Path>,
+    ) -> Result<PathBuf, Error> {
+        info!("install_sdk() called for target framework: {}", self.base);
         info!("Script path: {:?}, exists: {}", dotnet_install_script, dotnet_install_script.exists());
 
-        // Convert TFM to channel format for dotnet-install script
-        let channel = self.to_channel()?;
-        info!("Converted TFM {} to channel {}", self.0, channel);
+        let sdk_request = self.resolve_sdk_request(project_root)?;
+        info!("Resolved SDK request for TFM {}: {:?}", self.base, sdk_request);
+
+        let installed = InstalledEnvironment::discover();
+        if let Some(root) = installed.root_satisfying(&sdk_request) {
+            info!(
+                "Found already-installed SDK satisfying {:?}, skipping dotnet-install: {:?}",
+                sdk_request, root
+            );
+            return Ok(root);
+        }
 
         // Determine the installation directory
-        let install_dir = std::env::temp_dir().join("dotnet-sdks").join(&self.0);
+        let install_dir = std::env::temp_dir().join("dotnet-sdks").join(&self.base);
         info!("Target installation directory: {:?}", install_dir);
         std::fs::create_dir_all(&install_dir)?;
         info!("Created/verified installation directory exists");
@@ -194,42 +800,191 @@ impl TargetFramework {
         );
 
         // Run the installation script
-        info!("Running dotnet-install for channel {} to {:?}", channel, install_dir);
+        info!("Running dotnet-install for {:?} to {:?}", sdk_request, install_dir);
         let output = if cfg!(windows) {
-            Command::new("powershell")
+            let mut command = Command::new("powershell");
+            command
                 .arg("-ExecutionPolicy")
                 .arg("Bypass")
                 .arg("-File")
                 .arg(dotnet_install_script)
                 .arg("-InstallDir")
-                .arg(&install_dir)
-                .arg("-Channel")
-                .arg(&channel)
-                .output()?
+                .arg(&install_dir);
+            match &sdk_request {
+                SdkRequest::Channel(channel) => command.arg("-Channel").arg(channel),
+                SdkRequest::ExactVersion(version) => command.arg("-Version").arg(version),
+            };
+            command.output()?
         } else {
-            Command::new(dotnet_install_script)
-                .arg("--install-dir")
-                .arg(&install_dir)
-                .arg("--channel")
-                .arg(&channel)
-                .output()?
+            let mut command = Command::new(dotnet_install_script);
+            command.arg("--install-dir").arg(&install_dir);
+            match &sdk_request {
+                SdkRequest::Channel(channel) => command.arg("--channel").arg(channel),
+                SdkRequest::ExactVersion(version) => command.arg("--version").arg(version),
+            };
+            command.output()?
         };
 
-        info!("dotnet-install script completed with status: {:?}", output.status);
-        info!("Script stdout: {}", String::from_utf8_lossy(&output.stdout));
-        info!("Script stderr: {}", String::from_utf8_lossy(&output.stderr));
+        info!("dotnet-install script completed with status: {:?}", output.status);
+        info!("Script stdout: {}", String::from_utf8_lossy(&output.stdout));
+        info!("Script stderr: {}", String::from_utf8_lossy(&output.stderr));
+
+        if !output.status.success() {
+            error!(
+                "dotnet-install script failed with status {:?}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr)
+            );
+            return Err(anyhow!("Failed to install .NET SDK for {}: {}", self.base, String::from_utf8_lossy(&output.stderr)));
+        }
+
+        info!("Successfully installed .NET SDK to {:?}", install_dir);
+        Ok(install_dir)
+    }
+
+    /// Heuristically detect the effective target framework(s) for a project
+    /// file the way build tooling does: read `<TargetFramework>` /
+    /// `<TargetFrameworks>` / `<TargetFrameworkVersion>` from the project
+    /// file itself and, when the project defines none of them, take all three
+    /// from the nearest `Directory.Build.props`, `Directory.Build.targets` or
+    /// `Packages.props` that defines one (MSBuild property inheritance); the
+    /// properties are not merged one by one. Returns every
+    /// targeted framework for multi-targeted (`<TargetFrameworks>`) projects.
+    pub(crate) fn detect_from_project(project_path: &PathBuf) -> Result<Vec<Self>, Error> {
+        let mut properties = Self::parse_msbuild_properties(project_path)?;
+
+        if properties.target_framework.is_none()
+            && properties.target_frameworks.is_none()
+            && properties.target_framework_version.is_none()
+        {
+            if let Some(inherited) = Self::find_inherited_properties(project_path) {
+                debug!(
+                    "No TargetFramework in {:?}, inheriting from Directory.Build.props/.targets or Packages.props",
+                    project_path
+                );
+                properties = inherited;
+            }
+        }
+
+        let raw_frameworks = Self::split_target_frameworks(&properties).ok_or_else(|| {
+            anyhow!(
+                "No TargetFramework, TargetFrameworks, or TargetFrameworkVersion found for {:?} (including inherited Directory.Build.props/.targets or Packages.props)",
+                project_path
+            )
+        })?;
+
+        let mut frameworks = Vec::new();
+        for raw in raw_frameworks {
+            frameworks.push(TargetFramework::from_base(Self::normalize(&raw)?));
+        }
+
+        debug!(
+            "Detected target framework(s) for {:?}: {:?}",
+            project_path, frameworks
+        );
+        Ok(frameworks)
+    }
+
+    /// Parse the MSBuild properties this module cares about out of any
+    /// project or props file (`.csproj`, `.fsproj`, `Directory.Build.props`,
+    /// `Packages.props`).
+    fn parse_msbuild_properties(path: &PathBuf) -> Result<MsBuildProperties, Error> {
+        let file = File::open(path)
+            .map_err(|e| anyhow!("Failed to open project/props file {:?}: {}", path, e))?;
+        let buf_reader = BufReader::new(file);
+        let mut reader = Reader::from_reader(buf_reader);
+        reader.config_mut().trim_text(true);
+
+        let mut buf = Vec::new();
+        let mut in_property_group = false;
+        let mut properties = MsBuildProperties::default();
+
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                    let name = e.name();
+                    match name.as_ref() {
+                        b"PropertyGroup" => in_property_group = true,
+                        b"TargetFramework" if in_property_group => {
+                            if let Ok(Event::Text(text)) = reader.read_event_into(&mut buf) {
+                                let value = String::from_utf8_lossy(&text).trim().to_string();
+                                if !value.is_empty() {
+                                    properties.target_framework = Some(value);
+                                }
+                            }
+                        }
+                        b"TargetFrameworks" if in_property_group => {
+                            if let Ok(Event::Text(text)) = reader.read_event_into(&mut buf) {
+                                let value = String::from_utf8_lossy(&text).trim().to_string();
+                                if !value.is_empty() {
+                                    properties.target_frameworks = Some(value);
+                                }
+                            }
+                        }
+                        b"TargetFrameworkVersion" if in_property_group => {
+                            if let Ok(Event::Text(text)) = reader.read_event_into(&mut buf) {
+                                let value = String::from_utf8_lossy(&text).trim().to_string();
+                                if !value.is_empty() {
+                                    properties.target_framework_version = Some(value);
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                Ok(Event::End(e)) => {
+                    if e.name().as_ref() == b"PropertyGroup" {
+                        in_property_group = false;
+                    }
+                }
+                Ok(Event::Eof) => break,
+                Err(e) => {
+                    return Err(anyhow!(
+                        "XML parsing error in {:?} at position {}: {}",
+                        path,
+                        reader.buffer_position(),
+                        e
+                    ));
+                }
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        Ok(properties)
+    }
 
-        if !output.status.success() {
-            error!(
-                "dotnet-install script failed with status {:?}: {}",
-                output.status,
-                String::from_utf8_lossy(&output.stderr)
-            );
-            return Err(anyhow!("Failed to install .NET SDK for {}: {}", self.0, String::from_utf8_lossy(&output.stderr)));
+    /// Walk up from a project file's directory looking for the nearest
+    /// `Directory.Build.props`, `Directory.Build.targets`, or
+    /// `Packages.props` that actually defines a TargetFramework-related
+    /// property, the same way MSBuild implicitly imports
+    /// `Directory.Build.props`/`.targets` from ancestor directories.
+    fn find_inherited_properties(project_path: &Path) -> Option<MsBuildProperties> {
+        let mut dir = project_path.parent();
+        while let Some(current) = dir {
+            for name in [
+                "Directory.Build.props",
+                "Directory.Build.targets",
+                "Packages.props",
+            ] {
+                let candidate = current.join(name);
+                if candidate.is_file() {
+                    match Self::parse_msbuild_properties(&candidate) {
+                        Ok(props)
+                            if props.target_framework.is_some()
+                                || props.target_frameworks.is_some()
+                                || props.target_framework_version.is_some() =>
+                        {
+                            return Some(props);
+                        }
+                        Ok(_) => {}
+                        Err(e) => debug!("Failed to parse {:?}: {}", candidate, e),
+                    }
+                }
+            }
+            dir = current.parent();
         }
-
-        info!("Successfully installed .NET SDK to {:?}", install_dir);
-        Ok(install_dir)
+        None
     }
 
     /// Normalize a Target Framework Moniker (TFM) to a standard format
@@ -350,6 +1105,55 @@ impl TargetFramework {
     }
 }
 
+/// Roll-forward policy for selecting a shared-framework (runtime) version
+/// when no exact TFM match is installed. Kept separate from the reference
+/// pack's `TfmRollForwardPolicy` in `sdk_detection` since the two resolve
+/// different directory layouts (`shared/<framework>/<version>` here vs
+/// `packs/<pack>.Ref/<version>/ref/<tfm>` there).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FrameworkRollForwardPolicy {
+    /// Same major, lowest minor >= requested.
+    Minor,
+    /// Same major, highest minor.
+    LatestMinor,
+    /// Exact major.minor, highest patch.
+    LatestPatch,
+}
+
+impl Default for FrameworkRollForwardPolicy {
+    fn default() -> Self {
+        Self::Minor
+    }
+}
+
+/// Parse the (major, minor) out of a modern "netX.Y" TFM base, e.g.
+/// `net8.0` -> `(8, 0)`. Returns `None` for monikers that aren't eligible
+/// for roll-forward (`net48`, `netstandard2.0`, platform-suffixed TFMs, etc).
+fn parse_net_major_minor(tfm: &str) -> Option<(u32, u32)> {
+    let rest = tfm.strip_prefix("net")?;
+    if rest.contains('-') {
+        return None;
+    }
+    let mut parts = rest.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((major, minor))
+}
+
+/// Parse an installed shared-framework version directory name (e.g.
+/// `8.0.1` or `9.0.0-preview.1`) into (major, minor, patch).
+fn parse_framework_version(version: &str) -> Option<(u32, u32, u32)> {
+    let numeric = version.split('-').next()?;
+    let mut parts = numeric.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    let patch = parts.next()?.parse().ok()?;
+    Some((major, minor, patch))
+}
+
 /// Helper functions for working with multiple TFMs
 pub(crate) struct TargetFrameworkHelper;
 
@@ -380,7 +1184,79 @@ impl TargetFrameworkHelper {
         Ok(csproj_files)
     }
 
-    /// Get the earliest target framework version from all .csproj files in a directory
+    /// Find all .csproj and .fsproj files in a directory and subdirectories
+    pub(crate) fn find_project_files(location: &PathBuf) -> Result<Vec<PathBuf>, Error> {
+        let mut project_files = Vec::new();
+
+        for entry in WalkDir::new(location)
+            .follow_links(false)
+            .into_iter()
+            .filter_map(|e| e.ok())
+        {
+            if entry.file_type().is_file() {
+                if let Some(extension) = entry.path().extension() {
+                    if extension == "csproj" || extension == "fsproj" {
+                        project_files.push(entry.path().to_path_buf());
+                    }
+                }
+            }
+        }
+
+        info!(
+            "Found {} project file(s) in {:?}",
+            project_files.len(),
+            location
+        );
+        Ok(project_files)
+    }
+
+    /// Heuristically detect the target framework(s) used across every
+    /// project file found under `location`, so analysis can proceed without
+    /// the user hand-specifying a TFM.
+    pub(crate) fn detect_from_directory(location: &PathBuf) -> Result<Vec<TargetFramework>, Error> {
+        let project_files = Self::find_project_files(location)?;
+
+        if project_files.is_empty() {
+            return Err(anyhow!("No .csproj or .fsproj files found in {:?}", location));
+        }
+
+        let mut frameworks = Vec::new();
+        for project in &project_files {
+            match TargetFramework::detect_from_project(project) {
+                Ok(detected) => {
+                    for framework in detected {
+                        if !frameworks.contains(&framework) {
+                            frameworks.push(framework);
+                        }
+                    }
+                }
+                Err(e) => {
+                    debug!("Failed to detect target framework for {:?}: {}", project, e);
+                    // Continue processing other projects
+                }
+            }
+        }
+
+        if frameworks.is_empty() {
+            return Err(anyhow!(
+                "Could not determine target framework from any project file in {:?}",
+                location
+            ));
+        }
+
+        frameworks.sort();
+        info!(
+            "Detected {} distinct target framework(s) in {:?}: {:?}",
+            frameworks.len(),
+            location,
+            frameworks
+        );
+        Ok(frameworks)
+    }
+
+    /// Get the earliest target framework version across every framework
+    /// targeted by any .csproj file in a directory, including each targeted
+    /// framework of multi-targeted (`<TargetFrameworks>`) projects.
     pub(crate) fn get_earliest_from_directory(
         location: &PathBuf,
     ) -> Result<TargetFramework, Error> {
@@ -392,8 +1268,8 @@ impl TargetFrameworkHelper {
 
         let mut frameworks = Vec::new();
         for csproj in &csproj_files {
-            match TargetFramework::from_csproj(csproj) {
-                Ok(framework) => frameworks.push(framework),
+            match TargetFramework::all_from_csproj(csproj) {
+                Ok(detected) => frameworks.extend(detected),
                 Err(e) => {
                     debug!("Failed to get target framework for {:?}: {}", csproj, e);
                     // Continue processing other projects
@@ -407,7 +1283,7 @@ impl TargetFrameworkHelper {
             ));
         }
 
-        // Sort to get the earliest version (lexicographically smallest)
+        // Sort to get the earliest version (semantic ordering, not lexicographic)
         frameworks.sort();
         let earliest = frameworks.into_iter().next().unwrap();
 
@@ -415,6 +1291,84 @@ impl TargetFrameworkHelper {
         Ok(earliest)
     }
 
+    /// Resolve the shared-framework directory (e.g.
+    /// `shared/Microsoft.NETCore.App/<version>`) that best satisfies `tfm`
+    /// under `policy`. Mirrors the real host's framework resolution: after
+    /// picking the best candidate version, validates that
+    /// `<framework_name>.deps.json` actually exists in that directory,
+    /// discarding and re-resolving against the remaining candidates if it's
+    /// missing ("Ignoring FX version without .deps.json").
+    pub(crate) fn resolve_framework_dir(
+        sdk_path: &Path,
+        framework_name: &str,
+        tfm: &TargetFramework,
+        policy: FrameworkRollForwardPolicy,
+    ) -> Result<PathBuf, Error> {
+        let framework_dir = sdk_path.join("shared").join(framework_name);
+        let (req_major, req_minor) = parse_net_major_minor(tfm.as_str()).ok_or_else(|| {
+            anyhow!(
+                "TFM {} isn't a roll-forward-eligible moniker for framework resolution",
+                tfm.as_str()
+            )
+        })?;
+
+        let mut candidates: Vec<(u32, u32, u32, String)> = Self::list_version_dirs(&framework_dir)
+            .into_iter()
+            .filter_map(|version| {
+                parse_framework_version(&version).map(|(major, minor, patch)| (major, minor, patch, version))
+            })
+            .filter(|(major, minor, _, _)| match policy {
+                FrameworkRollForwardPolicy::Minor | FrameworkRollForwardPolicy::LatestMinor => {
+                    *major == req_major && *minor >= req_minor
+                }
+                FrameworkRollForwardPolicy::LatestPatch => *major == req_major && *minor == req_minor,
+            })
+            .collect();
+
+        candidates.sort_by_key(|(major, minor, patch, _)| (*major, *minor, *patch));
+        if matches!(
+            policy,
+            FrameworkRollForwardPolicy::LatestMinor | FrameworkRollForwardPolicy::LatestPatch
+        ) {
+            candidates.reverse();
+        }
+
+        for (_, _, _, version) in candidates {
+            let dir = framework_dir.join(&version);
+            let deps_json = dir.join(format!("{}.deps.json", framework_name));
+            if deps_json.is_file() {
+                return Ok(dir);
+            }
+            debug!(
+                "Ignoring FX version {} for {} -- missing {:?}",
+                version, framework_name, deps_json
+            );
+        }
+
+        Err(anyhow!(
+            "No installed {} version satisfies TFM {} with a valid .deps.json under {:?}",
+            framework_name,
+            tfm.as_str(),
+            framework_dir
+        ))
+    }
+
+    /// List the installed version directory names under a shared-framework
+    /// directory (e.g. `shared/Microsoft.NETCore.App`).
+    fn list_version_dirs(framework_dir: &Path) -> Vec<String> {
+        match std::fs::read_dir(framework_dir) {
+            Ok(entries) => entries
+                .filter_map(|e| e.ok())
+                .filter(|e| e.path().is_dir())
+                .filter_map(|e| e.file_name().to_str().map(|s| s.to_string()))
+                .collect(),
+            Err(e) => {
+                debug!("no installed versions under {:?}: {}", framework_dir, e);
+                vec![]
+            }
+        }
+    }
+
     /// Find and collect XML documentation files from the SDK reference packs
     /// Looks in SDK_PATH/packs/ for:
     /// - Microsoft.NETCore.App.Ref/<version>/ref/<tfm>
@@ -734,6 +1688,169 @@ mod tests {
         assert!(TargetFramework::from_str("net").is_err());
     }
 
+    #[test]
+    fn test_from_str_parses_platform_suffix() {
+        let tfm = TargetFramework::from_str("net8.0-android").unwrap();
+        assert_eq!(tfm.as_str(), "net8.0");
+        assert_eq!(tfm.platform(), Some("android"));
+        assert_eq!(tfm.platform_version(), None);
+    }
+
+    #[test]
+    fn test_from_str_parses_platform_version() {
+        let tfm = TargetFramework::from_str("net8.0-ios17.2").unwrap();
+        assert_eq!(tfm.base_tfm(), "net8.0");
+        assert_eq!(tfm.platform(), Some("ios"));
+        assert_eq!(tfm.platform_version(), Some("17.2"));
+
+        let tfm = TargetFramework::from_str("net8.0-windows10.0.19041").unwrap();
+        assert_eq!(tfm.platform(), Some("windows"));
+        assert_eq!(tfm.platform_version(), Some("10.0.19041"));
+    }
+
+    #[test]
+    fn test_from_str_rejects_unknown_platform() {
+        assert!(TargetFramework::from_str("net8.0-linux").is_err());
+    }
+
+    #[test]
+    fn test_from_str_no_platform_suffix_has_none() {
+        let tfm = TargetFramework::from_str("net8.0").unwrap();
+        assert_eq!(tfm.platform(), None);
+        assert_eq!(tfm.platform_version(), None);
+    }
+
+    #[test]
+    fn test_fallback_chain_modern_net_includes_netcoreapp_and_netstandard() {
+        let net8 = TargetFramework::from_str("net8.0").unwrap();
+        let chain: Vec<String> = net8
+            .fallback_chain()
+            .iter()
+            .map(|f| f.as_str().to_string())
+            .collect();
+
+        assert_eq!(chain[0], "net8.0");
+        assert!(chain.contains(&"net7.0".to_string()));
+        assert!(chain.contains(&"net5.0".to_string()));
+        assert!(chain.contains(&"netcoreapp3.1".to_string()));
+        assert!(chain.contains(&"netstandard2.1".to_string()));
+        assert!(!chain.contains(&"net9.0".to_string()));
+    }
+
+    #[test]
+    fn test_fallback_chain_netstandard_only_includes_lower_netstandard() {
+        let netstandard20 = TargetFramework::from_str("netstandard2.0").unwrap();
+        let chain: Vec<String> = netstandard20
+            .fallback_chain()
+            .iter()
+            .map(|f| f.as_str().to_string())
+            .collect();
+
+        assert_eq!(chain, vec!["netstandard2.0", "netstandard1.6", "netstandard1.5", "netstandard1.4", "netstandard1.3", "netstandard1.2", "netstandard1.1", "netstandard1.0"]);
+    }
+
+    #[test]
+    fn test_fallback_chain_net_framework_includes_netstandard_up_to_2_0() {
+        let net472 = TargetFramework::from_str("net472").unwrap();
+        let chain: Vec<String> = net472
+            .fallback_chain()
+            .iter()
+            .map(|f| f.as_str().to_string())
+            .collect();
+
+        assert!(chain.contains(&"net471".to_string()));
+        assert!(chain.contains(&"net45".to_string()));
+        assert!(chain.contains(&"netstandard2.0".to_string()));
+        assert!(!chain.contains(&"netstandard2.1".to_string()));
+        assert!(!chain.contains(&"net48".to_string()));
+    }
+
+    #[test]
+    fn test_is_compatible_with_package_tfm_in_fallback_chain() {
+        let project = TargetFramework::from_str("net8.0").unwrap();
+        let package = TargetFramework::from_str("netstandard2.0").unwrap();
+        assert!(project.is_compatible_with(&package));
+
+        let incompatible_package = TargetFramework::from_str("net9.0").unwrap();
+        assert!(!project.is_compatible_with(&incompatible_package));
+    }
+
+    #[test]
+    fn test_is_at_least_within_same_family() {
+        let net8 = TargetFramework::from_str("net8.0").unwrap();
+        let net6 = TargetFramework::from_str("net6.0").unwrap();
+        assert_eq!(net8.is_at_least(&net6), Some(true));
+        assert_eq!(net6.is_at_least(&net8), Some(false));
+        assert_eq!(net8.is_at_least(&net8), Some(true));
+    }
+
+    #[test]
+    fn test_is_at_least_incomparable_families_returns_none() {
+        let netstandard20 = TargetFramework::from_str("netstandard2.0").unwrap();
+        let net48 = TargetFramework::from_str("net48").unwrap();
+        assert_eq!(netstandard20.is_at_least(&net48), None);
+    }
+
+    #[test]
+    fn test_family_classification() {
+        assert_eq!(
+            TargetFramework::from_str("net8.0").unwrap().family(),
+            TfmFamily::ModernNet
+        );
+        assert_eq!(
+            TargetFramework::from_str("netcoreapp3.1").unwrap().family(),
+            TfmFamily::NetCore
+        );
+        assert_eq!(
+            TargetFramework::from_str("netstandard2.0").unwrap().family(),
+            TfmFamily::NetStandard
+        );
+        assert_eq!(
+            TargetFramework::from_str("net48").unwrap().family(),
+            TfmFamily::NetFramework
+        );
+    }
+
+    #[test]
+    fn test_family_predicates() {
+        let net8 = TargetFramework::from_str("net8.0").unwrap();
+        assert!(net8.is_modern_net());
+        assert!(!net8.is_net_framework());
+        assert!(!net8.is_net_core());
+        assert!(!net8.is_net_standard());
+
+        let net48 = TargetFramework::from_str("net48").unwrap();
+        assert!(net48.is_net_framework());
+        assert!(!net48.is_modern_net());
+    }
+
+    #[test]
+    fn test_serde_round_trips_through_display_format() {
+        let tfm = TargetFramework::from_str(" net8.0 ").unwrap();
+        let json = serde_json::to_string(&tfm).unwrap();
+        assert_eq!(json, "\"net8.0\"");
+
+        let round_tripped: TargetFramework = serde_json::from_str(&json).unwrap();
+        assert_eq!(round_tripped, tfm);
+    }
+
+    #[test]
+    fn test_serde_rejects_invalid_tfm() {
+        let result: Result<TargetFramework, _> = serde_json::from_str("\"invalid\"");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_try_from_string_and_str() {
+        let from_string = TargetFramework::try_from("net8.0".to_string()).unwrap();
+        assert_eq!(from_string.as_str(), "net8.0");
+
+        let from_str: TargetFramework = "net6.0".try_into().unwrap();
+        assert_eq!(from_str.as_str(), "net6.0");
+
+        assert!(TargetFramework::try_from("invalid".to_string()).is_err());
+    }
+
     #[test]
     fn test_normalize_whitespace_handling() {
         // Test whitespace handling
@@ -981,11 +2098,436 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_ordering_modern_net_numeric_not_lexicographic() {
+        let net9 = TargetFramework::from_str("net9.0").unwrap();
+        let net10 = TargetFramework::from_str("net10.0").unwrap();
+        assert!(net9 < net10);
+    }
+
+    #[test]
+    fn test_ordering_old_net_framework_numeric() {
+        let net472 = TargetFramework::from_str("net472").unwrap();
+        let net48 = TargetFramework::from_str("net48").unwrap();
+        assert!(net472 < net48);
+    }
+
+    #[test]
+    fn test_ordering_family_rank() {
+        let net_framework = TargetFramework::from_str("net48").unwrap();
+        let netstandard = TargetFramework::from_str("netstandard2.0").unwrap();
+        let netcoreapp = TargetFramework::from_str("netcoreapp3.1").unwrap();
+        let modern = TargetFramework::from_str("net5.0").unwrap();
+
+        let mut frameworks = vec![
+            modern.clone(),
+            netcoreapp.clone(),
+            netstandard.clone(),
+            net_framework.clone(),
+        ];
+        frameworks.sort();
+        assert_eq!(frameworks, vec![net_framework, netstandard, netcoreapp, modern]);
+    }
+
+    /// Test fixture for a scratch `shared/<framework>/<version>` layout.
+    struct TestSharedFrameworkDir {
+        path: PathBuf,
+    }
+
+    impl TestSharedFrameworkDir {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir()
+                .join("target_framework_shared_tests")
+                .join(name);
+            let _ = std::fs::remove_dir_all(&path);
+            std::fs::create_dir_all(&path).unwrap();
+            Self { path }
+        }
+
+        fn create_version(&self, framework_name: &str, version: &str, with_deps_json: bool) {
+            let dir = self.path.join("shared").join(framework_name).join(version);
+            std::fs::create_dir_all(&dir).unwrap();
+            if with_deps_json {
+                std::fs::write(
+                    dir.join(format!("{}.deps.json", framework_name)),
+                    "{}",
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    impl Drop for TestSharedFrameworkDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.path);
+        }
+    }
+
+    #[test]
+    fn test_resolve_framework_dir_picks_lowest_compatible_minor() {
+        let dir = TestSharedFrameworkDir::new("lowest_minor");
+        dir.create_version("Microsoft.NETCore.App", "8.1.0", true);
+        dir.create_version("Microsoft.NETCore.App", "8.2.0", true);
+
+        let tfm = TargetFramework::from_str("net8.0").unwrap();
+        let resolved = TargetFrameworkHelper::resolve_framework_dir(
+            &dir.path,
+            "Microsoft.NETCore.App",
+            &tfm,
+            FrameworkRollForwardPolicy::Minor,
+        )
+        .unwrap();
+
+        assert_eq!(resolved, dir.path.join("shared/Microsoft.NETCore.App/8.1.0"));
+    }
+
+    #[test]
+    fn test_resolve_framework_dir_skips_version_missing_deps_json() {
+        let dir = TestSharedFrameworkDir::new("missing_deps_json");
+        dir.create_version("Microsoft.NETCore.App", "8.1.0", false);
+        dir.create_version("Microsoft.NETCore.App", "8.2.0", true);
+
+        let tfm = TargetFramework::from_str("net8.0").unwrap();
+        let resolved = TargetFrameworkHelper::resolve_framework_dir(
+            &dir.path,
+            "Microsoft.NETCore.App",
+            &tfm,
+            FrameworkRollForwardPolicy::Minor,
+        )
+        .unwrap();
+
+        assert_eq!(resolved, dir.path.join("shared/Microsoft.NETCore.App/8.2.0"));
+    }
+
+    #[test]
+    fn test_resolve_framework_dir_latest_minor_picks_highest() {
+        let dir = TestSharedFrameworkDir::new("latest_minor");
+        dir.create_version("Microsoft.NETCore.App", "8.1.0", true);
+        dir.create_version("Microsoft.NETCore.App", "8.2.0", true);
+
+        let tfm = TargetFramework::from_str("net8.0").unwrap();
+        let resolved = TargetFrameworkHelper::resolve_framework_dir(
+            &dir.path,
+            "Microsoft.NETCore.App",
+            &tfm,
+            FrameworkRollForwardPolicy::LatestMinor,
+        )
+        .unwrap();
+
+        assert_eq!(resolved, dir.path.join("shared/Microsoft.NETCore.App/8.2.0"));
+    }
+
+    #[test]
+    fn test_resolve_framework_dir_errors_without_any_candidate() {
+        let dir = TestSharedFrameworkDir::new("no_candidates");
+        let tfm = TargetFramework::from_str("net8.0").unwrap();
+
+        let result = TargetFrameworkHelper::resolve_framework_dir(
+            &dir.path,
+            "Microsoft.NETCore.App",
+            &tfm,
+            FrameworkRollForwardPolicy::Minor,
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_installed_environment_parse_list_line() {
+        assert_eq!(
+            InstalledEnvironment::parse_list_line("8.0.100 [/usr/share/dotnet/sdk]"),
+            Some((
+                "8.0.100".to_string(),
+                PathBuf::from("/usr/share/dotnet/sdk")
+            ))
+        );
+        assert_eq!(InstalledEnvironment::parse_list_line("garbage"), None);
+    }
+
+    #[test]
+    fn test_resolve_sdk_request_uses_global_json_pin_when_present() {
+        let dir = TestProjectDir::new("sdk_request_pin");
+        dir.write(
+            "global.json",
+            r#"{"sdk": {"version": "8.0.404"}}"#,
+        );
+
+        let tfm = TargetFramework::from_str("net8.0").unwrap();
+        let request = tfm.resolve_sdk_request(Some(&dir.path)).unwrap();
+
+        assert_eq!(request, SdkRequest::ExactVersion("8.0.404".to_string()));
+    }
+
+    #[test]
+    fn test_resolve_sdk_request_falls_back_to_channel_without_global_json() {
+        let dir = TestProjectDir::new("sdk_request_no_pin");
+
+        let tfm = TargetFramework::from_str("net8.0").unwrap();
+        let request = tfm.resolve_sdk_request(Some(&dir.path)).unwrap();
+
+        assert_eq!(request, SdkRequest::Channel("8.0".to_string()));
+    }
+
+    #[test]
+    fn test_installed_environment_root_satisfying_exact_version() {
+        let env = InstalledEnvironment {
+            sdks: vec![SdkInfo {
+                version: "8.0.404".to_string(),
+                path: PathBuf::from("/usr/share/dotnet/sdk"),
+            }],
+            frameworks: vec![],
+        };
+
+        assert_eq!(
+            env.root_satisfying(&SdkRequest::ExactVersion("8.0.404".to_string())),
+            Some(PathBuf::from("/usr/share/dotnet"))
+        );
+        assert_eq!(
+            env.root_satisfying(&SdkRequest::ExactVersion("8.0.405".to_string())),
+            None
+        );
+    }
+
+    #[test]
+    fn test_installed_environment_root_satisfying_channel() {
+        let env = InstalledEnvironment {
+            sdks: vec![SdkInfo {
+                version: "8.0.100".to_string(),
+                path: PathBuf::from("/usr/share/dotnet/sdk"),
+            }],
+            frameworks: vec![],
+        };
+
+        assert_eq!(
+            env.root_satisfying_channel("8.0"),
+            Some(PathBuf::from("/usr/share/dotnet"))
+        );
+        assert_eq!(env.root_satisfying_channel("9.0"), None);
+    }
+
     #[test]
     fn test_tfm_to_channel_invalid() {
         // Invalid TFMs should error - but we can't create invalid TFMs via from_str
         // So this tests the internal logic
-        let invalid_tfm = TargetFramework("invalid".to_string());
+        let invalid_tfm = TargetFramework::from_base("invalid".to_string());
         assert!(invalid_tfm.to_channel().is_err());
     }
+
+    #[test]
+    fn test_all_from_csproj_reads_target_frameworks_plural() {
+        let dir = TestProjectDir::new("all_from_csproj_plural");
+        let csproj = dir.write(
+            "App.csproj",
+            r#"<Project Sdk="Microsoft.NET.Sdk">
+                <PropertyGroup>
+                    <TargetFrameworks>net8.0;net6.0;netstandard2.0</TargetFrameworks>
+                </PropertyGroup>
+            </Project>"#,
+        );
+
+        let frameworks = TargetFramework::all_from_csproj(&csproj).unwrap();
+        let as_strs: Vec<&str> = frameworks.iter().map(|f| f.as_str()).collect();
+        assert_eq!(as_strs, vec!["net8.0", "net6.0", "netstandard2.0"]);
+    }
+
+    #[test]
+    fn test_from_csproj_inherits_from_directory_build_targets() {
+        let dir = TestProjectDir::new("from_csproj_inherits_targets");
+        dir.write(
+            "Directory.Build.targets",
+            r#"<Project>
+                <PropertyGroup>
+                    <TargetFramework>net6.0</TargetFramework>
+                </PropertyGroup>
+            </Project>"#,
+        );
+        let csproj = dir.write(
+            "src/App.csproj",
+            r#"<Project Sdk="Microsoft.NET.Sdk">
+                <PropertyGroup>
+                    <Nullable>enable</Nullable>
+                </PropertyGroup>
+            </Project>"#,
+        );
+
+        let framework = TargetFramework::from_csproj(&csproj).unwrap();
+        assert_eq!(framework.as_str(), "net6.0");
+    }
+
+    #[test]
+    fn test_from_csproj_returns_earliest_of_multi_targeted_set() {
+        let dir = TestProjectDir::new("from_csproj_earliest");
+        let csproj = dir.write(
+            "App.csproj",
+            r#"<Project Sdk="Microsoft.NET.Sdk">
+                <PropertyGroup>
+                    <TargetFrameworks>net8.0;net6.0;netstandard2.0</TargetFrameworks>
+                </PropertyGroup>
+            </Project>"#,
+        );
+
+        let earliest = TargetFramework::from_csproj(&csproj).unwrap();
+        assert_eq!(earliest.as_str(), "netstandard2.0");
+    }
+
+    /// Test fixture for a scratch directory containing project/props files
+    struct TestProjectDir {
+        path: PathBuf,
+    }
+
+    impl TestProjectDir {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir()
+                .join("target_framework_tests")
+                .join(name);
+            let _ = std::fs::remove_dir_all(&path);
+            std::fs::create_dir_all(&path).unwrap();
+            Self { path }
+        }
+
+        fn write(&self, relative: &str, contents: &str) -> PathBuf {
+            let full_path = self.path.join(relative);
+            if let Some(parent) = full_path.parent() {
+                std::fs::create_dir_all(parent).unwrap();
+            }
+            std::fs::write(&full_path, contents).unwrap();
+            full_path
+        }
+    }
+
+    impl Drop for TestProjectDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.path);
+        }
+    }
+
+    #[test]
+    fn test_detect_from_project_reads_own_target_framework() {
+        let dir = TestProjectDir::new("own_tfm");
+        let csproj = dir.write(
+            "App.csproj",
+            r#"<Project Sdk="Microsoft.NET.Sdk">
+                <PropertyGroup>
+                    <TargetFramework>net8.0</TargetFramework>
+                </PropertyGroup>
+            </Project>"#,
+        );
+
+        let frameworks = TargetFramework::detect_from_project(&csproj).unwrap();
+        assert_eq!(frameworks.len(), 1);
+        assert_eq!(frameworks[0].as_str(), "net8.0");
+    }
+
+    #[test]
+    fn test_detect_from_project_reads_target_frameworks_plural() {
+        let dir = TestProjectDir::new("plural_tfm");
+        let csproj = dir.write(
+            "App.csproj",
+            r#"<Project Sdk="Microsoft.NET.Sdk">
+                <PropertyGroup>
+                    <TargetFrameworks>net8.0;net6.0;netstandard2.0</TargetFrameworks>
+                </PropertyGroup>
+            </Project>"#,
+        );
+
+        let frameworks = TargetFramework::detect_from_project(&csproj).unwrap();
+        let as_strs: Vec<&str> = frameworks.iter().map(|f| f.as_str()).collect();
+        assert_eq!(as_strs, vec!["net8.0", "net6.0", "netstandard2.0"]);
+    }
+
+    #[test]
+    fn test_detect_from_project_inherits_from_directory_build_props() {
+        let dir = TestProjectDir::new("inherited_tfm");
+        dir.write(
+            "Directory.Build.props",
+            r#"<Project>
+                <PropertyGroup>
+                    <TargetFramework>net6.0</TargetFramework>
+                </PropertyGroup>
+            </Project>"#,
+        );
+        let csproj = dir.write(
+            "src/App.csproj",
+            r#"<Project Sdk="Microsoft.NET.Sdk">
+                <PropertyGroup>
+                    <Nullable>enable</Nullable>
+                </PropertyGroup>
+            </Project>"#,
+        );
+
+        let frameworks = TargetFramework::detect_from_project(&csproj).unwrap();
+        assert_eq!(frameworks.len(), 1);
+        assert_eq!(frameworks[0].as_str(), "net6.0");
+    }
+
+    #[test]
+    fn test_detect_from_project_own_tfm_overrides_inherited() {
+        let dir = TestProjectDir::new("override_tfm");
+        dir.write(
+            "Directory.Build.props",
+            r#"<Project>
+                <PropertyGroup>
+                    <TargetFramework>net6.0</TargetFramework>
+                </PropertyGroup>
+            </Project>"#,
+        );
+        let csproj = dir.write(
+            "src/App.csproj",
+            r#"<Project Sdk="Microsoft.NET.Sdk">
+                <PropertyGroup>
+                    <TargetFramework>net8.0</TargetFramework>
+                </PropertyGroup>
+            </Project>"#,
+        );
+
+        let frameworks = TargetFramework::detect_from_project(&csproj).unwrap();
+        assert_eq!(frameworks[0].as_str(), "net8.0");
+    }
+
+    #[test]
+    fn test_detect_from_project_errors_without_any_tfm() {
+        let dir = TestProjectDir::new("no_tfm");
+        let csproj = dir.write(
+            "App.csproj",
+            r#"<Project Sdk="Microsoft.NET.Sdk">
+                <PropertyGroup>
+                    <Nullable>enable</Nullable>
+                </PropertyGroup>
+            </Project>"#,
+        );
+
+        assert!(TargetFramework::detect_from_project(&csproj).is_err());
+    }
+
+    #[test]
+    fn test_detect_from_directory_finds_csproj_and_fsproj() {
+        let dir = TestProjectDir::new("mixed_project_types");
+        dir.write(
+            "CSharpApp.csproj",
+            r#"<Project Sdk="Microsoft.NET.Sdk">
+                <PropertyGroup>
+                    <TargetFramework>net8.0</TargetFramework>
+                </PropertyGroup>
+            </Project>"#,
+        );
+        dir.write(
+            "FSharpApp.fsproj",
+            r#"<Project Sdk="Microsoft.NET.Sdk">
+                <PropertyGroup>
+                    <TargetFramework>net6.0</TargetFramework>
+                </PropertyGroup>
+            </Project>"#,
+        );
+
+        let frameworks = TargetFrameworkHelper::detect_from_directory(&dir.path).unwrap();
+        let as_strs: Vec<&str> = frameworks.iter().map(|f| f.as_str()).collect();
+        assert!(as_strs.contains(&"net8.0"));
+        assert!(as_strs.contains(&"net6.0"));
+    }
+
+    #[test]
+    fn test_detect_from_directory_errors_with_no_project_files() {
+        let dir = TestProjectDir::new("empty_dir");
+        assert!(TargetFrameworkHelper::detect_from_directory(&dir.path).is_err());
+    }
 }
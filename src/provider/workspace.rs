@@ -0,0 +1,369 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Error};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde::Deserialize;
+use tokio::fs;
+use tracing::debug;
+
+use crate::provider::dependency_graph::{compare_versions, find_csproj_files, parse_direct_references};
+use crate::provider::dependency_resolution::{Dependencies, REFERNCE_ASSEMBLIES_NAME};
+use crate::provider::target_framework::TargetFramework;
+use crate::provider::Project;
+
+/// Which dependency manifest format a project uses. Each variant knows how
+/// to discover its own manifest, enumerate the project's [`Dependencies`],
+/// and locate the matching reference assemblies, so the rest of the
+/// indexing pipeline (`load_sdk_xml_files_to_database`, the per-dependency
+/// stitch loop) can work the same way regardless of manifest format --
+/// mirroring how rust-analyzer's `project_model` exposes a single
+/// `ProjectWorkspace` over both `cargo metadata` and `rust-project.json`.
+pub(crate) enum ProjectWorkspace {
+    /// `paket.dependencies`, resolved via the `paket` CLI. The only format
+    /// this provider has ever driven end to end.
+    Paket { manifest: PathBuf },
+    /// No Paket/NuGet lockfile, just SDK-style `<PackageReference>` entries
+    /// declared directly in a `.csproj`.
+    PackageReference { csproj: PathBuf },
+    /// The older, `packages.config`-based NuGet manifest.
+    PackagesConfig { manifest: PathBuf },
+    /// NuGet restore's own resolved dependency graph, written to
+    /// `obj/project.assets.json`.
+    AssetsJson { manifest: PathBuf },
+    /// A hand-written [`MANUAL_DESCRIPTOR_FILE_NAME`] descriptor, for
+    /// projects that don't use Paket or where automated resolution is
+    /// undesirable -- analogous to rust-analyzer's `rust-project.json`.
+    Manual { manifest: PathBuf },
+}
+
+/// The manual project descriptor file name [`ProjectWorkspace::discover`]
+/// looks for, taking precedence over every auto-detected manifest since a
+/// user who wrote one wants deterministic control over what gets indexed.
+const MANUAL_DESCRIPTOR_FILE_NAME: &str = "konveyor-project.json";
+
+/// One dependency entry in a [`MANUAL_DESCRIPTOR_FILE_NAME`] descriptor.
+#[derive(Debug, Deserialize)]
+struct ManualDependencyDescriptor {
+    name: String,
+    version: String,
+    location: PathBuf,
+}
+
+/// The shape of a [`MANUAL_DESCRIPTOR_FILE_NAME`] descriptor: every
+/// dependency the project needs indexed, the target framework they were
+/// resolved against, and where the reference assemblies for that
+/// framework live.
+#[derive(Debug, Deserialize)]
+struct ManualProjectDescriptor {
+    target_framework: String,
+    reference_assemblies: PathBuf,
+    dependencies: Vec<ManualDependencyDescriptor>,
+}
+
+/// A dependency manifest format that knows how to enumerate a project's
+/// dependencies and locate its reference assemblies. Implemented by every
+/// [`ProjectWorkspace`] variant so `Project::resolve` doesn't need to know
+/// which manifest format it's talking to.
+#[tonic::async_trait]
+pub(crate) trait DependencyManifest {
+    /// Enumerates `project`'s dependencies, returning the reference
+    /// assembly path, the resolved target framework, and the dependencies
+    /// themselves -- the same shape [`Project::read_packet_dependency_file`]
+    /// has always returned.
+    async fn dependencies(
+        &self,
+        project: &Project,
+    ) -> Result<(PathBuf, String, Vec<Dependencies>), Error>;
+}
+
+impl ProjectWorkspace {
+    /// Probes `project_dir` for a manifest. A hand-written
+    /// [`MANUAL_DESCRIPTOR_FILE_NAME`] always wins, since a user who wrote
+    /// one wants deterministic control over what gets indexed; otherwise
+    /// this prefers `paket.dependencies` (the only auto-detected format
+    /// this provider fully drives, including running `paket` itself) and
+    /// falls back through the NuGet-native formats in the order a `dotnet
+    /// restore` would produce/consume them: the fully-resolved
+    /// `project.assets.json` if restore has already run, the legacy
+    /// `packages.config`, and finally SDK-style `<PackageReference>`
+    /// entries read straight out of the `.csproj`.
+    pub(crate) fn discover(project_dir: &Path) -> Option<ProjectWorkspace> {
+        let manual_manifest = project_dir.join(MANUAL_DESCRIPTOR_FILE_NAME);
+        if manual_manifest.exists() {
+            return Some(ProjectWorkspace::Manual {
+                manifest: manual_manifest,
+            });
+        }
+
+        let paket_manifest = project_dir.join("paket.dependencies");
+        if paket_manifest.exists() {
+            return Some(ProjectWorkspace::Paket {
+                manifest: paket_manifest,
+            });
+        }
+
+        let assets_manifest = project_dir.join("obj").join("project.assets.json");
+        if assets_manifest.exists() {
+            return Some(ProjectWorkspace::AssetsJson {
+                manifest: assets_manifest,
+            });
+        }
+
+        let packages_config = project_dir.join("packages.config");
+        if packages_config.exists() {
+            return Some(ProjectWorkspace::PackagesConfig {
+                manifest: packages_config,
+            });
+        }
+
+        find_csproj_files(project_dir)
+            .into_iter()
+            .next()
+            .map(|csproj| ProjectWorkspace::PackageReference { csproj })
+    }
+}
+
+#[tonic::async_trait]
+impl DependencyManifest for ProjectWorkspace {
+    async fn dependencies(
+        &self,
+        project: &Project,
+    ) -> Result<(PathBuf, String, Vec<Dependencies>), Error> {
+        match self {
+            ProjectWorkspace::Paket { manifest } => {
+                project.read_packet_dependency_file(manifest).await
+            }
+            ProjectWorkspace::PackageReference { csproj } => {
+                let target_frameworks = TargetFramework::detect_from_project(csproj)?;
+                let name_versions = parse_direct_references(csproj)?
+                    .into_iter()
+                    .filter(|dep| !dep.version.is_empty())
+                    .map(|dep| (dep.name, dep.version))
+                    .collect();
+                nuget_native_dependencies(name_versions, target_frameworks).await
+            }
+            ProjectWorkspace::PackagesConfig { manifest } => {
+                let (name_versions, target_frameworks) = parse_packages_config(manifest).await?;
+                nuget_native_dependencies(name_versions, target_frameworks).await
+            }
+            ProjectWorkspace::AssetsJson { manifest } => {
+                let (name_versions, target_frameworks) = parse_assets_json(manifest).await?;
+                nuget_native_dependencies(name_versions, target_frameworks).await
+            }
+            ProjectWorkspace::Manual { manifest } => parse_manual_descriptor(manifest).await,
+        }
+    }
+}
+
+/// Parses a [`MANUAL_DESCRIPTOR_FILE_NAME`] descriptor straight into the
+/// same `(reference_assembly_path, target_framework, Vec<Dependencies>)`
+/// shape every other [`ProjectWorkspace`] variant produces, so
+/// `load_sdk_xml_files_to_database` and the stitching loop run unchanged
+/// regardless of where the dependency list came from.
+async fn parse_manual_descriptor(
+    manifest: &Path,
+) -> Result<(PathBuf, String, Vec<Dependencies>), Error> {
+    let content = fs::read_to_string(manifest)
+        .await
+        .map_err(|e| anyhow!("failed to read {:?}: {}", manifest, e))?;
+    let descriptor: ManualProjectDescriptor = serde_json::from_str(&content)
+        .map_err(|e| anyhow!("failed to parse {:?}: {}", manifest, e))?;
+    let target_framework = TargetFramework::try_from(descriptor.target_framework.as_str())?;
+    let highest_restriction = target_framework.to_string();
+
+    let deps = descriptor
+        .dependencies
+        .into_iter()
+        .map(|dep| Dependencies {
+            location: dep.location,
+            name: dep.name,
+            version: dep.version,
+            highest_restriction: highest_restriction.clone(),
+            decompiled_size: Mutex::new(None),
+            decompiled_location: Arc::new(Mutex::new(std::collections::HashSet::new())),
+        })
+        .collect();
+
+    Ok((descriptor.reference_assemblies, highest_restriction, deps))
+}
+
+/// Shared by every NuGet-native manifest format (everything except
+/// Paket): resolves a single target framework out of whichever the
+/// manifest reported, builds a [`Dependencies`] per `(name, version)`
+/// located in the local NuGet global packages folder, and locates the
+/// reference assemblies in that same cache.
+async fn nuget_native_dependencies(
+    name_versions: Vec<(String, String)>,
+    target_frameworks: Vec<TargetFramework>,
+) -> Result<(PathBuf, String, Vec<Dependencies>), Error> {
+    if name_versions.is_empty() {
+        return Ok((PathBuf::new(), String::new(), Vec::new()));
+    }
+    let target_framework = target_frameworks
+        .into_iter()
+        .max()
+        .ok_or_else(|| anyhow!("unable to determine a target framework for this project"))?;
+
+    let packages_dir = nuget_global_packages_dir();
+    let deps = name_versions
+        .into_iter()
+        .map(|(name, version)| Dependencies {
+            location: packages_dir.join(name.to_lowercase()).join(&version),
+            name,
+            version,
+            highest_restriction: target_framework.to_string(),
+            decompiled_size: Mutex::new(None),
+            decompiled_location: Arc::new(Mutex::new(std::collections::HashSet::new())),
+        })
+        .collect();
+
+    let reference_assembly_path = locate_reference_assemblies(&target_framework).await?;
+    Ok((reference_assembly_path, target_framework.to_string(), deps))
+}
+
+/// Where `dotnet`/NuGet extracts restored packages on this machine,
+/// honoring the `NUGET_PACKAGES` override NuGet itself respects before
+/// falling back to the per-user default cache location.
+fn nuget_global_packages_dir() -> PathBuf {
+    if let Ok(dir) = std::env::var("NUGET_PACKAGES") {
+        return PathBuf::from(dir);
+    }
+    #[cfg(target_os = "windows")]
+    {
+        if let Ok(profile) = std::env::var("USERPROFILE") {
+            return PathBuf::from(profile).join(".nuget").join("packages");
+        }
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        if let Ok(home) = std::env::var("HOME") {
+            return PathBuf::from(home).join(".nuget").join("packages");
+        }
+    }
+    PathBuf::from(".nuget").join("packages")
+}
+
+/// Finds the reference assemblies for `target_framework` in the NuGet
+/// global packages folder, under whichever version of
+/// `Microsoft.NETFramework.ReferenceAssemblies.<tfm>` restore installed.
+async fn locate_reference_assemblies(target_framework: &TargetFramework) -> Result<PathBuf, Error> {
+    let package_dir = nuget_global_packages_dir().join(
+        format!("{}.{}", REFERNCE_ASSEMBLIES_NAME, target_framework).to_lowercase(),
+    );
+    let mut entries = fs::read_dir(&package_dir)
+        .await
+        .map_err(|e| anyhow!("reference assemblies not found in NuGet cache at {:?}: {}", package_dir, e))?;
+    let mut versions = Vec::new();
+    while let Some(entry) = entries.next_entry().await? {
+        if let Some(name) = entry.file_name().to_str() {
+            versions.push(name.to_string());
+        }
+    }
+    versions.sort_by(|a, b| compare_versions(a, b));
+    let version = versions.pop().ok_or_else(|| {
+        anyhow!(
+            "no installed version of the reference assemblies found under {:?}",
+            package_dir
+        )
+    })?;
+    Ok(package_dir.join(version))
+}
+
+/// Parses a `packages.config` manifest's `<package id="..." version="..."
+/// targetFramework="..." />` entries into `(name, version)` pairs, plus
+/// the target framework(s) declared across them.
+async fn parse_packages_config(
+    manifest: &Path,
+) -> Result<(Vec<(String, String)>, Vec<TargetFramework>), Error> {
+    let content = fs::read_to_string(manifest)
+        .await
+        .map_err(|e| anyhow!("failed to read packages.config {:?}: {}", manifest, e))?;
+    let mut reader = Reader::from_str(&content);
+    reader.config_mut().trim_text(true);
+
+    let mut name_versions = Vec::new();
+    let mut target_frameworks = Vec::new();
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) if e.name().as_ref() == b"package" => {
+                let mut id = None;
+                let mut version = None;
+                let mut target_framework = None;
+                for attr in e.attributes().flatten() {
+                    match attr.key.as_ref() {
+                        b"id" => id = Some(String::from_utf8_lossy(&attr.value).trim().to_string()),
+                        b"version" => {
+                            version = Some(String::from_utf8_lossy(&attr.value).trim().to_string())
+                        }
+                        b"targetFramework" => {
+                            target_framework =
+                                Some(String::from_utf8_lossy(&attr.value).trim().to_string())
+                        }
+                        _ => {}
+                    }
+                }
+                if let (Some(id), Some(version)) = (id, version) {
+                    name_versions.push((id, version));
+                }
+                if let Some(target_framework) = target_framework {
+                    if let Ok(tfm) = TargetFramework::try_from(target_framework.as_str()) {
+                        target_frameworks.push(tfm);
+                    }
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(anyhow!(
+                    "XML parsing error in {:?} at position {}: {}",
+                    manifest,
+                    reader.buffer_position(),
+                    e
+                ));
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok((name_versions, target_frameworks))
+}
+
+/// Parses a NuGet restore's `project.assets.json`: the `(name, version)`
+/// pairs resolved for its first (non-RID-qualified) target, plus the
+/// target framework that target was resolved for.
+async fn parse_assets_json(
+    manifest: &Path,
+) -> Result<(Vec<(String, String)>, Vec<TargetFramework>), Error> {
+    let content = fs::read_to_string(manifest)
+        .await
+        .map_err(|e| anyhow!("failed to read project.assets.json {:?}: {}", manifest, e))?;
+    let json: serde_json::Value = serde_json::from_str(&content)?;
+
+    let targets = json
+        .get("targets")
+        .and_then(|t| t.as_object())
+        .ok_or_else(|| anyhow!("{:?} has no \"targets\" section", manifest))?;
+    let (target_name, packages) = targets
+        .iter()
+        .next()
+        .ok_or_else(|| anyhow!("{:?} has no resolved targets", manifest))?;
+    let tfm_text = target_name.split('/').next().unwrap_or(target_name);
+    debug!("resolving project.assets.json target {:?}", tfm_text);
+    let target_framework = TargetFramework::try_from(tfm_text)?;
+
+    let name_versions = packages
+        .as_object()
+        .map(|packages| {
+            packages
+                .keys()
+                .filter_map(|key| key.split_once('/'))
+                .map(|(name, version)| (name.to_string(), version.to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok((name_versions, vec![target_framework]))
+}